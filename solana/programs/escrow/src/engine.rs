@@ -0,0 +1,418 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{EscrowError, Move, MoveAction};
+
+/// Cells per side of the board, matching `game/board.ts`
+pub const GRID_SIZE: u8 = 10;
+/// Bombs hidden on every board, matching `game/board.ts`
+pub const BOMB_COUNT: usize = 25;
+
+/// Total cells on the board
+const CELLS: usize = GRID_SIZE as usize * GRID_SIZE as usize;
+/// Bitmask with one bit set per cell
+const ALL_CELLS: u128 = (1u128 << CELLS) - 1;
+
+/// Mulberry32 PRNG, bit-for-bit with the backend's generator
+struct Mulberry32(u32);
+
+impl Mulberry32 {
+    fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_add(0x6D2B_79F5);
+        let mut t = self.0;
+        t = (t ^ (t >> 15)).wrapping_mul(t | 1);
+        t ^= t.wrapping_add((t ^ (t >> 7)).wrapping_mul(t | 61));
+        t ^ (t >> 14)
+    }
+}
+
+/// A minesweeper board as bitmaps, one bit per cell (`y * GRID_SIZE + x`)
+pub struct Board {
+    bombs: u128,
+    revealed: u128,
+}
+
+impl Board {
+    /// Regenerate the board the backend derives from `seed`
+    /// Mirrors `generateBombPositions`: a Fisher-Yates shuffle of the cell
+    /// indices driven by Mulberry32 seeded with the first 4 seed bytes
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut rng = Mulberry32(u32::from_be_bytes([seed[0], seed[1], seed[2], seed[3]]));
+        let mut indices = [0u8; CELLS];
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = i as u8;
+        }
+        
+        for i in (1..CELLS).rev() {
+            // floor(rng() * (i + 1)) with rng() = next / 2^32, done exactly
+            let j = ((rng.next_u32() as u64 * (i as u64 + 1)) >> 32) as usize;
+            indices.swap(i, j);
+        }
+        
+        let bombs = indices[..BOMB_COUNT]
+            .iter()
+            .fold(0u128, |bombs, &cell| bombs | 1u128 << cell);
+        Self { bombs, revealed: 0 }
+    }
+    
+    /// The board for `seed` with the cells in `revealed` already open
+    pub fn restore(seed: &[u8; 32], revealed: u128) -> Self {
+        Self {
+            revealed,
+            ..Self::from_seed(seed)
+        }
+    }
+    
+    /// Bitmap of the revealed cells
+    pub fn revealed(&self) -> u128 {
+        self.revealed
+    }
+    
+    /// Cell index for board coordinates
+    pub fn cell(x: u8, y: u8) -> Result<usize> {
+        require!(x < GRID_SIZE && y < GRID_SIZE, EscrowError::InvalidMoveLog);
+        Ok(y as usize * GRID_SIZE as usize + x as usize)
+    }
+    
+    pub fn is_bomb(&self, cell: usize) -> bool {
+        self.bombs & 1u128 << cell != 0
+    }
+    
+    pub fn is_revealed(&self, cell: usize) -> bool {
+        self.revealed & 1u128 << cell != 0
+    }
+    
+    /// Every safe cell has been revealed
+    pub fn is_cleared(&self) -> bool {
+        self.revealed | self.bombs == ALL_CELLS
+    }
+    
+    /// Lowest-index safe cell still hidden, used to auto-play a seat
+    /// that missed the round deadline
+    pub fn first_hidden_safe_cell(&self) -> Option<usize> {
+        let hidden_safe = !(self.revealed | self.bombs) & ALL_CELLS;
+        (hidden_safe != 0).then(|| hidden_safe.trailing_zeros() as usize)
+    }
+    
+    /// Bitmask of the (up to 8) cells around `cell`
+    fn neighbours(cell: usize) -> u128 {
+        let size = GRID_SIZE as i16;
+        let (x, y) = (cell as i16 % size, cell as i16 / size);
+        let mut mask = 0u128;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if (dx != 0 || dy != 0) && (0..size).contains(&nx) && (0..size).contains(&ny) {
+                    mask |= 1u128 << (ny * size + nx);
+                }
+            }
+        }
+        mask
+    }
+    
+    /// Reveal `cell`, cascading through cells with no adjacent bombs
+    /// Returns whether the cell was a bomb
+    pub fn reveal(&mut self, cell: usize) -> bool {
+        if self.is_bomb(cell) {
+            self.revealed |= 1u128 << cell;
+            return true;
+        }
+        
+        let mut pending = 1u128 << cell;
+        while pending != 0 {
+            let next = pending.trailing_zeros() as usize;
+            pending &= !(1u128 << next);
+            if self.is_revealed(next) {
+                continue;
+            }
+            
+            self.revealed |= 1u128 << next;
+            let around = Self::neighbours(next);
+            if around & self.bombs == 0 {
+                pending |= around & !self.revealed;
+            }
+        }
+        false
+    }
+}
+
+/// Resolve one round of clicks, given in ascending seat order, following
+/// `processRound`, and return the winning seat if the round decided the game
+/// Bomb clicks clear the seat's bit in `alive`; flags never reach here
+/// since they are private hints that cannot change the outcome
+pub fn resolve_round(
+    board: &mut Board,
+    alive: &mut u8,
+    clicks: impl Iterator<Item = (u8, usize)>,
+) -> Option<u8> {
+    let mut last_eliminated = None;
+    let mut last_clicker = None;
+    for (player, cell) in clicks {
+        last_clicker = Some(player);
+        if board.is_revealed(cell) {
+            continue;
+        }
+        if board.reveal(cell) {
+            *alive &= !(1u8 << player);
+            last_eliminated = Some(player);
+        }
+    }
+    
+    if *alive == 0 {
+        last_eliminated
+    } else if alive.count_ones() == 1 {
+        Some(alive.trailing_zeros() as u8)
+    } else if board.is_cleared() {
+        last_clicker.or(Some(alive.trailing_zeros() as u8))
+    } else {
+        None
+    }
+}
+
+/// Replay a move log against the board derived from `seed`, following
+/// the round rules of `processRound`, and return the winning seat
+///
+/// The log must hold exactly one move per living seat per round, in
+/// ascending seat order, including the moves the backend auto-played for
+/// timed-out players; it must end on the round that decided the game.
+pub fn replay(seed: &[u8; 32], player_count: u8, moves: &[Move]) -> Result<u8> {
+    replay_board(seed, player_count, moves).map(|(winner, _)| winner)
+}
+
+/// Whether `winner` won the game in `moves` by clearing the board without
+/// ever flagging a safe cell - the jackpot condition
+pub fn is_clean_sweep(
+    seed: &[u8; 32],
+    player_count: u8,
+    moves: &[Move],
+    winner: u8,
+) -> Result<bool> {
+    let (replayed, board) = replay_board(seed, player_count, moves)?;
+    let wrong_flag = moves.iter().any(|mv| {
+        mv.player == winner
+            && mv.action == MoveAction::Flag
+            && !board.is_bomb(mv.y as usize * GRID_SIZE as usize + mv.x as usize)
+    });
+    Ok(replayed == winner && board.is_cleared() && !wrong_flag)
+}
+
+/// Replay a move log as `replay` does, returning the winning seat and the
+/// final board
+fn replay_board(seed: &[u8; 32], player_count: u8, moves: &[Move]) -> Result<(u8, Board)> {
+    let mut board = Board::from_seed(seed);
+    let mut alive = ((1u16 << player_count) - 1) as u8;
+    let mut round = 1u16;
+    let mut next = 0usize;
+    
+    loop {
+        let start = next;
+        while next < moves.len() && moves[next].round == round {
+            next += 1;
+        }
+        let round_moves = &moves[start..next];
+        
+        // Validate against the board as it stood when the round opened
+        let mut moved = 0u8;
+        for mv in round_moves {
+            require!(mv.player < player_count, EscrowError::InvalidMoveLog);
+            // Seats must be alive and strictly ascending, so none repeats
+            let seat = 1u8 << mv.player;
+            require!(
+                alive & seat != 0 && moved >> mv.player == 0,
+                EscrowError::InvalidMoveLog
+            );
+            moved |= seat;
+            let cell = Board::cell(mv.x, mv.y)?;
+            require!(
+                mv.action == MoveAction::Flag || !board.is_revealed(cell),
+                EscrowError::InvalidMoveLog
+            );
+        }
+        require!(moved == alive, EscrowError::InvalidMoveLog);
+        
+        let clicks = round_moves
+            .iter()
+            .filter(|mv| mv.action == MoveAction::Click)
+            .map(|mv| (mv.player, mv.y as usize * GRID_SIZE as usize + mv.x as usize));
+        if let Some(winner) = resolve_round(&mut board, &mut alive, clicks) {
+            require!(next == moves.len(), EscrowError::InvalidMoveLog);
+            return Ok((winner, board));
+        }
+        round = round.checked_add(1).ok_or(EscrowError::InvalidMoveLog)?;
+    }
+}
+
+/// Leaf hash of a move in the move log merkle tree
+pub fn move_leaf(mv: &Move) -> [u8; 32] {
+    hashv(&[
+        &mv.round.to_le_bytes(),
+        &[mv.player, mv.action as u8, mv.x, mv.y],
+    ])
+    .to_bytes()
+}
+
+/// Merkle root the backend commits for a move log, leaves in log order
+pub fn move_root(moves: &[Move]) -> [u8; 32] {
+    merkle_root(moves.iter().map(move_leaf).collect())
+}
+
+/// Merkle root over `leaves` - pairs hash as `hashv(left, right)` and an
+/// odd node is carried up as is
+pub fn merkle_root(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    if level.is_empty() {
+        return [0u8; 32];
+    }
+    
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hashv(&[left, right]).to_bytes(),
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn seed(prefix: [u8; 4]) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        seed[..4].copy_from_slice(&prefix);
+        seed
+    }
+    
+    fn mv(round: u16, player: u8, action: MoveAction, cell: usize) -> Move {
+        Move {
+            round,
+            player,
+            action,
+            x: (cell % GRID_SIZE as usize) as u8,
+            y: (cell / GRID_SIZE as usize) as u8,
+        }
+    }
+    
+    fn bomb_cell(board: &Board) -> usize {
+        board.bombs.trailing_zeros() as usize
+    }
+    
+    /// A two-seat game both seats play out by clicking the lowest hidden
+    /// safe cell every round, so seat 1 clicks last as the board clears
+    fn sweep(seed: &[u8; 32]) -> Vec<Move> {
+        let mut board = Board::from_seed(seed);
+        let mut moves = Vec::new();
+        let mut round = 1;
+        while let Some(cell) = board.first_hidden_safe_cell() {
+            moves.push(mv(round, 0, MoveAction::Click, cell));
+            moves.push(mv(round, 1, MoveAction::Click, cell));
+            board.reveal(cell);
+            round += 1;
+        }
+        moves
+    }
+    
+    #[test]
+    fn bomb_masks_match_the_backend() {
+        // Bomb bitmaps `generateBombPositions` in `game/board.ts` places
+        // for these seed prefixes, bit `y * GRID_SIZE + x` per bomb
+        let expected: [([u8; 4], u128); 4] = [
+            ([0x00, 0x00, 0x00, 0x00], 0x900ab0600ccc480a341020010),
+            ([0x12, 0x34, 0x56, 0x78], 0x0008328814b8b1260000940b0),
+            ([0xde, 0xad, 0xbe, 0xef], 0xa16401000452644a2018c3080),
+            ([0xff, 0xff, 0xff, 0xff], 0x184a020804004a4211d20403d),
+        ];
+        for (prefix, bombs) in expected {
+            let board = Board::from_seed(&seed(prefix));
+            assert_eq!(board.bombs, bombs);
+            assert_eq!(board.bombs.count_ones() as usize, BOMB_COUNT);
+        }
+    }
+    
+    #[test]
+    fn only_the_first_four_seed_bytes_pick_the_board() {
+        let mut other = seed([0x12, 0x34, 0x56, 0x78]);
+        other[4..].copy_from_slice(&[0xab; 28]);
+        
+        assert_eq!(
+            Board::from_seed(&other).bombs,
+            Board::from_seed(&seed([0x12, 0x34, 0x56, 0x78])).bombs
+        );
+    }
+    
+    #[test]
+    fn replay_ends_when_one_seat_is_left() {
+        let seed = seed([0xde, 0xad, 0xbe, 0xef]);
+        let board = Board::from_seed(&seed);
+        let safe = board.first_hidden_safe_cell().unwrap();
+        let moves = [
+            mv(1, 0, MoveAction::Click, bomb_cell(&board)),
+            mv(1, 1, MoveAction::Click, safe),
+        ];
+        
+        assert_eq!(replay(&seed, 2, &moves).unwrap(), 1);
+    }
+    
+    #[test]
+    fn replay_rejects_a_log_that_runs_past_the_deciding_round() {
+        let seed = seed([0xde, 0xad, 0xbe, 0xef]);
+        let board = Board::from_seed(&seed);
+        let safe = board.first_hidden_safe_cell().unwrap();
+        let moves = [
+            mv(1, 0, MoveAction::Click, bomb_cell(&board)),
+            mv(1, 1, MoveAction::Click, safe),
+            mv(2, 1, MoveAction::Click, safe + 1),
+        ];
+        
+        assert!(replay(&seed, 2, &moves).is_err());
+    }
+    
+    #[test]
+    fn replay_rejects_a_round_missing_a_living_seat() {
+        let seed = seed([0x12, 0x34, 0x56, 0x78]);
+        let board = Board::from_seed(&seed);
+        let moves = [mv(1, 1, MoveAction::Click, board.first_hidden_safe_cell().unwrap())];
+        
+        assert!(replay(&seed, 2, &moves).is_err());
+    }
+    
+    #[test]
+    fn last_clicker_wins_a_cleared_board() {
+        let seed = seed([0x12, 0x34, 0x56, 0x78]);
+        let moves = sweep(&seed);
+        
+        assert_eq!(replay(&seed, 2, &moves).unwrap(), 1);
+        assert!(is_clean_sweep(&seed, 2, &moves, 1).unwrap());
+        assert!(!is_clean_sweep(&seed, 2, &moves, 0).unwrap());
+    }
+    
+    #[test]
+    fn flagging_a_safe_cell_spoils_a_clean_sweep() {
+        let seed = seed([0x12, 0x34, 0x56, 0x78]);
+        let board = Board::from_seed(&seed);
+        let mut moves = sweep(&seed);
+        
+        moves[1] = mv(1, 1, MoveAction::Flag, bomb_cell(&board));
+        assert!(is_clean_sweep(&seed, 2, &moves, 1).unwrap());
+        
+        let safe = Board::cell(moves[0].x, moves[0].y).unwrap();
+        moves[1] = mv(1, 1, MoveAction::Flag, safe);
+        assert_eq!(replay(&seed, 2, &moves).unwrap(), 1);
+        assert!(!is_clean_sweep(&seed, 2, &moves, 1).unwrap());
+    }
+    
+    #[test]
+    fn clean_sweep_needs_a_cleared_board() {
+        let seed = seed([0xde, 0xad, 0xbe, 0xef]);
+        let board = Board::from_seed(&seed);
+        let moves = [
+            mv(1, 0, MoveAction::Click, bomb_cell(&board)),
+            mv(1, 1, MoveAction::Click, board.first_hidden_safe_cell().unwrap()),
+        ];
+        
+        assert!(!is_clean_sweep(&seed, 2, &moves, 1).unwrap());
+    }
+}
@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{
+    BalanceGate, Deal, EngineState, EscrowError, Game, GameStatus, RandomnessSource, SeatHold,
+    GAME_VERSION, MAX_PLAYERS, MAX_SEAT_HOLDS,
+};
+
+/// Layout version of the game account `data` and its contents in the
+/// current layout, for `migrate_game`
+/// The fields added since the game's version are reset; `vault_balance`
+/// is booked by the caller once the legacy vault is drained
+pub fn decode_game(data: &[u8]) -> Result<(u8, Game)> {
+    require!(
+        data.len() > 8 && data[..8] == Game::DISCRIMINATOR,
+        EscrowError::InvalidGameAccount
+    );
+    // Version 0 accounts have no version byte and are exactly one
+    // byte short of a version 1 account
+    let version = if data.len() == 8 + GameV1::INIT_SPACE {
+        0
+    } else {
+        data[8]
+    };
+    require!(version < GAME_VERSION, EscrowError::GameAlreadyMigrated);
+    
+    let game = if version < 2 {
+        // 0, 1 -> 2: cut the per-seat arrays down to the seat count
+        let fields = if version == 0 { &data[8..] } else { &data[9..] };
+        GameV1::deserialize(&mut &fields[..])
+            .map_err(|_| EscrowError::InvalidGameAccount)?
+            .upgrade()
+    } else {
+        // 2 -> 3: `vault_balance`, `proposal`, `seat_holds` and
+        // `rating_band` follow the version 2 fields. The account may
+        // have slack past them, so what is read here is whatever
+        // those bytes held - all are reset
+        let mut fields = data[8..].to_vec();
+        fields.resize(fields.len() + 10 + MAX_SEAT_HOLDS * SeatHold::INIT_SPACE, 0);
+        let mut game = Game::deserialize(&mut &fields[..])
+            .map_err(|_| EscrowError::InvalidGameAccount)?;
+        game.version = GAME_VERSION;
+        game.vault_balance = 0;
+        game.proposal = None;
+        game.seat_holds = [SeatHold::default(); MAX_SEAT_HOLDS];
+        game.rating_band = None;
+        game
+    };
+    Ok((version, game))
+}
+
+/// `Game` layout of versions 0 and 1, read by `migrate_game`
+///
+/// Identical to the current layout except that every per-seat array is
+/// sized for `MAX_PLAYERS` whatever the game's seat count. Version 1
+/// accounts lead with a version byte; version 0 accounts start straight
+/// at `game_id`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct GameV1 {
+    pub game_id: [u8; 8],
+    pub label: Option<[u8; 8]>,
+    pub creator: Pubkey,
+    pub stake_amount: u64,
+    pub stake_mint: Pubkey,
+    pub usd_stake: bool,
+    pub paid: [u64; MAX_PLAYERS],
+    pub player_count: u8,
+    pub min_players: u8,
+    pub max_players: u8,
+    pub fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub bounty_bps: u16,
+    pub survival_payout: bool,
+    pub side_stake: u64,
+    pub rebuy_window: i64,
+    pub max_rebuys: u8,
+    pub payout_schedule: [u16; MAX_PLAYERS],
+    pub join_deadline: i64,
+    pub scheduled_start: Option<i64>,
+    pub players: [Pubkey; MAX_PLAYERS],
+    pub status: GameStatus,
+    pub seed: [u8; 32],
+    pub winner: Option<Pubkey>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub cancelled_at: Option<i64>,
+    pub last_transition_at: i64,
+    pub refunded: u8,
+    pub stuck_flags: u8,
+    pub draw_mask: u8,
+    pub teams: [u8; MAX_PLAYERS],
+    pub winning_team: u8,
+    pub strikes: [u8; MAX_PLAYERS],
+    pub action_nonce: u64,
+    pub tournament: Option<Pubkey>,
+    pub tournament_round: u8,
+    pub season: Option<Pubkey>,
+    pub lobby_page: Option<u16>,
+    pub allowlisted: bool,
+    pub invite_code_hash: Option<[u8; 32]>,
+    pub nft_collection: Option<Pubkey>,
+    pub balance_gate: Option<BalanceGate>,
+    pub funded_prize: u64,
+    pub sponsored_pot: u64,
+    pub rematch_of: Option<[u8; 8]>,
+    pub commit_reveal: bool,
+    pub commitments: [[u8; 32]; MAX_PLAYERS],
+    pub revealed: u8,
+    pub randomness_source: RandomnessSource,
+    pub randomness_account: Option<Pubkey>,
+    pub randomness_slot: u64,
+    pub seed_pending: bool,
+    pub seed_commitment: Option<[u8; 32]>,
+    pub engine: Option<EngineState>,
+    pub move_root: Option<[u8; 32]>,
+    pub proposed_winner: Option<Pubkey>,
+    pub dispute_deadline: Option<i64>,
+    pub unclaimed: u64,
+    pub house_fee: u64,
+    pub rakeback_accrued: u8,
+    pub jackpot_claimed: bool,
+    pub eliminated: u8,
+    pub eliminated_at: [i64; MAX_PLAYERS],
+    pub elimination_order: [u8; MAX_PLAYERS],
+    pub bounties: [u64; MAX_PLAYERS],
+    pub insured: u8,
+    pub insurance_claimed: u8,
+    pub deal: Option<Deal>,
+    pub side_pot: u8,
+    pub side_pot_claimed: u8,
+    pub rebuys: [u8; MAX_PLAYERS],
+    pub rebuy_stakes: u64,
+    pub powerup_spent: [u64; MAX_PLAYERS],
+    pub powerup_pot: u64,
+    pub tips: [u64; MAX_PLAYERS],
+    pub vesting: [u64; MAX_PLAYERS],
+    pub vested_claimed: [u64; MAX_PLAYERS],
+    pub stake_sponsors: [Pubkey; MAX_PLAYERS],
+    pub payout_wallets: [Pubkey; MAX_PLAYERS],
+    pub winnings: [u64; MAX_PLAYERS],
+    pub stats_recorded: u8,
+    pub ratings_updated: bool,
+    pub vesting_period: i64,
+    pub bump: u8,
+}
+
+impl GameV1 {
+    /// The game in the current layout, its seat arrays cut down to
+    /// `max_players`
+    pub fn upgrade(self) -> Game {
+        let seats = self.max_players as usize;
+        Game {
+            version: GAME_VERSION,
+            game_id: self.game_id,
+            label: self.label,
+            creator: self.creator,
+            stake_amount: self.stake_amount,
+            stake_mint: self.stake_mint,
+            usd_stake: self.usd_stake,
+            paid: self.paid[..seats].to_vec(),
+            player_count: self.player_count,
+            min_players: self.min_players,
+            max_players: self.max_players,
+            fee_bps: self.fee_bps,
+            creator_fee_bps: self.creator_fee_bps,
+            bounty_bps: self.bounty_bps,
+            survival_payout: self.survival_payout,
+            side_stake: self.side_stake,
+            rebuy_window: self.rebuy_window,
+            max_rebuys: self.max_rebuys,
+            payout_schedule: self.payout_schedule[..seats].to_vec(),
+            join_deadline: self.join_deadline,
+            scheduled_start: self.scheduled_start,
+            players: self.players[..seats].to_vec(),
+            status: self.status,
+            seed: self.seed,
+            winner: self.winner,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            cancelled_at: self.cancelled_at,
+            last_transition_at: self.last_transition_at,
+            refunded: self.refunded,
+            stuck_flags: self.stuck_flags,
+            draw_mask: self.draw_mask,
+            teams: self.teams[..seats].to_vec(),
+            winning_team: self.winning_team,
+            strikes: self.strikes[..seats].to_vec(),
+            action_nonce: self.action_nonce,
+            tournament: self.tournament,
+            tournament_round: self.tournament_round,
+            season: self.season,
+            lobby_page: self.lobby_page,
+            allowlisted: self.allowlisted,
+            invite_code_hash: self.invite_code_hash,
+            nft_collection: self.nft_collection,
+            balance_gate: self.balance_gate,
+            funded_prize: self.funded_prize,
+            sponsored_pot: self.sponsored_pot,
+            rematch_of: self.rematch_of,
+            commit_reveal: self.commit_reveal,
+            commitments: self.commitments[..seats].to_vec(),
+            revealed: self.revealed,
+            randomness_source: self.randomness_source,
+            randomness_account: self.randomness_account,
+            randomness_slot: self.randomness_slot,
+            seed_pending: self.seed_pending,
+            seed_commitment: self.seed_commitment,
+            engine: self.engine,
+            move_root: self.move_root,
+            proposed_winner: self.proposed_winner,
+            dispute_deadline: self.dispute_deadline,
+            unclaimed: self.unclaimed,
+            house_fee: self.house_fee,
+            rakeback_accrued: self.rakeback_accrued,
+            jackpot_claimed: self.jackpot_claimed,
+            eliminated: self.eliminated,
+            eliminated_at: self.eliminated_at[..seats].to_vec(),
+            elimination_order: self.elimination_order[..seats].to_vec(),
+            bounties: self.bounties[..seats].to_vec(),
+            insured: self.insured,
+            insurance_claimed: self.insurance_claimed,
+            deal: self.deal,
+            side_pot: self.side_pot,
+            side_pot_claimed: self.side_pot_claimed,
+            rebuys: self.rebuys[..seats].to_vec(),
+            rebuy_stakes: self.rebuy_stakes,
+            powerup_spent: self.powerup_spent[..seats].to_vec(),
+            powerup_pot: self.powerup_pot,
+            tips: self.tips[..seats].to_vec(),
+            vesting: self.vesting[..seats].to_vec(),
+            vested_claimed: self.vested_claimed[..seats].to_vec(),
+            stake_sponsors: self.stake_sponsors[..seats].to_vec(),
+            payout_wallets: self.payout_wallets[..seats].to_vec(),
+            winnings: self.winnings[..seats].to_vec(),
+            stats_recorded: self.stats_recorded,
+            ratings_updated: self.ratings_updated,
+            vesting_period: self.vesting_period,
+            bump: self.bump,
+            vault_balance: 0,
+            proposal: None,
+            seat_holds: Default::default(),
+            rating_band: None,
+        }
+    }
+}
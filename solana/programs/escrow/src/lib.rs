@@ -1,78 +1,549 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_lang::system_program;
+use std::cell::RefMut;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::metadata::MetadataAccount;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked,
+};
+
+pub mod engine;
+pub mod legacy;
+pub mod oracle;
+pub mod payout;
+pub mod randomness;
+pub mod rating;
+pub mod sigverify;
+pub mod zk;
+
+use engine::Board;
+use payout::PayoutBreakdown;
+use zk::{Groth16Proof, VerifyingKey};
+use anchor_lang::solana_program::sysvar::{instructions as instructions_sysvar, slot_hashes};
+use oracle::{PythPrice, SOL_USD_FEED_ID};
+use randomness::{recent_slot_hash, OraoRandomness, SwitchboardRandomness};
 
 declare_id!("HBomBxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx");
 
+/// Smallest lobby a game can be created with
+pub const MIN_PLAYERS: u8 = 2;
+/// Largest lobby a game can be created with (sizes the players array)
+pub const MAX_PLAYERS: usize = 8;
+
+/// Basis point denominator for fee math
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Largest bracket a tournament can run (must be a power of two)
+pub const MAX_TOURNAMENT_ENTRANTS: usize = 16;
+
+/// Players a season can rank
+pub const MAX_SEASON_PLAYERS: usize = 32;
+
+/// Players the global leaderboard ranks
+pub const LEADERBOARD_SIZE: usize = 32;
+
+/// Stake mints a player profile keeps separate stats for
+pub const MAX_STAT_MINTS: usize = 4;
+
+/// Recorded wins in a row that unlock `AchievementKind::WinStreak`
+pub const ACHIEVEMENT_WIN_STREAK: u32 = 10;
+
+/// Entrants a sit-and-go queue seats per game
+pub const QUEUE_SIZE: usize = 5;
+
+/// Lobbies listed per registry page
+pub const LOBBY_PAGE_SIZE: usize = 32;
+
+/// Joins to a scheduled game close this many seconds before its start
+pub const SCHEDULED_JOIN_BUFFER_SECS: i64 = 5 * 60;
+
+/// Wallets a private game's allowlist can hold
+pub const MAX_ALLOWLIST: usize = 32;
+
+/// Games listed per page of a player's game index
+pub const GAME_INDEX_PAGE_SIZE: usize = 32;
+
+/// Withdrawal destinations a player profile can register
+pub const MAX_WITHDRAWAL_ADDRESSES: usize = 4;
+
+/// Departed players a waiting game holds a seat for at once
+pub const MAX_SEAT_HOLDS: usize = 4;
+
+/// Referee keys a result quorum can list
+pub const MAX_REFEREES: usize = 8;
+
+/// Extra stake mints the config allowlist can hold
+pub const MAX_STAKE_MINTS: usize = 8;
+
+/// NFT collections the config allowlist can hold for NFT-staked games
+pub const MAX_NFT_COLLECTIONS: usize = 8;
+
+/// Recipients the house fee can be split across besides the treasury
+pub const MAX_FEE_SHARES: usize = 4;
+
+/// Stake-size tiers in the house fee schedule
+pub const MAX_FEE_TIERS: usize = 4;
+
+/// Oldest Pyth price (in seconds) a USD-staked join may be priced at
+pub const PRICE_MAX_AGE_SECS: i64 = 60;
+
+/// Seconds each round of an on-chain engine game stays open for moves
+pub const ENGINE_ROUND_SECS: i64 = 10;
+
+/// Moves a game's on-chain move log can hold
+pub const MAX_LOGGED_MOVES: usize = 128;
+
+/// Moves accepted in one `submit_moves_batch` call
+pub const MAX_MOVE_BATCH: usize = 50;
+/// Bytes per packed move in a batch: seat and action, x, y
+pub const PACKED_MOVE_LEN: usize = 3;
+
+/// Strikes at which a waiting player is automatically kicked and refunded
+pub const STRIKE_THRESHOLD: u8 = 3;
+
+/// Maximum number of games a single parlay can enter
+pub const MAX_PARLAY_GAMES: usize = 10;
+
+/// Seconds a game may sit in Waiting before it is considered stuck
+pub const STUCK_WAITING_SECS: i64 = 24 * 60 * 60;
+/// Seconds a game may stay Live before it is considered stuck
+pub const STUCK_LIVE_SECS: i64 = 2 * 60 * 60;
+/// Seconds after cancellation by which every player should be refunded
+pub const STUCK_REFUND_SECS: i64 = 7 * 24 * 60 * 60;
+/// Seconds after a game ends before it may be archived or closed, so
+/// stats, ratings, achievements, rakeback, insurance and jackpot claims
+/// that read it can still land
+pub const GAME_CLOSE_DELAY_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// `Game::stuck_flags` bits set by `flag_stuck`
+pub const STUCK_WAITING_TOO_LONG: u8 = 1 << 0;
+pub const STUCK_LIVE_TOO_LONG: u8 = 1 << 1;
+pub const STUCK_UNREFUNDED: u8 = 1 << 2;
+pub const STUCK_VAULT_NOT_EMPTY: u8 = 1 << 3;
+
+/// Longest a session key may stay valid
+pub const MAX_SESSION_SECS: i64 = 24 * 60 * 60;
+
+/// `SessionKey::permissions` bits
+pub const SESSION_SUBMIT_MOVES: u8 = 1 << 0;
+pub const SESSION_FORFEIT: u8 = 1 << 1;
+
+/// Current `Game` account layout, bumped with every schema change
+/// Version 0 is the layout before `version` was added; version 2 sizes
+/// the per-seat arrays to the game's seat count; version 3 moves funds
+/// from a vault per game to the shared vault of the stake mint and adds
+/// settlement proposals and seat holds
+pub const GAME_VERSION: u8 = 3;
+
 /// HAPPYBOMBER Escrow Program
 /// 
 /// Handles staking and payouts for multiplayer minesweeper games.
-/// - 5 agents per game
+/// - 2 to 8 agents per game, chosen at creation
 /// - Stakes locked in escrow PDA
-/// - Winner gets 95%, house gets 5%
+/// - Winner takes the pool minus a per-game house fee (bps)
 /// - Seed committed on-chain for verifiable fairness
 
 #[program]
 pub mod happybomber_escrow {
     use super::*;
 
-    /// Create a new game with specified stake amount
+    /// Initialize the global config - the signer becomes admin
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        config.admin = ctx.accounts.admin.key();
+        config.bump = ctx.bumps.config;
+        config.stats_season = 0;
+        config.stats_season_started_at = Clock::get()?.unix_timestamp;
+        config.apply(&params)?;
+        
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            params,
+        });
+        
+        Ok(())
+    }
+
+    /// Update the global config - admin only
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Pubkey,
+        params: ConfigParams,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        config.admin = admin;
+        config.apply(&params)?;
+        
+        emit!(ConfigUpdated {
+            admin,
+            params,
+        });
+        
+        Ok(())
+    }
+
+    /// Create a new game with the given stake and lobby parameters
+    /// The game ID is taken from the global game counter; listing the
+    /// lobby costs the creator `config.listing_fee` lamports
     pub fn create_game(
         ctx: Context<CreateGame>,
-        game_id: [u8; 8],
-        stake_amount: u64,
+        params: CreateGameParams,
     ) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
         let game = &mut ctx.accounts.game;
         let clock = Clock::get()?;
+        let CreateGameParams {
+            label,
+            stake_amount,
+            min_players,
+            max_players,
+            fee_bps,
+            payout_schedule,
+            join_deadline,
+            scheduled_start,
+            invite_code_hash,
+            nft_collection,
+            balance_gate,
+            rating_band,
+            commit_reveal,
+            on_chain_engine,
+            usd_stake,
+            creator_fee_bps,
+            bounty_bps,
+            survival_payout,
+            side_stake,
+            rebuy_window,
+            max_rebuys,
+        } = params;
+        
+        require!(
+            join_deadline > clock.unix_timestamp,
+            EscrowError::InvalidJoinDeadline
+        );
+        // A scheduled lobby must stay joinable for a while and must not be
+        // expirable before its start time
+        if let Some(start) = scheduled_start {
+            require!(
+                start - SCHEDULED_JOIN_BUFFER_SECS > clock.unix_timestamp
+                    && join_deadline >= start,
+                EscrowError::InvalidScheduledStart
+            );
+        }
+        require!(
+            fee_bps <= ctx.accounts.config.max_fee_bps
+                && creator_fee_bps <= ctx.accounts.config.max_creator_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        if let Some(band) = &rating_band {
+            require!(band.min_rating <= band.max_rating, EscrowError::InvalidRatingBand);
+        }
+        require!(
+            ctx.accounts.config.accepts_mint(&ctx.accounts.usdc_mint.key()),
+            EscrowError::MintNotAllowed
+        );
+        // USD stakes are priced against SOL/USD, so only SOL games take them
+        require!(
+            !usd_stake || ctx.accounts.usdc_mint.key() == native_mint::ID,
+            EscrowError::UsdStakeUnsupported
+        );
+        reject_transfer_fee(&ctx.accounts.usdc_mint)?;
+        
+        validate_lobby(min_players, max_players, &payout_schedule)?;
+        // The engine only decides a single winner
+        require!(
+            !on_chain_engine || payout_schedule.len() == 1,
+            EscrowError::EngineModeUnsupported
+        );
+        // Every seat but the winner can be eliminated once, and again after
+        // each rebuy; the bounties must leave room for the house fee
+        let eliminations = (max_players as u64).saturating_sub(1)
+            + max_players as u64 * max_rebuys as u64;
+        require!(
+            bounty_bps as u64 * eliminations + fee_bps as u64 <= BPS_DENOMINATOR,
+            EscrowError::InvalidBounty
+        );
+        require!(
+            rebuy_window >= 0 && (max_rebuys == 0) == (rebuy_window == 0),
+            EscrowError::InvalidRebuy
+        );
+        // Eliminations are reported by the backend, not the engine
+        require!(
+            !on_chain_engine || (bounty_bps == 0 && !survival_payout),
+            EscrowError::EngineModeUnsupported
+        );
+        require!(
+            commit_reveal
+                || ctx.accounts.config.randomness_source != RandomnessSource::CommitReveal,
+            EscrowError::CommitRevealRequired
+        );
         
-        game.game_id = game_id;
-        game.creator = ctx.accounts.creator.key();
+        // Season games count towards standings, which only track winners
+        let season = match &ctx.accounts.season {
+            Some(season) => {
+                require!(
+                    season.is_active(clock.unix_timestamp),
+                    EscrowError::SeasonNotActive
+                );
+                require!(payout_schedule.len() == 1, EscrowError::InvalidPayoutSchedule);
+                Some(season.key())
+            }
+            None => None,
+        };
+        
+        game.init(
+            game_id,
+            ctx.accounts.creator.key(),
+            ctx.accounts.usdc_mint.key(),
+            max_players,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.label = label;
         game.stake_amount = stake_amount;
-        game.player_count = 0;
-        game.players = [Pubkey::default(); 5];
-        game.status = GameStatus::Waiting;
-        game.seed = [0u8; 32];
-        game.winner = None;
-        game.created_at = clock.unix_timestamp;
-        game.started_at = None;
-        game.bump = ctx.bumps.game;
+        game.usd_stake = usd_stake;
+        game.min_players = min_players;
+        game.fee_bps = fee_bps;
+        game.creator_fee_bps = creator_fee_bps;
+        game.bounty_bps = bounty_bps;
+        game.survival_payout = survival_payout;
+        game.side_stake = side_stake;
+        game.rebuy_window = rebuy_window;
+        game.max_rebuys = max_rebuys;
+        game.payout_schedule[..payout_schedule.len()].copy_from_slice(&payout_schedule);
+        game.join_deadline = join_deadline;
+        game.scheduled_start = scheduled_start;
+        game.season = season;
+        game.lobby_page = Some(ctx.accounts.lobby_registry.page);
+        game.invite_code_hash = invite_code_hash;
+        game.nft_collection = nft_collection;
+        game.balance_gate = balance_gate;
+        game.rating_band = rating_band;
+        game.commit_reveal = commit_reveal;
+        game.engine = on_chain_engine.then(EngineState::default);
+        
+        let game_key = game.key();
+        ctx.accounts.lobby_registry.insert(game_key)?;
+        pay_listing_fee(
+            &ctx.accounts.config,
+            &ctx.accounts.creator,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+        )?;
         
         emit!(GameCreated {
             game_id,
+            label,
             creator: game.creator,
             stake_amount,
+            min_players,
+            max_players,
+            fee_bps,
+            payout_schedule,
+            join_deadline,
+            scheduled_start,
         });
         
         Ok(())
     }
 
     /// Join a game - transfers stake to escrow
-    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
+    /// `invite_code` is required for invite-only lobbies and `commitment`
+    /// for commit-reveal lobbies; `insured` also pays the premium of
+    /// `config.insurance` from the player's token account
+    pub fn join_game(
+        ctx: Context<JoinGame>,
+        invite_code: Option<Vec<u8>>,
+        commitment: Option<[u8; 32]>,
+        insured: bool,
+    ) -> Result<()> {
         let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
         
         require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
-        require!(game.player_count < 5, EscrowError::GameFull);
+        require!(
+            clock.unix_timestamp <= game.join_cutoff(),
+            EscrowError::JoinDeadlinePassed
+        );
         
-        // Check player hasn't already joined
+        // Add player to game
         let player = ctx.accounts.player.key();
-        for i in 0..game.player_count as usize {
-            require!(game.players[i] != player, EscrowError::AlreadyJoined);
+        let credentials = JoinCredentials {
+            allowlist: ctx.accounts.allowlist.as_ref(),
+            invite_code: invite_code.as_deref(),
+            nft_token_account: ctx.accounts.nft_token_account.as_ref(),
+            nft_metadata: ctx.accounts.nft_metadata.as_ref(),
+            gate_token_account: ctx.accounts.gate_token_account.as_ref(),
+            profile: Some(&ctx.accounts.profile),
+        };
+        check_join_gates(game, &credentials, &player)?;
+        let seat = game.seat_player(player, clock.unix_timestamp)?;
+        game.commit_entropy(seat, commitment)?;
+        if let Some(agent) = ctx.accounts.agent.as_ref() {
+            game.payout_wallets[seat] = agent.payout_wallet;
+        }
+        apply_withdrawal_addresses(game, seat, &ctx.accounts.profile)?;
+        if let Some(game_index) = ctx.accounts.game_index.as_mut() {
+            game_index.insert(game.key())?;
         }
         
-        // Transfer stake to vault
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.player_token_account.to_account_info(),
-            to: ctx.accounts.vault.to_account_info(),
-            authority: ctx.accounts.player.to_account_info(),
+        let amount = if game.usd_stake {
+            let price_update = ctx
+                .accounts
+                .price_update
+                .as_ref()
+                .ok_or(EscrowError::InvalidPriceAccount)?;
+            PythPrice::load(price_update, &SOL_USD_FEED_ID)?.lamports_for_cents(
+                game.stake_amount,
+                &clock,
+                PRICE_MAX_AGE_SECS,
+            )?
+        } else {
+            game.stake_amount
         };
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-        );
-        token::transfer(cpi_ctx, game.stake_amount)?;
+        game.paid[seat] = amount;
         
-        // Add player to game
-        game.players[game.player_count as usize] = player;
-        game.player_count += 1;
+        // A sponsor funds the stake from their token account; the seat and
+        // any winnings stay the player's, refunds go back to the sponsor
+        let payer = match ctx.accounts.sponsor.as_ref() {
+            Some(sponsor) => {
+                require!(
+                    ctx.accounts.player_balance.is_none()
+                        && ctx.accounts.player_token_account.is_some(),
+                    EscrowError::SponsorTokenAccountRequired
+                );
+                game.stake_sponsors[seat] = sponsor.key();
+                sponsor.to_account_info()
+            }
+            None => ctx.accounts.player.to_account_info(),
+        };
+        
+        // Transfer stake to vault - debited from the player's in-program
+        // balance when one is passed; SOL games can also be joined with
+        // plain SOL, which is wrapped straight into the vault
+        match (
+            ctx.accounts.player_balance.as_mut(),
+            ctx.accounts.player_token_account.as_ref(),
+        ) {
+            (Some(player_balance), _) => {
+                let balance_vault = ctx
+                    .accounts
+                    .balance_vault
+                    .as_ref()
+                    .ok_or(EscrowError::BalanceVaultRequired)?;
+                require!(player_balance.amount >= amount, EscrowError::InsufficientBalance);
+                player_balance.amount -= amount;
+                
+                let mint_key = ctx.accounts.mint.key();
+                let seeds = &[
+                    b"balance_vault",
+                    mint_key.as_ref(),
+                    &[ctx.bumps.balance_vault],
+                ];
+                let signer = &[&seeds[..]];
+                
+                let cpi_accounts = TransferChecked {
+                    from: balance_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: balance_vault.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                );
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            }
+            (None, Some(player_token_account)) => {
+                let cpi_accounts = TransferChecked {
+                    from: player_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: payer.clone(),
+                };
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                );
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            }
+            (None, None) => {
+                require_keys_eq!(game.stake_mint, native_mint::ID, EscrowError::InvalidMint);
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.player.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    cpi_accounts,
+                );
+                system_program::transfer(cpi_ctx, amount)?;
+                
+                let cpi_ctx_sync = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    SyncNative {
+                        account: ctx.accounts.vault.to_account_info(),
+                    },
+                );
+                token_interface::sync_native(cpi_ctx_sync)?;
+            }
+        }
+        
+        // The premium goes straight to the insurance pool and is not
+        // returned if the player leaves or the game is cancelled
+        if insured {
+            let terms = ctx
+                .accounts
+                .config
+                .insurance
+                .ok_or(EscrowError::InsuranceUnavailable)?;
+            let (Some(player_token_account), Some(insurance_pool)) = (
+                ctx.accounts.player_token_account.as_ref(),
+                ctx.accounts.insurance_pool.as_ref(),
+            ) else {
+                return err!(EscrowError::InsuranceUnavailable);
+            };
+            let premium = payout::insurance_share(amount, terms.premium_bps)?;
+            game.insured |= 1 << seat;
+            
+            let cpi_accounts = TransferChecked {
+                from: player_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: insurance_pool.to_account_info(),
+                authority: payer,
+            };
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+            );
+            token_interface::transfer_checked(cpi_ctx, premium, ctx.accounts.mint.decimals)?;
+            
+            emit!(InsurancePurchased {
+                game_id: game.game_id,
+                player,
+                premium,
+            });
+        }
+        
+        if let Some(sponsor) = ctx.accounts.sponsor.as_ref() {
+            emit!(StakeSponsored {
+                game_id: game.game_id,
+                player,
+                sponsor: sponsor.key(),
+                amount,
+            });
+        }
         
         emit!(PlayerJoined {
             game_id: game.game_id,
@@ -80,356 +551,15199 @@ pub mod happybomber_escrow {
             player_count: game.player_count,
         });
         
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
         Ok(())
     }
 
-    /// Start the game - commits seed on-chain
-    /// Only callable when 5 players have joined
-    pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
+    /// Leave a game before it starts - returns the caller's stake
+    /// The seat stays held for `config.rejoin_grace` seconds in case the
+    /// caller left by mistake
+    pub fn leave_game(ctx: Context<LeaveGame>) -> Result<()> {
         let game = &mut ctx.accounts.game;
         let clock = Clock::get()?;
         
         require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
-        require!(game.player_count == 5, EscrowError::NotEnoughPlayers);
         
-        // Generate seed from recent blockhash + game_id
-        // In production, use a more secure randomness source
-        let blockhash = clock.slot.to_le_bytes();
-        let mut seed_data = [0u8; 32];
-        seed_data[..8].copy_from_slice(&game.game_id);
-        seed_data[8..16].copy_from_slice(&blockhash);
-        seed_data[16..24].copy_from_slice(&clock.unix_timestamp.to_le_bytes());
+        let player = ctx.accounts.player.key();
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(
+            ctx.accounts.player_token_account.owner == game.refund_owner(index),
+            EscrowError::WrongPlayer
+        );
         
-        game.seed = seed_data;
-        game.status = GameStatus::Live;
-        game.started_at = Some(clock.unix_timestamp);
+        let refunded = unseat_and_refund(
+            game,
+            &ctx.accounts.vault,
+            &ctx.accounts.player_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.bumps.vault,
+            index,
+        )?;
+        game.hold_seat(
+            player,
+            clock.unix_timestamp,
+            clock.unix_timestamp + ctx.accounts.config.rejoin_grace,
+        );
         
-        emit!(GameStarted {
+        emit!(PlayerLeft {
             game_id: game.game_id,
-            seed: game.seed,
-            started_at: clock.unix_timestamp,
+            player,
+            refunded,
+            player_count: game.player_count,
         });
         
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
         Ok(())
     }
 
-    /// End the game - distributes payouts
-    /// Only callable by authorized backend (game authority)
-    pub fn end_game(
-        ctx: Context<EndGame>,
-        winner_index: u8,
+    /// Rejoin a waiting game after leaving or being kicked, taking the seat
+    /// held for the caller while `config.rejoin_grace` has not run out
+    /// Takes the same accounts and arguments as `join_game`
+    pub fn rejoin_game(
+        ctx: Context<JoinGame>,
+        invite_code: Option<Vec<u8>>,
+        commitment: Option<[u8; 32]>,
+        insured: bool,
     ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.game.seat_hold_of(&ctx.accounts.player.key(), now).is_some(),
+            EscrowError::NoSeatHold
+        );
+        join_game(ctx, invite_code, commitment, insured)
+    }
+
+    /// Kick a player before the game starts - refunds their stake
+    /// Only callable by the game creator; the seat stays held for
+    /// `config.rejoin_grace` seconds so a wrongly kicked player can rejoin
+    pub fn kick_player(ctx: Context<KickPlayer>, player_index: u8) -> Result<()> {
         let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
         
-        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
-        require!(winner_index < 5, EscrowError::InvalidWinner);
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            ctx.accounts.creator.key() == game.creator,
+            EscrowError::Unauthorized
+        );
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
         
-        let winner = game.players[winner_index as usize];
-        require!(winner != Pubkey::default(), EscrowError::InvalidWinner);
+        let index = player_index as usize;
+        let player = game.players[index];
+        require!(
+            ctx.accounts.player_token_account.owner == game.refund_owner(index),
+            EscrowError::WrongPlayer
+        );
         
-        game.winner = Some(winner);
-        game.status = GameStatus::Finished;
+        let refunded = unseat_and_refund(
+            game,
+            &ctx.accounts.vault,
+            &ctx.accounts.player_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.bumps.vault,
+            index,
+        )?;
+        game.hold_seat(
+            player,
+            clock.unix_timestamp,
+            clock.unix_timestamp + ctx.accounts.config.rejoin_grace,
+        );
         
-        // Calculate payouts
-        let total_pool = game.stake_amount * 5;
-        let house_fee = total_pool / 20; // 5%
-        let winner_payout = total_pool - house_fee;
+        emit!(PlayerKicked {
+            game_id: game.game_id,
+            player,
+            refunded,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Swap a waiting player for a substitute in the same seat
+    /// Both must sign; the substitute stakes in and the outgoing player
+    /// is refunded, so seat order is preserved
+    pub fn replace_player(
+        ctx: Context<ReplacePlayer>,
+        invite_code: Option<Vec<u8>>,
+        commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            clock.unix_timestamp <= game.join_cutoff(),
+            EscrowError::JoinDeadlinePassed
+        );
+        
+        // A substitute would owe a different amount at today's price
+        require!(!game.usd_stake, EscrowError::UsdStakeUnsupported);
+        
+        let player = ctx.accounts.player.key();
+        let substitute = ctx.accounts.substitute.key();
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(game.seat_of(&substitute).is_none(), EscrowError::AlreadyJoined);
+        let credentials = JoinCredentials {
+            allowlist: ctx.accounts.allowlist.as_ref(),
+            invite_code: invite_code.as_deref(),
+            nft_token_account: ctx.accounts.nft_token_account.as_ref(),
+            nft_metadata: ctx.accounts.nft_metadata.as_ref(),
+            gate_token_account: ctx.accounts.gate_token_account.as_ref(),
+            profile: Some(&ctx.accounts.substitute_profile),
+        };
+        check_join_gates(game, &credentials, &substitute)?;
+        require!(
+            ctx.accounts.player_token_account.owner == game.refund_owner(index),
+            EscrowError::WrongPlayer
+        );
+        
+        // Substitute stakes in
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.substitute_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.substitute.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.stake_amount, ctx.accounts.mint.decimals)?;
         
-        // Transfer to winner
+        // Outgoing player is refunded
         let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
         let seeds = &[
             b"vault",
-            game_id.as_ref(),
+            stake_mint.as_ref(),
             &[ctx.bumps.vault],
         ];
         let signer = &[&seeds[..]];
         
-        let cpi_accounts = Transfer {
+        let cpi_accounts_refund = TransferChecked {
             from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.winner_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
             authority: ctx.accounts.vault.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new_with_signer(
+        let cpi_ctx_refund = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
+            cpi_accounts_refund,
             signer,
         );
-        token::transfer(cpi_ctx, winner_payout)?;
+        token_interface::transfer_checked(cpi_ctx_refund, game.refund_of(index), ctx.accounts.mint.decimals)?;
         
-        // Transfer house fee
-        let cpi_accounts_house = Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.house_token_account.to_account_info(),
-            authority: ctx.accounts.vault.to_account_info(),
-        };
-        let cpi_ctx_house = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts_house,
-            signer,
-        );
-        token::transfer(cpi_ctx_house, house_fee)?;
+        game.players[index] = substitute;
+        game.stake_sponsors[index] = Pubkey::default();
+        game.payout_wallets[index] = Pubkey::default();
+        apply_withdrawal_addresses(game, index, &ctx.accounts.substitute_profile)?;
+        // The outgoing player's premium stays in the insurance pool, as it
+        // does when a player leaves; the substitute joins uninsured
+        game.insured &= !(1 << index);
+        game.side_pot &= !(1 << index);
+        game.strikes[index] = 0;
+        game.revealed &= !(1 << index);
+        game.commit_entropy(index, commitment)?;
         
-        emit!(GameEnded {
-            game_id: game.game_id,
-            winner,
-            winner_payout,
-            house_fee,
+        emit!(PlayerReplaced {
+            game_id,
+            player,
+            substitute,
+            seat: index as u8,
         });
         
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
         Ok(())
     }
 
-    /// Cancel a game before it starts - refunds all players
-    pub fn cancel_game(ctx: Context<CancelGame>) -> Result<()> {
+    /// Start the game - commits seed on-chain
+    /// Only callable once at least `min_players` have joined
+    /// The board seed comes from `config.randomness_source`; VRF seeds are
+    /// written by `settle_randomness` once fulfilled, backend seeds are
+    /// committed here and revealed at settlement, and the game cannot
+    /// settle before its seed is known
+    pub fn start_game(
+        ctx: Context<StartGame>,
+        seed_commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
         let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
         
         require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
         require!(
-            ctx.accounts.authority.key() == game.creator,
-            EscrowError::Unauthorized
+            game.player_count >= game.min_players,
+            EscrowError::NotEnoughPlayers
         );
+        if let Some(start) = game.scheduled_start {
+            require!(
+                clock.unix_timestamp >= start,
+                EscrowError::ScheduledStartNotReached
+            );
+        }
         
-        game.status = GameStatus::Cancelled;
+        if game.commit_reveal {
+            let all_revealed = ((1u16 << game.player_count) - 1) as u8;
+            require!(game.revealed == all_revealed, EscrowError::EntropyNotRevealed);
+        }
         
-        emit!(GameCancelled {
+        let source = ctx.accounts.config.randomness_source;
+        require!(
+            seed_commitment.is_some() == (source == RandomnessSource::BackendCommit),
+            EscrowError::InvalidCommitment
+        );
+        // The engine needs the seed on-chain while the game is played
+        require!(
+            game.engine.is_none() || source != RandomnessSource::BackendCommit,
+            EscrowError::EngineModeUnsupported
+        );
+        game.randomness_source = source;
+        game.randomness_account = None;
+        game.randomness_slot = 0;
+        game.seed_pending = false;
+        game.seed_commitment = None;
+        game.seed = [0u8; 32];
+        
+        match source {
+            RandomnessSource::SlotHash | RandomnessSource::CommitReveal => {
+                require!(
+                    game.commit_reveal || source == RandomnessSource::SlotHash,
+                    EscrowError::CommitRevealRequired
+                );
+                let seed = derive_seed(&game.game_id, &clock, &ctx.accounts.slot_hashes)?;
+                game.seed = if game.commit_reveal {
+                    game.mix_revealed_entropy(seed)
+                } else {
+                    seed
+                };
+            }
+            RandomnessSource::SwitchboardVrf => {
+                let account = ctx
+                    .accounts
+                    .randomness_account
+                    .as_ref()
+                    .ok_or(EscrowError::InvalidRandomnessAccount)?;
+                // A request committed in the previous slot cannot have been
+                // revealed yet, so nobody knows the seed when the game starts
+                let randomness = SwitchboardRandomness::load(account)?;
+                require!(
+                    randomness.seed_slot == clock.slot - 1,
+                    EscrowError::RandomnessAlreadyRevealed
+                );
+                game.randomness_account = Some(account.key());
+                game.randomness_slot = randomness.seed_slot;
+                game.seed_pending = true;
+            }
+            RandomnessSource::OraoVrf => {
+                let account = ctx
+                    .accounts
+                    .randomness_account
+                    .as_ref()
+                    .ok_or(EscrowError::InvalidRandomnessAccount)?;
+                // The request is seeded with the game address, so it cannot
+                // have been picked from already fulfilled requests
+                let randomness = OraoRandomness::load(account, &game.key().to_bytes())?;
+                require!(
+                    randomness.value.is_none(),
+                    EscrowError::RandomnessAlreadyRevealed
+                );
+                game.randomness_account = Some(account.key());
+                game.seed_pending = true;
+            }
+            RandomnessSource::BackendCommit => {
+                // Only the hash is stored, so the backend is bound to its
+                // seed before play and cannot swap boards afterwards
+                game.seed_commitment = seed_commitment;
+                game.seed_pending = true;
+            }
+        }
+        if !game.seed_pending {
+            game.open_engine(clock.unix_timestamp);
+        }
+        
+        game.status = GameStatus::Live;
+        game.started_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        delist_lobby(game, ctx.accounts.lobby_registry.as_mut())?;
+        
+        emit!(GameStarted {
             game_id: game.game_id,
+            seed: game.seed,
+            started_at: clock.unix_timestamp,
         });
         
-        // Refunds handled separately via refund_player instruction
         Ok(())
     }
 
-    /// Refund a player from a cancelled game
-    pub fn refund_player(ctx: Context<RefundPlayer>, player_index: u8) -> Result<()> {
-        let game = &ctx.accounts.game;
-        
-        require!(game.status == GameStatus::Cancelled, EscrowError::GameNotCancelled);
-        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+    /// Write fulfilled VRF randomness into the game seed - permissionless
+    /// Switchboard values must be settled in the slot the oracle revealed
+    pub fn settle_randomness(ctx: Context<SettleRandomness>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
         
-        let player = game.players[player_index as usize];
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(game.seed_pending, EscrowError::RandomnessAlreadyRevealed);
         require!(
-            ctx.accounts.player_token_account.owner == player,
-            EscrowError::WrongPlayer
+            game.randomness_account == Some(ctx.accounts.randomness_account.key()),
+            EscrowError::InvalidRandomnessAccount
         );
         
-        let game_id = game.game_id;
-        let seeds = &[
-            b"vault",
-            game_id.as_ref(),
-            &[ctx.bumps.vault],
-        ];
-        let signer = &[&seeds[..]];
+        let account = &ctx.accounts.randomness_account;
+        let value = match game.randomness_source {
+            RandomnessSource::SwitchboardVrf => {
+                let randomness = SwitchboardRandomness::load(account)?;
+                // A re-committed request would let the value be re-rolled
+                require!(
+                    randomness.seed_slot == game.randomness_slot,
+                    EscrowError::InvalidRandomnessAccount
+                );
+                randomness.revealed_value(&clock)?
+            }
+            RandomnessSource::OraoVrf => OraoRandomness::load(account, &game.key().to_bytes())?
+                .value
+                .ok_or(EscrowError::RandomnessNotRevealed)?,
+            RandomnessSource::SlotHash
+            | RandomnessSource::CommitReveal
+            | RandomnessSource::BackendCommit => {
+                return err!(EscrowError::InvalidRandomnessAccount)
+            }
+        };
         
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.player_token_account.to_account_info(),
-            authority: ctx.accounts.vault.to_account_info(),
+        game.seed = if game.commit_reveal {
+            game.mix_revealed_entropy(value)
+        } else {
+            value
         };
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer,
-        );
-        token::transfer(cpi_ctx, game.stake_amount)?;
+        game.seed_pending = false;
+        game.open_engine(clock.unix_timestamp);
         
-        emit!(PlayerRefunded {
+        emit!(GameSeeded {
             game_id: game.game_id,
-            player,
-            amount: game.stake_amount,
+            seed: game.seed,
         });
         
         Ok(())
     }
-}
-
-// === Accounts ===
 
-#[derive(Accounts)]
-#[instruction(game_id: [u8; 8])]
-pub struct CreateGame<'info> {
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + Game::INIT_SPACE,
-        seeds = [b"game", game_id.as_ref()],
-        bump
-    )]
-    pub game: Account<'info, Game>,
+    /// Propose the winner of the game - no funds move yet
+    /// Only callable by authorized backend (game authority)
+    /// `move_root` is the merkle root of the complete move log, kept on the
+    /// game so the result can be challenged against it; the proposal can
+    /// be finalized once `config.dispute_window` has passed
+    /// With `config.tee` or `config.referees` set, the transaction must also
+    /// carry Ed25519 program instructions with the enclave's signed result
+    /// report and a quorum of referee signatures over (game id, winner)
+    pub fn propose_result(
+        ctx: Context<ProposeResult>,
+        winner_index: u8,
+        seed_reveal: Option<[u8; 32]>,
+        move_root: [u8; 32],
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(game.engine.is_none(), EscrowError::EngineSettlementRequired);
+        game.reveal_backend_seed(seed_reveal)?;
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        require!(game.paid_places() == 1, EscrowError::RankedSettlementRequired);
+        require!(!game.has_teams(), EscrowError::TeamSettlementRequired);
+        require!(!game.survival_payout, EscrowError::SurvivalSettlementRequired);
+        require!(winner_index < game.player_count, EscrowError::InvalidWinner);
+        if let Some(logged) = game.logged_placements() {
+            require!(logged[0] == winner_index, EscrowError::ResultContradictsLog);
+        }
+        
+        let winner = game.players[winner_index as usize];
+        require!(winner != Pubkey::default(), EscrowError::InvalidWinner);
+        
+        // Results from the attested enclave carry its signed report
+        if let Some(tee) = ctx.accounts.config.tee {
+            let report = tee.result_report(&game.game_id, &winner, &move_root);
+            let signers = sigverify::ed25519_signers(&ctx.accounts.instructions, &report)?;
+            require!(
+                signers.contains(&tee.enclave_key),
+                EscrowError::AttestationRequired
+            );
+        }
+        
+        if let Some(referees) = ctx.accounts.config.referees {
+            let message = RefereeQuorum::result_message(&game.game_id, &winner);
+            let signers = sigverify::ed25519_signers(&ctx.accounts.instructions, &message)?;
+            require!(
+                referees.approvals(&signers) >= referees.threshold,
+                EscrowError::RefereeQuorumNotMet
+            );
+        }
+        
+        let dispute_deadline = clock.unix_timestamp + ctx.accounts.config.dispute_window;
+        game.proposed_winner = Some(winner);
+        game.dispute_deadline = Some(dispute_deadline);
+        game.move_root = Some(move_root);
+        
+        emit!(ResultProposed {
+            game_id: game.game_id,
+            winner,
+            dispute_deadline,
+        });
+        
+        Ok(())
+    }
+
+    /// Settle a proposed result once its dispute window has passed
+    /// Permissionless - the winner was fixed by `propose_result`; the house
+    /// fee is paid now and the winnings are left for `claim_winnings`
+    /// Remaining accounts: the fee split recipients' accounts, then a
+    /// (referral, referrer balance) pair per referred player
+    pub fn finalize_result<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeResult<'info>>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let winner = game.proposed_winner.ok_or(EscrowError::NoResultProposed)?;
+        let dispute_deadline = game.dispute_deadline.ok_or(EscrowError::NoResultProposed)?;
+        require!(
+            clock.unix_timestamp >= dispute_deadline,
+            EscrowError::DisputeWindowOpen
+        );
+        game.winner = Some(winner);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        // Calculate payouts
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = house_fee;
+        
+        // Winnings stay in the vault until the winner claims them, or
+        // vest when above the vesting threshold
+        let winner_index = game.seat_of(&winner).ok_or(EscrowError::InvalidWinner)?;
+        if !vest_payout(game, winner_index, winner_payout, &ctx.accounts.config)? {
+            game.unclaimed = winner_payout;
+        }
+        
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        // Season games divert a share of the rake into the season pool
+        let season_cut = pay_season_cut(
+            game,
+            winner,
+            house_fee,
+            &mut ctx.accounts.season,
+            &ctx.accounts.season_vault,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            house_fee - season_cut,
+            fee_waived,
+            ctx.remaining_accounts,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEnded {
+            game_id: game.game_id,
+            winner,
+            winner_payout,
+            house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Winner withdraws the winnings recorded by `finalize_result` into
+    /// their own token account
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let winner = game.winner.ok_or(EscrowError::InvalidWinner)?;
+        let winner_index = game.seat_of(&winner).ok_or(EscrowError::InvalidWinner)?;
+        require_keys_eq!(
+            ctx.accounts.winner.key(),
+            game.payout_owner(winner_index),
+            EscrowError::InvalidWinner
+        );
+        let amount = game.unclaimed;
+        require!(amount > 0, EscrowError::NothingToClaim);
+        game.unclaimed = 0;
+        
+        create_payout_account(
+            &ctx.accounts.winner,
+            &ctx.accounts.winner_token_account,
+            &ctx.accounts.winner,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+            &ctx.accounts.system_program,
+        )?;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        // SOL winnings arrive as native SOL - closing the wrapped account
+        // releases its balance and rent to the winner
+        if game.stake_mint == native_mint::ID {
+            let cpi_ctx_close = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.winner_token_account.to_account_info(),
+                    destination: ctx.accounts.winner.to_account_info(),
+                    authority: ctx.accounts.winner.to_account_info(),
+                },
+            );
+            token_interface::close_account(cpi_ctx_close)?;
+        }
+        
+        emit!(WinningsClaimed {
+            game_id,
+            winner: ctx.accounts.winner.key(),
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Withdraw winnings held in the caller's pending-claim account after a
+    /// payout to their frozen token account was diverted there
+    pub fn claim_pending_payout(ctx: Context<ClaimPendingPayout>) -> Result<()> {
+        check_withdrawal_address(
+            &ctx.accounts.profile,
+            &ctx.accounts.player_token_account.owner,
+        )?;
+        let amount = ctx.accounts.pending_claim.amount;
+        let mint_key = ctx.accounts.mint.key();
+        let player_key = ctx.accounts.player.key();
+        let seeds = &[
+            b"pending_claim",
+            mint_key.as_ref(),
+            player_key.as_ref(),
+            &[ctx.bumps.pending_claim],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.pending_claim.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.pending_claim.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        // Return the rent of the emptied account to the player
+        let cpi_ctx_close = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.pending_claim.to_account_info(),
+                destination: ctx.accounts.player.to_account_info(),
+                authority: ctx.accounts.pending_claim.to_account_info(),
+            },
+            signer,
+        );
+        token_interface::close_account(cpi_ctx_close)?;
+        
+        emit!(PendingPayoutClaimed {
+            player: player_key,
+            mint: mint_key,
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Create a player's pending-claim account for a mint ahead of a
+    /// multi-payee settlement that has to divert their share because their
+    /// token account is frozen
+    /// Anyone may pay for it; only the player can withdraw from it
+    pub fn open_pending_claim(ctx: Context<OpenPendingClaim>) -> Result<()> {
+        emit!(PendingClaimOpened {
+            player: ctx.accounts.player.key(),
+            mint: ctx.accounts.mint.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Propose how a game not paid to a single winner settles - ranked
+    /// placements, a draw, the winning team, the end of a survival game or
+    /// the accepted deal
+    /// Only callable by authorized backend (game authority); the proposal
+    /// can be challenged like `propose_result` and is paid out by the
+    /// matching `end_game_*` instruction or `settle_deal` once
+    /// `config.dispute_window` has passed
+    /// Ranked placements with a zero `count` are taken from a complete
+    /// elimination log
+    /// With `config.tee` or `config.referees` set, the transaction must also
+    /// carry Ed25519 program instructions with the enclave's signed
+    /// settlement report and a quorum of referee signatures over it
+    pub fn propose_settlement(
+        ctx: Context<ProposeResult>,
+        settlement: ProposedSettlement,
+        seed_reveal: Option<[u8; 32]>,
+        move_root: [u8; 32],
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(game.engine.is_none(), EscrowError::EngineSettlementRequired);
+        game.reveal_backend_seed(seed_reveal)?;
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        require!(game.season.is_none(), EscrowError::SeasonSettlementRequired);
+        
+        let settlement = match settlement {
+            ProposedSettlement::Ranked { placements, count } => {
+                require!(!game.has_teams(), EscrowError::TeamSettlementRequired);
+                require!(!game.survival_payout, EscrowError::SurvivalSettlementRequired);
+                // A single paid place goes through `propose_result`
+                require!(game.paid_places() > 1, EscrowError::InvalidPlacements);
+                // A complete elimination log decides the placements, not the backend
+                let (placements, count) = match game.logged_placements() {
+                    Some(logged) => {
+                        require!(
+                            count == 0 || placements.get(..count as usize) == Some(&logged[..]),
+                            EscrowError::ResultContradictsLog
+                        );
+                        let mut placements = [0u8; MAX_PLAYERS];
+                        placements[..logged.len()].copy_from_slice(&logged);
+                        (placements, logged.len() as u8)
+                    }
+                    None => (placements, count),
+                };
+                require!(
+                    count as usize >= game.paid_places() && count <= game.player_count,
+                    EscrowError::InvalidPlacements
+                );
+                let mut seen = 0u8;
+                for &index in placements[..count as usize].iter() {
+                    require!(index < game.player_count, EscrowError::InvalidPlacements);
+                    require!(seen & (1 << index) == 0, EscrowError::InvalidPlacements);
+                    seen |= 1 << index;
+                }
+                ProposedSettlement::Ranked { placements, count }
+            }
+            ProposedSettlement::Draw { tied } => {
+                require!(!game.survival_payout, EscrowError::SurvivalSettlementRequired);
+                // A complete elimination log leaves a single winner, never a tie
+                require!(game.logged_placements().is_none(), EscrowError::ResultContradictsLog);
+                let seated = ((1u16 << game.player_count) - 1) as u8;
+                require!(
+                    tied.count_ones() >= 2 && tied & !seated == 0,
+                    EscrowError::InvalidDraw
+                );
+                settlement
+            }
+            ProposedSettlement::Team { team } => {
+                require!(game.has_teams(), EscrowError::InvalidTeams);
+                require!(!game.survival_payout, EscrowError::SurvivalSettlementRequired);
+                require!(
+                    team != 0 && game.teams[..game.player_count as usize].contains(&team),
+                    EscrowError::InvalidTeams
+                );
+                settlement
+            }
+            ProposedSettlement::Survival { ended_at } => {
+                require!(game.survival_payout, EscrowError::SurvivalModeRequired);
+                require!(!game.has_teams(), EscrowError::TeamSettlementRequired);
+                let started_at = game.started_at.unwrap_or(game.created_at);
+                require!(
+                    ended_at >= started_at && ended_at <= clock.unix_timestamp,
+                    EscrowError::InvalidPlacements
+                );
+                settlement
+            }
+            ProposedSettlement::Deal => {
+                let deal = game.deal.ok_or(EscrowError::NoDeal)?;
+                let remaining = game.remaining();
+                require!(deal.accepted & remaining == remaining, EscrowError::DealNotAccepted);
+                settlement
+            }
+        };
+        
+        // The enclave and referees sign the settlement as it will be stored
+        if let Some(tee) = ctx.accounts.config.tee {
+            let report = tee.settlement_report(&game.game_id, &settlement, &move_root)?;
+            let signers = sigverify::ed25519_signers(&ctx.accounts.instructions, &report)?;
+            require!(
+                signers.contains(&tee.enclave_key),
+                EscrowError::AttestationRequired
+            );
+        }
+        
+        if let Some(referees) = ctx.accounts.config.referees {
+            let message = RefereeQuorum::settlement_message(&game.game_id, &settlement)?;
+            let signers = sigverify::ed25519_signers(&ctx.accounts.instructions, &message)?;
+            require!(
+                referees.approvals(&signers) >= referees.threshold,
+                EscrowError::RefereeQuorumNotMet
+            );
+        }
+        
+        let dispute_deadline = clock.unix_timestamp + ctx.accounts.config.dispute_window;
+        game.proposal = Some(settlement);
+        game.dispute_deadline = Some(dispute_deadline);
+        game.move_root = Some(move_root);
+        
+        emit!(SettlementProposed {
+            game_id: game.game_id,
+            settlement,
+            dispute_deadline,
+        });
+        
+        Ok(())
+    }
+
+    /// End the game with the ranked placements proposed by
+    /// `propose_settlement` - splits the prize pool according to the
+    /// game's payout schedule
+    /// Permissionless once the dispute window has passed; the token account
+    /// for each paid place is passed as a remaining account from first
+    /// place down, followed by the pending-claim account of each frozen one
+    /// (see `open_pending_claim`), the fee split recipients' accounts and a
+    /// (referral, referrer balance) pair per referred player
+    pub fn end_game_ranked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndGameRanked<'info>>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let placements = match game.take_settlement(clock.unix_timestamp)? {
+            ProposedSettlement::Ranked { placements, count } => placements[..count as usize].to_vec(),
+            _ => return err!(EscrowError::WrongSettlement),
+        };
+        
+        let breakdown = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = breakdown.house_fee;
+        let paid_places = game.paid_places();
+        require!(placements.len() >= paid_places, EscrowError::InvalidPlacements);
+        require!(
+            ctx.remaining_accounts.len() >= paid_places + ctx.accounts.config.fee_split.len(),
+            EscrowError::InvalidPlacements
+        );
+        
+        let winner = game.players[placements[0] as usize];
+        game.winner = Some(winner);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let mut paid = Vec::with_capacity(paid_places);
+        let (pending_claims, rest) = split_pending_claims(
+            &ctx.remaining_accounts[..paid_places],
+            &ctx.remaining_accounts[paid_places..],
+        )?;
+        for (place, info) in ctx.remaining_accounts[..paid_places].iter().enumerate() {
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(
+                token_account.owner == game.payout_owner(placements[place] as usize),
+                EscrowError::WrongPlayer
+            );
+            require!(token_account.mint == game.stake_mint, EscrowError::InvalidMint);
+            
+            let amount = breakdown.place_payouts[place];
+            paid.push(amount);
+            if vest_payout(game, placements[place] as usize, amount, &ctx.accounts.config)? {
+                continue;
+            }
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: payee_destination(&token_account, info, pending_claims, game_id, amount)?,
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        }
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            breakdown.house_fee,
+            breakdown.fee_waived,
+            rest,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEndedRanked {
+            game_id,
+            placements: placements
+                .iter()
+                .map(|&index| game.players[index as usize])
+                .collect(),
+            payouts: paid,
+            house_fee: breakdown.house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// End the game as the draw proposed by `propose_settlement` - splits
+    /// the prize pool evenly among the tied players; the house still takes
+    /// its fee
+    /// Permissionless once the dispute window has passed; the token account
+    /// for each tied player is passed as a remaining account in seat order,
+    /// followed by the pending-claim account of each frozen one (see
+    /// `open_pending_claim`), the fee split recipients' accounts and a
+    /// (referral, referrer balance) pair per referred player
+    pub fn end_game_draw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndGameRanked<'info>>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let draw_mask = match game.take_settlement(clock.unix_timestamp)? {
+            ProposedSettlement::Draw { tied } => tied,
+            _ => return err!(EscrowError::WrongSettlement),
+        };
+        let tied_indices: Vec<u8> = (0..game.player_count)
+            .filter(|&seat| draw_mask & (1 << seat) != 0)
+            .collect();
+        require!(
+            ctx.remaining_accounts.len()
+                >= tied_indices.len() + ctx.accounts.config.fee_split.len(),
+            EscrowError::InvalidDraw
+        );
+        
+        let breakdown = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = breakdown.house_fee;
+        let shares = payout::split_evenly(breakdown.winner_payout, tied_indices.len());
+        
+        game.draw_mask = draw_mask;
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let (pending_claims, rest) = split_pending_claims(
+            &ctx.remaining_accounts[..tied_indices.len()],
+            &ctx.remaining_accounts[tied_indices.len()..],
+        )?;
+        for (i, info) in ctx.remaining_accounts[..tied_indices.len()].iter().enumerate() {
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(
+                token_account.owner == game.payout_owner(tied_indices[i] as usize),
+                EscrowError::WrongPlayer
+            );
+            require!(token_account.mint == game.stake_mint, EscrowError::InvalidMint);
+            if vest_payout(game, tied_indices[i] as usize, shares[i], &ctx.accounts.config)? {
+                continue;
+            }
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: payee_destination(&token_account, info, pending_claims, game_id, shares[i])?,
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, shares[i], ctx.accounts.mint.decimals)?;
+        }
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            breakdown.house_fee,
+            breakdown.fee_waived,
+            rest,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameDrawn {
+            game_id,
+            tied: tied_indices
+                .iter()
+                .map(|&index| game.players[index as usize])
+                .collect(),
+            payouts: shares[..tied_indices.len()].to_vec(),
+            house_fee: breakdown.house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Assign seated players to teams before the game starts
+    /// `team_ids` holds one non-zero team id per seat; any change to the
+    /// roster clears the assignment so it must be redone before start
+    pub fn assign_teams(ctx: Context<AssignTeams>, team_ids: Vec<u8>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(game.engine.is_none(), EscrowError::EngineModeUnsupported);
+        require!(
+            team_ids.len() == game.player_count as usize,
+            EscrowError::InvalidTeams
+        );
+        require!(
+            team_ids.iter().all(|&team| team > 0),
+            EscrowError::InvalidTeams
+        );
+        require!(
+            team_ids.iter().any(|&team| team != team_ids[0]),
+            EscrowError::InvalidTeams
+        );
+        
+        game.teams.fill(0);
+        game.teams[..team_ids.len()].copy_from_slice(&team_ids);
+        
+        emit!(TeamsAssigned {
+            game_id: game.game_id,
+            teams: team_ids,
+        });
+        
+        Ok(())
+    }
+
+    /// End a team game on the winning team proposed by
+    /// `propose_settlement` - splits the prize pool pro-rata across the
+    /// team's members
+    /// Permissionless once the dispute window has passed; the token account
+    /// for each member is passed as a remaining account in seat order,
+    /// followed by the pending-claim account of each frozen one (see
+    /// `open_pending_claim`), the fee split recipients' accounts and a
+    /// (referral, referrer balance) pair per referred player
+    pub fn end_game_team<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndGameRanked<'info>>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let winning_team = match game.take_settlement(clock.unix_timestamp)? {
+            ProposedSettlement::Team { team } => team,
+            _ => return err!(EscrowError::WrongSettlement),
+        };
+        
+        let members: Vec<usize> = (0..game.player_count as usize)
+            .filter(|&i| game.teams[i] == winning_team)
+            .collect();
+        require!(!members.is_empty(), EscrowError::InvalidTeams);
+        require!(
+            ctx.remaining_accounts.len() >= members.len() + ctx.accounts.config.fee_split.len(),
+            EscrowError::InvalidTeams
+        );
+        
+        // Every seat staked the same amount, so pro-rata is an even split
+        let breakdown = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = breakdown.house_fee;
+        let shares = payout::split_evenly(breakdown.winner_payout, members.len());
+        
+        game.winner = Some(game.players[members[0]]);
+        game.winning_team = winning_team;
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let (pending_claims, rest) = split_pending_claims(
+            &ctx.remaining_accounts[..members.len()],
+            &ctx.remaining_accounts[members.len()..],
+        )?;
+        for (i, info) in ctx.remaining_accounts[..members.len()].iter().enumerate() {
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(
+                token_account.owner == game.payout_owner(members[i]),
+                EscrowError::WrongPlayer
+            );
+            require!(token_account.mint == game.stake_mint, EscrowError::InvalidMint);
+            if vest_payout(game, members[i], shares[i], &ctx.accounts.config)? {
+                continue;
+            }
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: payee_destination(&token_account, info, pending_claims, game_id, shares[i])?,
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, shares[i], ctx.accounts.mint.decimals)?;
+        }
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            breakdown.house_fee,
+            breakdown.fee_waived,
+            rest,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEndedTeam {
+            game_id,
+            winning_team,
+            members: members.iter().map(|&i| game.players[i]).collect(),
+            payouts: shares[..members.len()].to_vec(),
+            house_fee: breakdown.house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// End a survival game at the end time proposed by
+    /// `propose_settlement` - splits the prize pool pro-rata by how long
+    /// each player survived: from the start until their recorded
+    /// elimination, or until the proposed end for the players still standing
+    /// Permissionless once the dispute window has passed; the token account
+    /// for every seat is passed as a remaining account in seat order,
+    /// followed by the pending-claim account of each frozen one (see
+    /// `open_pending_claim`), the fee split recipients' accounts and a
+    /// (referral, referrer balance) pair per referred player
+    pub fn end_game_survival<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndGameRanked<'info>>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let ended_at = match game.take_settlement(clock.unix_timestamp)? {
+            ProposedSettlement::Survival { ended_at } => ended_at,
+            _ => return err!(EscrowError::WrongSettlement),
+        };
+        let seats = game.player_count as usize;
+        require!(
+            ctx.remaining_accounts.len() >= seats + ctx.accounts.config.fee_split.len(),
+            EscrowError::InvalidPlacements
+        );
+        
+        let started_at = game.started_at.unwrap_or(game.created_at);
+        let mut survival = [0u64; MAX_PLAYERS];
+        let (pending_claims, rest) = split_pending_claims(
+            &ctx.remaining_accounts[..seats],
+            &ctx.remaining_accounts[seats..],
+        )?;
+        for (seat, time) in survival.iter_mut().enumerate().take(seats) {
+            let out_at = if game.eliminated & (1 << seat) != 0 {
+                game.eliminated_at[seat]
+            } else {
+                ended_at
+            };
+            *time = out_at.saturating_sub(started_at).max(0) as u64;
+        }
+        // The longest survivor takes the win and the rounding dust
+        let winner_index = (0..seats)
+            .max_by_key(|&seat| (survival[seat], std::cmp::Reverse(seat)))
+            .ok_or(EscrowError::InvalidWinner)?;
+        
+        let breakdown = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = breakdown.house_fee;
+        let shares =
+            payout::split_by_weight(breakdown.winner_payout, &survival[..seats], winner_index)?;
+        
+        game.winner = Some(game.players[winner_index]);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        for (seat, info) in ctx.remaining_accounts[..seats].iter().enumerate() {
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(token_account.owner == game.payout_owner(seat), EscrowError::WrongPlayer);
+            require!(token_account.mint == game.stake_mint, EscrowError::InvalidMint);
+            if vest_payout(game, seat, shares[seat], &ctx.accounts.config)? {
+                continue;
+            }
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: payee_destination(&token_account, info, pending_claims, game_id, shares[seat])?,
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, shares[seat], ctx.accounts.mint.decimals)?;
+        }
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            breakdown.house_fee,
+            breakdown.fee_waived,
+            rest,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEndedSurvival {
+            game_id,
+            players: game.players[..seats].to_vec(),
+            survival_secs: survival[..seats].to_vec(),
+            payouts: shares[..seats].to_vec(),
+            house_fee: breakdown.house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Preview the settlement breakdown for a game
+    /// Returns the same amounts `finalize_result` would transfer
+    pub fn preview_payout(ctx: Context<PreviewPayout>) -> Result<PayoutBreakdown> {
+        payout::compute_breakdown(
+            &ctx.accounts.game,
+            &ctx.accounts.config,
+            Clock::get()?.unix_timestamp,
+        )
+    }
+
+    /// Cancel a game before it starts - refunds all players
+    /// Only callable by the game creator, or by the template creator for
+    /// a lobby spawned from their template
+    pub fn cancel_game(ctx: Context<CancelGame>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        // Spawned lobbies are created by the template PDA, which cannot sign
+        let creator = match ctx.accounts.template.as_ref() {
+            Some(template) if template.key() == game.creator => template.creator,
+            _ => game.creator,
+        };
+        require!(
+            ctx.accounts.authority.key() == creator,
+            EscrowError::Unauthorized
+        );
+        
+        game.status = GameStatus::Cancelled;
+        game.cancelled_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        game.clear_proposal();
+        delist_lobby(game, ctx.accounts.lobby_registry.as_mut())?;
+        
+        emit!(GameCancelled {
+            game_id: game.game_id,
+        });
+        
+        // Refunds handled separately via refund_player instruction
+        Ok(())
+    }
+
+    /// Expire a lobby that missed its join deadline - refunds all players
+    /// Permissionless so stakes never depend on the creator's goodwill
+    pub fn expire_game(ctx: Context<ExpireGame>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            clock.unix_timestamp > game.join_deadline,
+            EscrowError::JoinDeadlineNotReached
+        );
+        
+        game.status = GameStatus::Cancelled;
+        game.cancelled_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        delist_lobby(game, ctx.accounts.lobby_registry.as_mut())?;
+        
+        emit!(GameExpired {
+            game_id: game.game_id,
+            join_deadline: game.join_deadline,
+        });
+        
+        // Refunds handled separately via refund_player instruction
+        Ok(())
+    }
+
+    /// Claim a stake back from a game that overran `config.max_duration`
+    /// The first claim voids the game so it can no longer be settled;
+    /// remaining players can use this or `refund_player`
+    pub fn claim_timeout_refund(ctx: Context<ClaimTimeoutRefund>, player_index: u8) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        if game.status == GameStatus::Live {
+            require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+            let started_at = game.started_at.ok_or(EscrowError::GameNotLive)?;
+            require!(
+                clock.unix_timestamp > started_at + ctx.accounts.config.max_duration,
+                EscrowError::GameNotTimedOut
+            );
+            
+            game.status = GameStatus::Voided;
+            game.cancelled_at = Some(clock.unix_timestamp);
+            game.last_transition_at = clock.unix_timestamp;
+            
+            emit!(GameTimedOut {
+                game_id: game.game_id,
+                started_at,
+            });
+        }
+        
+        require!(game.status == GameStatus::Voided, EscrowError::GameNotLive);
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        
+        let player = game.players[player_index as usize];
+        require!(ctx.accounts.player.key() == player, EscrowError::WrongPlayer);
+        require!(
+            ctx.accounts.player_token_account.owner == game.refund_owner(player_index as usize),
+            EscrowError::WrongPlayer
+        );
+        
+        let bit = 1u8 << player_index;
+        require!(game.refunded & bit == 0, EscrowError::AlreadyRefunded);
+        game.refunded |= bit;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.refund_of(player_index as usize), ctx.accounts.mint.decimals)?;
+        
+        emit!(PlayerRefunded {
+            game_id,
+            player,
+            amount: game.refund_of(player_index as usize),
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Void a live game - refunds all players with no house fee
+    /// Recovery path when the game server cannot produce a result
+    pub fn void_game(ctx: Context<VoidGame>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        
+        game.status = GameStatus::Voided;
+        game.cancelled_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        game.clear_proposal();
+        
+        emit!(GameVoided {
+            game_id: game.game_id,
+        });
+        
+        // Refunds handled separately via refund_player instruction
+        Ok(())
+    }
+
+    /// Refund a player from a cancelled or voided game
+    pub fn refund_player(ctx: Context<RefundPlayer>, player_index: u8) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(
+            matches!(game.status, GameStatus::Cancelled | GameStatus::Voided),
+            EscrowError::GameNotCancelled
+        );
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        
+        let bit = 1u8 << player_index;
+        require!(game.refunded & bit == 0, EscrowError::AlreadyRefunded);
+        game.refunded |= bit;
+        
+        let player = game.players[player_index as usize];
+        require!(
+            ctx.accounts.player_token_account.owner == game.refund_owner(player_index as usize),
+            EscrowError::WrongPlayer
+        );
+        
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.refund_of(player_index as usize), ctx.accounts.mint.decimals)?;
+        
+        emit!(PlayerRefunded {
+            game_id: game.game_id,
+            player,
+            amount: game.refund_of(player_index as usize),
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Issue a moderation strike against a waiting player, also counted on
+    /// their profile
+    /// Reaching STRIKE_THRESHOLD kicks the player and refunds their stake;
+    /// the refund token account is only needed for that strike
+    pub fn issue_strike(
+        ctx: Context<IssueStrike>,
+        player_index: u8,
+        reason_code: u8,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        
+        let index = player_index as usize;
+        let player = game.players[index];
+        require_keys_eq!(ctx.accounts.player.key(), player, EscrowError::WrongPlayer);
+        
+        let action_nonce = game.consume_nonce(expected_nonce)?;
+        let strikes = game.add_strike(index);
+        
+        let profile = &mut ctx.accounts.profile;
+        profile.player = player;
+        profile.bump = ctx.bumps.profile;
+        profile.strikes = profile.strikes.checked_add(1).ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(StrikeIssued {
+            game_id: game.game_id,
+            player,
+            reason_code,
+            strikes,
+            action_nonce,
+        });
+        
+        if strikes < STRIKE_THRESHOLD {
+            return Ok(());
+        }
+        
+        // Threshold reached - same refund-and-unseat flow as kick_player
+        let player_token_account = ctx
+            .accounts
+            .player_token_account
+            .as_ref()
+            .ok_or(EscrowError::RefundAccountRequired)?;
+        require!(
+            player_token_account.owner == game.refund_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let refunded = unseat_and_refund(
+            game,
+            &ctx.accounts.vault,
+            player_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.bumps.vault,
+            index,
+        )?;
+        
+        emit!(PlayerAutoKicked {
+            game_id: game.game_id,
+            player,
+            strikes,
+            refunded,
+            action_nonce,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create a parlay - locks one deposit used to stake into several games
+    pub fn create_parlay(
+        ctx: Context<CreateParlay>,
+        total_amount: u64,
+        max_stake: u64,
+        games_remaining: u8,
+    ) -> Result<()> {
+        require!(
+            games_remaining > 0 && games_remaining as usize <= MAX_PARLAY_GAMES,
+            EscrowError::InvalidParlayGames
+        );
+        require!(
+            max_stake > 0 && total_amount >= max_stake,
+            EscrowError::InvalidParlayAmount
+        );
+        
+        let parlay = &mut ctx.accounts.parlay;
+        parlay.owner = ctx.accounts.owner.key();
+        parlay.max_stake = max_stake;
+        parlay.games_remaining = games_remaining;
+        parlay.entered_count = 0;
+        parlay.entered_games = [[0u8; 8]; MAX_PARLAY_GAMES];
+        parlay.bump = ctx.bumps.parlay;
+        parlay.vault_bump = ctx.bumps.parlay_vault;
+        
+        // Fund the parlay vault
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+            to: ctx.accounts.parlay_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, total_amount, ctx.accounts.usdc_mint.decimals)?;
+        
+        emit!(ParlayCreated {
+            owner: parlay.owner,
+            total_amount,
+            max_stake,
+            games_remaining,
+        });
+        
+        Ok(())
+    }
+
+    /// Join a game with the stake pulled from the caller's parlay vault
+    pub fn join_with_parlay(
+        ctx: Context<JoinWithParlay>,
+        invite_code: Option<Vec<u8>>,
+        commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let parlay = &mut ctx.accounts.parlay;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            clock.unix_timestamp <= game.join_cutoff(),
+            EscrowError::JoinDeadlinePassed
+        );
+        require!(!game.usd_stake, EscrowError::UsdStakeUnsupported);
+        parlay.enter(
+            game.game_id,
+            game.stake_amount,
+            ctx.accounts.parlay_vault.amount,
+        )?;
+        
+        let player = parlay.owner;
+        let credentials = JoinCredentials {
+            allowlist: ctx.accounts.allowlist.as_ref(),
+            invite_code: invite_code.as_deref(),
+            nft_token_account: ctx.accounts.nft_token_account.as_ref(),
+            nft_metadata: ctx.accounts.nft_metadata.as_ref(),
+            gate_token_account: ctx.accounts.gate_token_account.as_ref(),
+            profile: Some(&ctx.accounts.profile),
+        };
+        check_join_gates(game, &credentials, &player)?;
+        let seat = game.seat_player(player, clock.unix_timestamp)?;
+        game.commit_entropy(seat, commitment)?;
+        apply_withdrawal_addresses(game, seat, &ctx.accounts.profile)?;
+        // The parlay vault sponsors the seat, so refunds of the stake go
+        // back into the parlay rather than to the owner's wallet
+        game.stake_sponsors[seat] = ctx.accounts.parlay_vault.key();
+        
+        // Stake leaves the parlay vault immediately, so the remaining
+        // balance can never be committed to more than one game at once
+        let owner = parlay.owner;
+        let seeds = &[
+            b"parlay_vault",
+            owner.as_ref(),
+            &[parlay.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.parlay_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.parlay_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(PlayerJoined {
+            game_id: game.game_id,
+            player,
+            player_count: game.player_count,
+        });
+        
+        emit!(ParlayEntered {
+            owner,
+            game_id: game.game_id,
+            stake_amount: game.stake_amount,
+            games_remaining: parlay.games_remaining,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Close a parlay - refunds the unused balance to the owner
+    /// Every entered game must be passed as a remaining account; none the
+    /// parlay still holds a seat in may be Waiting or Live, and cancelled
+    /// or voided ones must have refunded the parlay first
+    pub fn close_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseParlay<'info>>,
+    ) -> Result<()> {
+        let parlay = &ctx.accounts.parlay;
+        let vault = ctx.accounts.parlay_vault.key();
+        
+        for entered in &parlay.entered_games[..parlay.entered_count as usize] {
+            let mut found = false;
+            for info in ctx.remaining_accounts.iter() {
+                let game: Account<Game> = Account::try_from(info)?;
+                if game.game_id == *entered {
+                    parlay.check_settled(info.key, &game, &vault)?;
+                    found = true;
+                    break;
+                }
+            }
+            require!(found, EscrowError::ParlayGameMissing);
+        }
+        
+        let owner = parlay.owner;
+        let seeds = &[
+            b"parlay_vault",
+            owner.as_ref(),
+            &[parlay.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let refunded = ctx.accounts.parlay_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.parlay_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.parlay_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, refunded, ctx.accounts.mint.decimals)?;
+        
+        let cpi_accounts_close = CloseAccount {
+            account: ctx.accounts.parlay_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.parlay_vault.to_account_info(),
+        };
+        let cpi_ctx_close = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_close,
+            signer,
+        );
+        token_interface::close_account(cpi_ctx_close)?;
+        
+        emit!(ParlayClosed {
+            owner,
+            refunded,
+        });
+        
+        Ok(())
+    }
+
+    /// Recompute the abnormal states a game is sitting in
+    /// Permissionless - flags that no longer hold are cleared, so a game
+    /// that recovered is unflagged by the next call
+    pub fn flag_stuck(ctx: Context<FlagStuck>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let flags = game.stuck_flags_at(now)?;
+        game.stuck_flags = flags;
+        
+        emit!(GameFlagged {
+            game_id: game.game_id,
+            status: game.status,
+            flags,
+        });
+        
+        Ok(())
+    }
+
+    /// Create a heads-up duel - the creator stakes immediately
+    pub fn create_duel(
+        ctx: Context<CreateDuel>,
+        duel_id: [u8; 8],
+        stake_amount: u64,
+        fee_bps: u16,
+    ) -> Result<()> {
+        let duel = &mut ctx.accounts.duel;
+        let clock = Clock::get()?;
+        
+        require!(
+            fee_bps <= ctx.accounts.config.max_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        
+        duel.duel_id = duel_id;
+        duel.players = [ctx.accounts.creator.key(), Pubkey::default()];
+        duel.stake_amount = stake_amount;
+        duel.fee_bps = fee_bps;
+        duel.status = GameStatus::Waiting;
+        duel.seed = [0u8; 32];
+        duel.winner = None;
+        duel.created_at = clock.unix_timestamp;
+        duel.started_at = None;
+        duel.stake_mint = ctx.accounts.usdc_mint.key();
+        duel.vault_balance = 0;
+        duel.bump = ctx.bumps.duel;
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, stake_amount, ctx.accounts.usdc_mint.decimals)?;
+        
+        emit!(DuelCreated {
+            duel_id,
+            creator: duel.players[0],
+            stake_amount,
+            fee_bps,
+        });
+        
+        ctx.accounts.vault_ledger.settle_duel(duel, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Take the open seat in a duel and start it in one step
+    pub fn join_and_start_duel(ctx: Context<JoinAndStartDuel>) -> Result<()> {
+        let duel = &mut ctx.accounts.duel;
+        let clock = Clock::get()?;
+        
+        require!(duel.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        
+        let opponent = ctx.accounts.opponent.key();
+        require!(duel.players[0] != opponent, EscrowError::AlreadyJoined);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.opponent_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.opponent.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, duel.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        duel.players[1] = opponent;
+        duel.seed = derive_seed(&duel.duel_id, &clock, &ctx.accounts.slot_hashes)?;
+        duel.status = GameStatus::Live;
+        duel.started_at = Some(clock.unix_timestamp);
+        
+        emit!(DuelStarted {
+            duel_id: duel.duel_id,
+            opponent,
+            seed: duel.seed,
+            started_at: clock.unix_timestamp,
+        });
+        
+        ctx.accounts.vault_ledger.settle_duel(duel, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Settle a duel - pays the winner and the house immediately
+    /// Only callable by authorized backend (game authority)
+    pub fn end_duel<'info>(ctx: Context<'_, '_, 'info, 'info, EndDuel<'info>>, winner_index: u8) -> Result<()> {
+        let duel = &mut ctx.accounts.duel;
+        let clock = Clock::get()?;
+        
+        require!(duel.status == GameStatus::Live, EscrowError::GameNotLive);
+        // Duel results carry no enclave report or referee signatures, so a
+        // deployment that requires them settles only through games
+        require!(ctx.accounts.config.tee.is_none(), EscrowError::AttestationRequired);
+        require!(ctx.accounts.config.referees.is_none(), EscrowError::RefereeQuorumNotMet);
+        require!(winner_index < 2, EscrowError::InvalidWinner);
+        
+        let winner = duel.players[winner_index as usize];
+        require!(
+            ctx.accounts.winner_token_account.owner == winner,
+            EscrowError::WrongPlayer
+        );
+        
+        duel.winner = Some(winner);
+        duel.status = GameStatus::Finished;
+        
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_duel_breakdown(duel, &ctx.accounts.config, clock.unix_timestamp)?;
+        
+        let duel_id = duel.duel_id;
+        let stake_mint = duel.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, winner_payout, ctx.accounts.mint.decimals)?;
+        
+        distribute_house_fee(
+            house_fee,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            ctx.remaining_accounts,
+            &ctx.accounts.config,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        emit!(DuelEnded {
+            duel_id,
+            winner,
+            winner_payout,
+            house_fee,
+        });
+        
+        if fee_waived > 0 {
+            emit!(RakeWaived {
+                id: duel_id,
+                fee_waived,
+            });
+        }
+        
+        ctx.accounts.vault_ledger.settle_duel(duel, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Cancel a duel nobody has joined - refunds the creator and closes it
+    pub fn cancel_duel(ctx: Context<CancelDuel>) -> Result<()> {
+        let duel = &mut ctx.accounts.duel;
+        
+        require!(duel.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        
+        let duel_id = duel.duel_id;
+        let stake_mint = duel.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, duel.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(DuelCancelled {
+            duel_id,
+        });
+        
+        ctx.accounts.vault_ledger.settle_duel(duel, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Refund both stakes of a duel that overran `config.max_duration`
+    /// without being settled - permissionless, like `claim_timeout_refund`
+    /// for games; the duel is voided so it can no longer be settled
+    pub fn claim_duel_timeout_refund(ctx: Context<ClaimDuelTimeoutRefund>) -> Result<()> {
+        let duel = &mut ctx.accounts.duel;
+        let clock = Clock::get()?;
+        
+        require!(duel.status == GameStatus::Live, EscrowError::GameNotLive);
+        let started_at = duel.started_at.ok_or(EscrowError::GameNotLive)?;
+        require!(
+            clock.unix_timestamp > started_at + ctx.accounts.config.max_duration,
+            EscrowError::GameNotTimedOut
+        );
+        duel.status = GameStatus::Voided;
+        
+        let duel_id = duel.duel_id;
+        let stake_mint = duel.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        for player_token_account in [
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.opponent_token_account,
+        ] {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: player_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, duel.stake_amount, ctx.accounts.mint.decimals)?;
+        }
+        
+        emit!(DuelTimedOut {
+            duel_id,
+            started_at,
+        });
+        
+        ctx.accounts.vault_ledger.settle_duel(duel, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create a best-of-N series - one buy-in covers every linked game
+    pub fn create_series(
+        ctx: Context<CreateSeries>,
+        series_id: [u8; 8],
+        params: CreateSeriesParams,
+    ) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let clock = Clock::get()?;
+        
+        require!(
+            params.fee_bps <= ctx.accounts.config.max_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        require!(
+            params.max_players >= MIN_PLAYERS && params.max_players as usize <= MAX_PLAYERS,
+            EscrowError::InvalidPlayerLimits
+        );
+        require!(params.wins_required > 0, EscrowError::InvalidSeries);
+        
+        series.series_id = series_id;
+        series.creator = ctx.accounts.creator.key();
+        series.buy_in = params.buy_in;
+        series.fee_bps = params.fee_bps;
+        series.max_players = params.max_players;
+        series.wins_required = params.wins_required;
+        series.player_count = 0;
+        series.players = [Pubkey::default(); MAX_PLAYERS];
+        series.wins = [0u8; MAX_PLAYERS];
+        series.games_played = 0;
+        series.status = GameStatus::Waiting;
+        series.seed = [0u8; 32];
+        series.winner = None;
+        series.refunded = 0;
+        series.action_nonce = 0;
+        series.created_at = clock.unix_timestamp;
+        series.current_game = None;
+        series.last_recorded_at = clock.unix_timestamp;
+        series.bump = ctx.bumps.series;
+        
+        emit!(SeriesCreated {
+            series_id,
+            creator: series.creator,
+            buy_in: params.buy_in,
+            max_players: params.max_players,
+            wins_required: params.wins_required,
+        });
+        
+        Ok(())
+    }
+
+    /// Join a series - transfers the buy-in to the series vault
+    pub fn join_series(ctx: Context<JoinSeries>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        
+        require!(series.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(series.player_count < series.max_players, EscrowError::GameFull);
+        
+        let player = ctx.accounts.player.key();
+        require!(
+            !series.players[..series.player_count as usize].contains(&player),
+            EscrowError::AlreadyJoined
+        );
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, series.buy_in, ctx.accounts.mint.decimals)?;
+        
+        let index = series.player_count as usize;
+        series.players[index] = player;
+        series.player_count += 1;
+        
+        emit!(SeriesJoined {
+            series_id: series.series_id,
+            player,
+            player_count: series.player_count,
+        });
+        
+        Ok(())
+    }
+
+    /// Start a full series - commits the seed for its first game
+    pub fn start_series(ctx: Context<ManageSeries>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let clock = Clock::get()?;
+        
+        require!(series.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            series.player_count == series.max_players,
+            EscrowError::NotEnoughPlayers
+        );
+        
+        series.status = GameStatus::Live;
+        series.seed = derive_seed(&series.series_id, &clock, &ctx.accounts.slot_hashes)?;
+        series.last_recorded_at = clock.unix_timestamp;
+        
+        emit!(SeriesGameStarted {
+            series_id: series.series_id,
+            game_number: 0,
+            seed: series.seed,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the series' current game between its players - a stake-free
+    /// Game that starts immediately on the seed the series committed
+    /// Its result is read back from the Game by `record_series_game`
+    pub fn create_series_game(ctx: Context<CreateSeriesGame>) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
+        let series = &mut ctx.accounts.series;
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(series.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(series.winner.is_none(), EscrowError::SeriesDecided);
+        require!(series.current_game.is_none(), EscrowError::SeriesGameInProgress);
+        
+        let seats = series.player_count as usize;
+        game.init(
+            game_id,
+            ctx.accounts.authority.key(),
+            ctx.accounts.usdc_mint.key(),
+            series.player_count,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.payout_schedule[0] = BPS_DENOMINATOR as u16;
+        game.player_count = series.player_count;
+        game.players[..seats].copy_from_slice(&series.players[..seats]);
+        game.status = GameStatus::Live;
+        game.seed = series.seed;
+        game.started_at = Some(clock.unix_timestamp);
+        series.current_game = Some(game.key());
+        
+        emit!(GameStarted {
+            game_id,
+            seed: game.seed,
+            started_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Record the series' current game once it has finished - permissionless,
+    /// the winner is read from the Game account
+    /// Clinching the series fixes the winner; otherwise the next game's
+    /// seed is committed
+    pub fn record_series_game(ctx: Context<RecordSeriesGame>, expected_nonce: u64) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let game = &ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(series.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(series.winner.is_none(), EscrowError::SeriesDecided);
+        require!(
+            series.current_game == Some(game.key()),
+            EscrowError::InvalidSeriesGame
+        );
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        
+        let game_winner = game.winner.ok_or(EscrowError::InvalidWinner)?;
+        let index = series.players[..series.player_count as usize]
+            .iter()
+            .position(|player| *player == game_winner)
+            .ok_or(EscrowError::InvalidWinner)?;
+        
+        let action_nonce = series.consume_nonce(expected_nonce)?;
+        series.current_game = None;
+        series.last_recorded_at = clock.unix_timestamp;
+        series.wins[index] = series.wins[index]
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        series.games_played = series
+            .games_played
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(SeriesGameRecorded {
+            series_id: series.series_id,
+            game_number: series.games_played - 1,
+            winner: game_winner,
+            wins: series.wins[index],
+            action_nonce,
+        });
+        
+        if series.wins[index] >= series.wins_required {
+            series.winner = Some(game_winner);
+        } else {
+            series.seed = derive_seed(&series.series_id, &clock, &ctx.accounts.slot_hashes)?;
+            emit!(SeriesGameStarted {
+                series_id: series.series_id,
+                game_number: series.games_played,
+                seed: series.seed,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Pay out a decided series to its winner and the house
+    pub fn settle_series<'info>(ctx: Context<'_, '_, 'info, 'info, SettleSeries<'info>>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let clock = Clock::get()?;
+        
+        require!(series.status == GameStatus::Live, EscrowError::GameNotLive);
+        let winner = series.winner.ok_or(EscrowError::SeriesNotDecided)?;
+        require!(
+            ctx.accounts.winner_token_account.owner == winner,
+            EscrowError::WrongPlayer
+        );
+        
+        series.status = GameStatus::Finished;
+        
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_series_breakdown(series, &ctx.accounts.config, clock.unix_timestamp)?;
+        
+        let series_id = series.series_id;
+        let seeds = &[
+            b"series_vault",
+            series_id.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, winner_payout, ctx.accounts.mint.decimals)?;
+        
+        distribute_house_fee(
+            house_fee,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            ctx.remaining_accounts,
+            &ctx.accounts.config,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        emit!(SeriesEnded {
+            series_id,
+            winner,
+            games_played: series.games_played,
+            winner_payout,
+            house_fee,
+        });
+        
+        if fee_waived > 0 {
+            emit!(RakeWaived {
+                id: series_id,
+                fee_waived,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Cancel a series before it starts - refunds handled by
+    /// `refund_series_player`
+    pub fn cancel_series(ctx: Context<CancelSeries>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        
+        require!(series.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            ctx.accounts.authority.key() == series.creator,
+            EscrowError::Unauthorized
+        );
+        
+        series.status = GameStatus::Cancelled;
+        
+        emit!(SeriesCancelled {
+            series_id: series.series_id,
+        });
+        
+        Ok(())
+    }
+
+    /// Void a live series that has not recorded a game within
+    /// `config.max_duration` - buy-ins are then refunded through
+    /// `refund_series_player`
+    /// Permissionless, so a stalled series cannot lock the buy-ins
+    pub fn void_series(ctx: Context<VoidSeries>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let clock = Clock::get()?;
+        
+        require!(series.status == GameStatus::Live, EscrowError::GameNotLive);
+        // A decided series is paid out by `settle_series` instead
+        require!(series.winner.is_none(), EscrowError::SeriesDecided);
+        require!(
+            clock.unix_timestamp > series.last_recorded_at + ctx.accounts.config.max_duration,
+            EscrowError::GameNotTimedOut
+        );
+        
+        series.status = GameStatus::Voided;
+        
+        emit!(SeriesTimedOut {
+            series_id: series.series_id,
+            games_played: series.games_played,
+        });
+        
+        Ok(())
+    }
+
+    /// Refund a player's buy-in from a cancelled or voided series
+    pub fn refund_series_player(ctx: Context<RefundSeriesPlayer>, player_index: u8) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        
+        require!(
+            matches!(series.status, GameStatus::Cancelled | GameStatus::Voided),
+            EscrowError::GameNotCancelled
+        );
+        require!(player_index < series.player_count, EscrowError::InvalidPlayer);
+        
+        let bit = 1u8 << player_index;
+        require!(series.refunded & bit == 0, EscrowError::AlreadyRefunded);
+        series.refunded |= bit;
+        
+        let player = series.players[player_index as usize];
+        require!(
+            ctx.accounts.player_token_account.owner == player,
+            EscrowError::WrongPlayer
+        );
+        
+        let series_id = series.series_id;
+        let seeds = &[
+            b"series_vault",
+            series_id.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, series.buy_in, ctx.accounts.mint.decimals)?;
+        
+        emit!(SeriesPlayerRefunded {
+            series_id,
+            player,
+            amount: series.buy_in,
+        });
+        
+        Ok(())
+    }
+
+    /// Create a single-elimination tournament
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: [u8; 8],
+        entry_fee: u64,
+        fee_bps: u16,
+        max_entrants: u8,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        require!(
+            fee_bps <= ctx.accounts.config.max_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        require!(
+            max_entrants >= 2
+                && max_entrants.is_power_of_two()
+                && max_entrants as usize <= MAX_TOURNAMENT_ENTRANTS,
+            EscrowError::InvalidTournament
+        );
+        
+        tournament.tournament_id = tournament_id;
+        tournament.creator = ctx.accounts.creator.key();
+        tournament.entry_fee = entry_fee;
+        tournament.fee_bps = fee_bps;
+        tournament.max_entrants = max_entrants;
+        tournament.entrant_count = 0;
+        tournament.entrants = [Pubkey::default(); MAX_TOURNAMENT_ENTRANTS];
+        tournament.alive = 0;
+        tournament.round = 0;
+        tournament.status = GameStatus::Waiting;
+        tournament.champion = None;
+        tournament.refunded = 0;
+        tournament.created_at = clock.unix_timestamp;
+        tournament.last_advanced_at = clock.unix_timestamp;
+        tournament.bump = ctx.bumps.tournament;
+        
+        emit!(TournamentCreated {
+            tournament_id,
+            creator: tournament.creator,
+            entry_fee,
+            max_entrants,
+        });
+        
+        Ok(())
+    }
+
+    /// Enter a tournament - transfers the entry fee to the tournament vault
+    pub fn join_tournament(ctx: Context<JoinTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        
+        require!(tournament.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            tournament.entrant_count < tournament.max_entrants,
+            EscrowError::GameFull
+        );
+        
+        let player = ctx.accounts.player.key();
+        require!(
+            !tournament.entrants[..tournament.entrant_count as usize].contains(&player),
+            EscrowError::AlreadyJoined
+        );
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, tournament.entry_fee, ctx.accounts.mint.decimals)?;
+        
+        let index = tournament.entrant_count as usize;
+        tournament.entrants[index] = player;
+        tournament.entrant_count += 1;
+        
+        emit!(TournamentJoined {
+            tournament_id: tournament.tournament_id,
+            player,
+            entrant_count: tournament.entrant_count,
+        });
+        
+        Ok(())
+    }
+
+    /// Start a full tournament - every entrant is alive in round 0
+    pub fn start_tournament(ctx: Context<StartTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            tournament.entrant_count == tournament.max_entrants,
+            EscrowError::NotEnoughPlayers
+        );
+        
+        tournament.status = GameStatus::Live;
+        tournament.alive = ((1u32 << tournament.entrant_count) - 1) as u16;
+        tournament.last_advanced_at = clock.unix_timestamp;
+        
+        emit!(TournamentStarted {
+            tournament_id: tournament.tournament_id,
+            entrants: tournament.entrant_count,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the bracket match between two alive entrants of the current
+    /// round; the match is a stake-free Game that starts immediately
+    /// Pairs are consecutive alive entrants in entry order, so the winners
+    /// of neighbouring matches meet in the next round
+    /// Remaining accounts: both players' profiles, in seat order
+    pub fn create_tournament_match(
+        ctx: Context<CreateTournamentMatch>,
+        first: u8,
+        second: u8,
+    ) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
+        let tournament = &ctx.accounts.tournament;
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(tournament.champion.is_none(), EscrowError::InvalidTournament);
+        require!(
+            tournament.is_bracket_pair(first, second),
+            EscrowError::InvalidBracketMatch
+        );
+        
+        game.init(
+            game_id,
+            ctx.accounts.authority.key(),
+            ctx.accounts.usdc_mint.key(),
+            2,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.payout_schedule[0] = BPS_DENOMINATOR as u16;
+        game.player_count = 2;
+        game.players[0] = tournament.entrants[first as usize];
+        game.players[1] = tournament.entrants[second as usize];
+        require!(
+            ctx.remaining_accounts.len() == 2,
+            EscrowError::ProfileAccountsRequired
+        );
+        for (seat, profile) in ctx.remaining_accounts.iter().enumerate() {
+            apply_withdrawal_addresses(game, seat, profile)?;
+        }
+        game.status = GameStatus::Live;
+        game.seed = derive_seed(&game_id, &clock, &ctx.accounts.slot_hashes)?;
+        game.started_at = Some(clock.unix_timestamp);
+        game.tournament = Some(tournament.key());
+        game.tournament_round = tournament.round;
+        
+        emit!(GameStarted {
+            game_id,
+            seed: game.seed,
+            started_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Advance the bracket from a settled tournament match
+    /// Permissionless - the match result is read from the Game account
+    pub fn advance_tournament(ctx: Context<AdvanceTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let game = &ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(
+            game.tournament == Some(tournament.key()),
+            EscrowError::InvalidBracketMatch
+        );
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(
+            game.tournament_round == tournament.round,
+            EscrowError::InvalidBracketMatch
+        );
+        
+        let winner = game.winner.ok_or(EscrowError::InvalidWinner)?;
+        let loser = if game.players[0] == winner {
+            game.players[1]
+        } else {
+            game.players[0]
+        };
+        let winner_index = tournament
+            .entrant_index(&winner)
+            .ok_or(EscrowError::InvalidBracketMatch)?;
+        let loser_index = tournament
+            .entrant_index(&loser)
+            .ok_or(EscrowError::InvalidBracketMatch)?;
+        
+        // Both must still be alive - a match can only advance once
+        require!(
+            tournament.alive & (1 << winner_index) != 0
+                && tournament.alive & (1 << loser_index) != 0,
+            EscrowError::InvalidBracketMatch
+        );
+        tournament.alive &= !(1u16 << loser_index);
+        tournament.last_advanced_at = clock.unix_timestamp;
+        
+        let remaining = tournament.alive.count_ones();
+        if remaining == 1 {
+            tournament.champion = Some(winner);
+        } else if remaining == (tournament.entrant_count as u32) >> (tournament.round + 1) {
+            tournament.round += 1;
+        }
+        
+        emit!(TournamentAdvanced {
+            tournament_id: tournament.tournament_id,
+            round: game.tournament_round,
+            winner,
+            eliminated: loser,
+        });
+        
+        Ok(())
+    }
+
+    /// Pay the tournament prize pool to the champion
+    pub fn finalize_tournament<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeTournament<'info>>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == GameStatus::Live, EscrowError::GameNotLive);
+        let champion = tournament.champion.ok_or(EscrowError::InvalidTournament)?;
+        require!(
+            ctx.accounts.champion_token_account.owner == champion,
+            EscrowError::WrongPlayer
+        );
+        
+        tournament.status = GameStatus::Finished;
+        
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_tournament_breakdown(tournament, &ctx.accounts.config, clock.unix_timestamp)?;
+        
+        let tournament_id = tournament.tournament_id;
+        let seeds = &[
+            b"tournament_vault",
+            tournament_id.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.champion_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, winner_payout, ctx.accounts.mint.decimals)?;
+        
+        distribute_house_fee(
+            house_fee,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            ctx.remaining_accounts,
+            &ctx.accounts.config,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        emit!(TournamentEnded {
+            tournament_id,
+            champion,
+            prize: winner_payout,
+            house_fee,
+        });
+        
+        if fee_waived > 0 {
+            emit!(RakeWaived {
+                id: tournament_id,
+                fee_waived,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Cancel a tournament before it starts - refunds handled by
+    /// `refund_tournament_entrant`
+    pub fn cancel_tournament(ctx: Context<CancelTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        
+        require!(tournament.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            ctx.accounts.authority.key() == tournament.creator,
+            EscrowError::Unauthorized
+        );
+        
+        tournament.status = GameStatus::Cancelled;
+        
+        emit!(TournamentCancelled {
+            tournament_id: tournament.tournament_id,
+        });
+        
+        Ok(())
+    }
+
+    /// Void a live tournament whose bracket has not advanced within
+    /// `config.max_duration` - entry fees are then refunded through
+    /// `refund_tournament_entrant`
+    /// Permissionless, so a stalled bracket cannot lock the entry fees
+    pub fn void_tournament(ctx: Context<VoidTournament>) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let clock = Clock::get()?;
+        
+        require!(tournament.status == GameStatus::Live, EscrowError::GameNotLive);
+        // A decided bracket is paid out by `finalize_tournament` instead
+        require!(tournament.champion.is_none(), EscrowError::InvalidTournament);
+        require!(
+            clock.unix_timestamp > tournament.last_advanced_at + ctx.accounts.config.max_duration,
+            EscrowError::GameNotTimedOut
+        );
+        
+        tournament.status = GameStatus::Voided;
+        
+        emit!(TournamentTimedOut {
+            tournament_id: tournament.tournament_id,
+            round: tournament.round,
+        });
+        
+        Ok(())
+    }
+
+    /// Refund an entrant's entry fee from a cancelled or voided tournament
+    pub fn refund_tournament_entrant(
+        ctx: Context<RefundTournamentEntrant>,
+        entrant_index: u8,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        
+        require!(
+            matches!(tournament.status, GameStatus::Cancelled | GameStatus::Voided),
+            EscrowError::GameNotCancelled
+        );
+        require!(entrant_index < tournament.entrant_count, EscrowError::InvalidPlayer);
+        
+        let bit = 1u16 << entrant_index;
+        require!(tournament.refunded & bit == 0, EscrowError::AlreadyRefunded);
+        tournament.refunded |= bit;
+        
+        let player = tournament.entrants[entrant_index as usize];
+        require!(
+            ctx.accounts.player_token_account.owner == player,
+            EscrowError::WrongPlayer
+        );
+        
+        let tournament_id = tournament.tournament_id;
+        let seeds = &[
+            b"tournament_vault",
+            tournament_id.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, tournament.entry_fee, ctx.accounts.mint.decimals)?;
+        
+        emit!(TournamentEntrantRefunded {
+            tournament_id,
+            player,
+            amount: tournament.entry_fee,
+        });
+        
+        Ok(())
+    }
+
+    /// Create a season - games opting in accrue points and a share of
+    /// their rake into the season prize pool
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: [u8; 8],
+        params: CreateSeasonParams,
+    ) -> Result<()> {
+        let season = &mut ctx.accounts.season;
+        let CreateSeasonParams {
+            rake_share_bps,
+            win_points,
+            play_points,
+            starts_at,
+            ends_at,
+            payout_schedule,
+        } = params;
+        
+        require!(
+            starts_at < ends_at && rake_share_bps as u64 <= BPS_DENOMINATOR,
+            EscrowError::InvalidSeason
+        );
+        // Standings are paid by position, so every listed place must be paid
+        require!(
+            !payout_schedule.is_empty()
+                && payout_schedule.len() <= MAX_PLAYERS
+                && !payout_schedule.contains(&0),
+            EscrowError::InvalidPayoutSchedule
+        );
+        let schedule_total: u64 = payout_schedule.iter().map(|&bps| bps as u64).sum();
+        require!(
+            schedule_total == BPS_DENOMINATOR,
+            EscrowError::InvalidPayoutSchedule
+        );
+        
+        season.season_id = season_id;
+        season.rake_share_bps = rake_share_bps;
+        season.win_points = win_points;
+        season.play_points = play_points;
+        season.starts_at = starts_at;
+        season.ends_at = ends_at;
+        season.payout_schedule = [0u16; MAX_PLAYERS];
+        season.payout_schedule[..payout_schedule.len()].copy_from_slice(&payout_schedule);
+        season.status = GameStatus::Live;
+        season.prize_pool = 0;
+        season.entry_count = 0;
+        season.standings = [SeasonStanding::default(); MAX_SEASON_PLAYERS];
+        season.bump = ctx.bumps.season;
+        season.vault_bump = ctx.bumps.vault;
+        
+        emit!(SeasonCreated {
+            season_id,
+            rake_share_bps,
+            starts_at,
+            ends_at,
+            payout_schedule,
+        });
+        
+        Ok(())
+    }
+
+    /// Distribute the season prize pool by final standings
+    /// Token accounts for each paid place are passed as remaining accounts,
+    /// highest standing first; with no ranked players the pool goes to the
+    /// house
+    pub fn finalize_season<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeSeason<'info>>,
+    ) -> Result<()> {
+        let season = &mut ctx.accounts.season;
+        let clock = Clock::get()?;
+        
+        require!(season.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(clock.unix_timestamp >= season.ends_at, EscrowError::SeasonNotEnded);
+        
+        let ranking = season.ranking();
+        let paid_places = season.paid_places();
+        require!(
+            ctx.remaining_accounts.len() == paid_places,
+            EscrowError::InvalidPlacements
+        );
+        
+        season.status = GameStatus::Finished;
+        
+        let prize_pool = ctx.accounts.vault.amount;
+        let payouts = payout::compute_season_payouts(season, prize_pool)?;
+        
+        let season_id = season.season_id;
+        let seeds = &[
+            b"season_vault",
+            season_id.as_ref(),
+            &[season.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let mut winners = Vec::with_capacity(paid_places);
+        for (place, info) in ctx.remaining_accounts.iter().enumerate() {
+            let player = season.standings[ranking[place]].player;
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(token_account.owner == player, EscrowError::WrongPlayer);
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: info.clone(),
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, payouts[place], ctx.accounts.mint.decimals)?;
+            winners.push(player);
+        }
+        
+        if paid_places == 0 && prize_pool > 0 {
+            let cpi_accounts_house = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx_house = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts_house,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx_house, prize_pool, ctx.accounts.mint.decimals)?;
+        }
+        
+        emit!(SeasonFinalized {
+            season_id,
+            prize_pool,
+            winners,
+            payouts: payouts[..paid_places].to_vec(),
+        });
+        
+        Ok(())
+    }
+
+    /// Create a sit-and-go queue for a stake tier
+    pub fn create_queue(
+        ctx: Context<CreateQueue>,
+        stake_amount: u64,
+        fee_bps: u16,
+        join_window: i64,
+    ) -> Result<()> {
+        let queue = &mut ctx.accounts.queue;
+        
+        require!(
+            fee_bps <= ctx.accounts.config.max_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        require!(join_window > 0, EscrowError::InvalidJoinDeadline);
+        
+        queue.stake_amount = stake_amount;
+        queue.fee_bps = fee_bps;
+        queue.join_window = join_window;
+        queue.entrant_count = 0;
+        queue.entrants = [Pubkey::default(); QUEUE_SIZE];
+        queue.games_formed = 0;
+        queue.bump = ctx.bumps.queue;
+        queue.vault_bump = ctx.bumps.vault;
+        
+        emit!(QueueCreated {
+            stake_amount,
+            fee_bps,
+        });
+        
+        Ok(())
+    }
+
+    /// Enter a sit-and-go queue - the stake is held by the queue vault
+    /// until a game is formed
+    pub fn join_queue(ctx: Context<JoinQueue>) -> Result<()> {
+        let queue = &mut ctx.accounts.queue;
+        let player = ctx.accounts.player.key();
+        
+        require!(
+            (queue.entrant_count as usize) < QUEUE_SIZE,
+            EscrowError::GameFull
+        );
+        require!(
+            !queue.entrants[..queue.entrant_count as usize].contains(&player),
+            EscrowError::AlreadyJoined
+        );
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, queue.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        let index = queue.entrant_count as usize;
+        queue.entrants[index] = player;
+        queue.entrant_count += 1;
+        
+        emit!(QueueJoined {
+            stake_amount: queue.stake_amount,
+            player,
+            entrant_count: queue.entrant_count,
+        });
+        
+        Ok(())
+    }
+
+    /// Leave a sit-and-go queue before a game is formed - refunds the stake
+    pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+        let queue = &mut ctx.accounts.queue;
+        let player = ctx.accounts.player.key();
+        
+        let count = queue.entrant_count as usize;
+        let index = queue.entrants[..count]
+            .iter()
+            .position(|p| *p == player)
+            .ok_or(EscrowError::NotInGame)?;
+        
+        queue.entrants.copy_within(index + 1..count, index);
+        queue.entrants[count - 1] = Pubkey::default();
+        queue.entrant_count -= 1;
+        
+        let stake_bytes = queue.stake_amount.to_le_bytes();
+        let seeds = &[
+            b"queue_vault",
+            stake_bytes.as_ref(),
+            &[queue.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, queue.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(QueueLeft {
+            stake_amount: queue.stake_amount,
+            player,
+            entrant_count: queue.entrant_count,
+        });
+        
+        Ok(())
+    }
+
+    /// Form a game from a full sit-and-go queue - permissionless
+    /// Seats every queued entrant and moves their stakes into the new
+    /// game's vault; the game is then started like any other lobby
+    /// Remaining accounts: every entrant's profile, in seat order
+    pub fn form_game(ctx: Context<FormGame>) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
+        let queue = &mut ctx.accounts.queue;
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(
+            queue.entrant_count as usize == QUEUE_SIZE,
+            EscrowError::NotEnoughPlayers
+        );
+        
+        let total = queue
+            .stake_amount
+            .checked_mul(QUEUE_SIZE as u64)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        game.init(
+            game_id,
+            queue.key(),
+            ctx.accounts.usdc_mint.key(),
+            QUEUE_SIZE as u8,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.stake_amount = queue.stake_amount;
+        game.fee_bps = queue.fee_bps;
+        game.payout_schedule[0] = BPS_DENOMINATOR as u16;
+        game.join_deadline = clock
+            .unix_timestamp
+            .checked_add(queue.join_window)
+            .ok_or(EscrowError::MathOverflow)?;
+        game.player_count = QUEUE_SIZE as u8;
+        game.players[..QUEUE_SIZE].copy_from_slice(&queue.entrants);
+        require!(
+            ctx.remaining_accounts.len() == QUEUE_SIZE,
+            EscrowError::ProfileAccountsRequired
+        );
+        for (seat, profile) in ctx.remaining_accounts.iter().enumerate() {
+            apply_withdrawal_addresses(game, seat, profile)?;
+        }
+        
+        let players = queue.entrants.to_vec();
+        queue.entrants = [Pubkey::default(); QUEUE_SIZE];
+        queue.entrant_count = 0;
+        queue.games_formed += 1;
+        
+        let stake_bytes = queue.stake_amount.to_le_bytes();
+        let seeds = &[
+            b"queue_vault",
+            stake_bytes.as_ref(),
+            &[queue.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.queue_vault.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.queue_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, total, ctx.accounts.usdc_mint.decimals)?;
+        
+        emit!(GameCreated {
+            game_id,
+            label: None,
+            creator: game.creator,
+            stake_amount: game.stake_amount,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            fee_bps: game.fee_bps,
+            payout_schedule: vec![BPS_DENOMINATOR as u16],
+            join_deadline: game.join_deadline,
+            scheduled_start: None,
+        });
+        
+        emit!(QueueFormed {
+            stake_amount: queue.stake_amount,
+            game_id,
+            players,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create an open-lobby registry page - permissionless, so a new page
+    /// can be added whenever the existing ones fill up
+    pub fn create_lobby_page(ctx: Context<CreateLobbyPage>, page: u16) -> Result<()> {
+        let registry = &mut ctx.accounts.lobby_registry;
+        
+        registry.page = page;
+        registry.count = 0;
+        registry.lobbies = [Pubkey::default(); LOBBY_PAGE_SIZE];
+        registry.bump = ctx.bumps.lobby_registry;
+        
+        Ok(())
+    }
+
+    /// Create the global game counter that assigns game IDs
+    pub fn initialize_game_counter(ctx: Context<InitializeGameCounter>) -> Result<()> {
+        let game_counter = &mut ctx.accounts.game_counter;
+        
+        game_counter.next_id = 0;
+        game_counter.bump = ctx.bumps.game_counter;
+        
+        Ok(())
+    }
+
+    /// Create a recurring game template - anyone can spawn its lobbies on
+    /// the template's cadence via `spawn_from_template`
+    pub fn create_game_template(
+        ctx: Context<CreateGameTemplate>,
+        template_id: [u8; 8],
+        params: GameTemplateParams,
+    ) -> Result<()> {
+        let template = &mut ctx.accounts.template;
+        let clock = Clock::get()?;
+        let GameTemplateParams {
+            stake_amount,
+            min_players,
+            max_players,
+            fee_bps,
+            payout_schedule,
+            first_start,
+            cadence_secs,
+            lead_secs,
+            expiry_secs,
+        } = params;
+        
+        require!(
+            fee_bps <= ctx.accounts.config.max_fee_bps,
+            EscrowError::InvalidFeeBps
+        );
+        validate_lobby(min_players, max_players, &payout_schedule)?;
+        // Each lobby must be spawned and expired within its own slot of the
+        // cadence, and stay joinable for some time after spawning
+        require!(
+            first_start > clock.unix_timestamp
+                && cadence_secs > 0
+                && lead_secs > SCHEDULED_JOIN_BUFFER_SECS
+                && lead_secs <= cadence_secs
+                && expiry_secs >= 0
+                && expiry_secs <= cadence_secs,
+            EscrowError::InvalidTemplate
+        );
+        
+        template.template_id = template_id;
+        template.creator = ctx.accounts.creator.key();
+        template.stake_amount = stake_amount;
+        template.min_players = min_players;
+        template.max_players = max_players;
+        template.fee_bps = fee_bps;
+        template.payout_schedule = [0u16; MAX_PLAYERS];
+        template.payout_schedule[..payout_schedule.len()].copy_from_slice(&payout_schedule);
+        template.schedule_len = payout_schedule.len() as u8;
+        template.next_start = first_start;
+        template.cadence_secs = cadence_secs;
+        template.lead_secs = lead_secs;
+        template.expiry_secs = expiry_secs;
+        template.games_spawned = 0;
+        template.bump = ctx.bumps.template;
+        
+        emit!(GameTemplateCreated {
+            template_id,
+            creator: template.creator,
+            stake_amount,
+            first_start,
+            cadence_secs,
+        });
+        
+        Ok(())
+    }
+
+    /// Spawn the next scheduled lobby from a template - permissionless
+    /// Callable from `lead_secs` before the next start; the lobby is
+    /// scheduled to start at that time and the template advances by one
+    /// cadence; the template PDA is the lobby's creator, and the template
+    /// creator can cancel it through `cancel_game`
+    pub fn spawn_from_template(ctx: Context<SpawnFromTemplate>) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
+        let template = &mut ctx.accounts.template;
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        // Missed slots are skipped rather than spawned late
+        template.skip_missed(clock.unix_timestamp)?;
+        let scheduled_start = template.next_start;
+        require!(
+            clock.unix_timestamp >= scheduled_start - template.lead_secs,
+            EscrowError::TemplateNotDue
+        );
+        let join_deadline = scheduled_start + template.expiry_secs;
+        
+        game.init(
+            game_id,
+            template.key(),
+            ctx.accounts.usdc_mint.key(),
+            template.max_players,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.label = Some(template.template_id);
+        game.stake_amount = template.stake_amount;
+        game.min_players = template.min_players;
+        game.fee_bps = template.fee_bps;
+        game.payout_schedule
+            .copy_from_slice(&template.payout_schedule[..template.max_players as usize]);
+        game.join_deadline = join_deadline;
+        game.scheduled_start = Some(scheduled_start);
+        game.lobby_page = Some(ctx.accounts.lobby_registry.page);
+        
+        let game_key = game.key();
+        ctx.accounts.lobby_registry.insert(game_key)?;
+        pay_listing_fee(
+            &ctx.accounts.config,
+            &ctx.accounts.payer,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+        )?;
+        
+        template.advance()?;
+        
+        emit!(GameCreated {
+            game_id,
+            label: game.label,
+            creator: game.creator,
+            stake_amount: game.stake_amount,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            fee_bps: game.fee_bps,
+            payout_schedule: template.payout_schedule[..template.schedule_len as usize].to_vec(),
+            join_deadline,
+            scheduled_start: Some(scheduled_start),
+        });
+        
+        Ok(())
+    }
+
+    /// Retire a game template - only the template creator
+    /// Lobbies already spawned are unaffected
+    pub fn close_game_template(ctx: Context<CloseGameTemplate>) -> Result<()> {
+        emit!(GameTemplateClosed {
+            template_id: ctx.accounts.template.template_id,
+        });
+        
+        Ok(())
+    }
+
+    /// Make a waiting game private - only wallets on the allowlist can
+    /// take a seat from now on
+    pub fn create_allowlist(ctx: Context<CreateAllowlist>, entries: Vec<Pubkey>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let allowlist = &mut ctx.accounts.allowlist;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        
+        allowlist.game = game.key();
+        allowlist.bump = ctx.bumps.allowlist;
+        allowlist.set_entries(&entries)?;
+        game.allowlisted = true;
+        
+        emit!(AllowlistUpdated {
+            game_id: game.game_id,
+            entries,
+        });
+        
+        Ok(())
+    }
+
+    /// Replace the allowlist of a waiting private game
+    /// Players already seated keep their seats
+    pub fn update_allowlist(ctx: Context<UpdateAllowlist>, entries: Vec<Pubkey>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let allowlist = &mut ctx.accounts.allowlist;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        
+        allowlist.set_entries(&entries)?;
+        
+        emit!(AllowlistUpdated {
+            game_id: game.game_id,
+            entries,
+        });
+        
+        Ok(())
+    }
+
+    /// Fund a game's prize pool from the house treasury (admin only)
+    /// A game with `stake_amount = 0` funded this way is a freeroll; the
+    /// funded prize is paid out with the stakes and never raked
+    pub fn fund_prize(ctx: Context<FundPrize>, amount: u64) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"treasury",
+            mint_key.as_ref(),
+            &[ctx.bumps.treasury],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.treasury.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        game.funded_prize = game
+            .funded_prize
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(PrizeFunded {
+            game_id: game.game_id,
+            amount,
+            funded_prize: game.funded_prize,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Return the funded prize of a cancelled or voided game to the house
+    /// Permissionless - the funds can only go back to the treasury
+    pub fn reclaim_prize(ctx: Context<ReclaimPrize>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(
+            game.status == GameStatus::Cancelled || game.status == GameStatus::Voided,
+            EscrowError::GameNotCancelled
+        );
+        require!(game.funded_prize > 0, EscrowError::InvalidPrizeAmount);
+        
+        let amount = game.funded_prize;
+        game.funded_prize = 0;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(PrizeReclaimed {
+            game_id,
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Add tokens to a waiting game's prize pool - any wallet may sponsor
+    /// Contributions are paid out with the stakes, never raked, and can be
+    /// reclaimed if the game is cancelled or voided
+    pub fn add_to_pot(ctx: Context<AddToPot>, amount: u64) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let contribution = &mut ctx.accounts.contribution;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.sponsor_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.sponsor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        contribution.game = game.key();
+        contribution.sponsor = ctx.accounts.sponsor.key();
+        contribution.amount = contribution
+            .amount
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        contribution.bump = ctx.bumps.contribution;
+        game.sponsored_pot = game
+            .sponsored_pot
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(PotSponsored {
+            game_id: game.game_id,
+            sponsor: contribution.sponsor,
+            amount,
+            sponsored_pot: game.sponsored_pot,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Return a sponsor's contribution from a cancelled or voided game
+    /// Permissionless - funds go to the sponsor's own token account
+    pub fn reclaim_contribution(ctx: Context<ReclaimContribution>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let amount = ctx.accounts.contribution.amount;
+        
+        require!(
+            game.status == GameStatus::Cancelled || game.status == GameStatus::Voided,
+            EscrowError::GameNotCancelled
+        );
+        require!(
+            ctx.accounts.sponsor_token_account.owner == ctx.accounts.sponsor.key(),
+            EscrowError::WrongPlayer
+        );
+        
+        game.sponsored_pot -= amount;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sponsor_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(ContributionReclaimed {
+            game_id,
+            sponsor: ctx.accounts.sponsor.key(),
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create a rematch of a finished game - the caller must be one of its
+    /// players and takes the first seat, staking immediately
+    /// The new lobby copies the previous stake, fee and payout schedule and
+    /// is allowlisted to the previous players, who re-stake via `join_game`
+    pub fn create_rematch(
+        ctx: Context<CreateRematch>,
+        join_deadline: i64,
+        commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let game_id = ctx.accounts.game_counter.take_id()?;
+        let previous = &ctx.accounts.previous_game;
+        let game = &mut ctx.accounts.game;
+        let allowlist = &mut ctx.accounts.allowlist;
+        let clock = Clock::get()?;
+        let player = ctx.accounts.player.key();
+        
+        require!(previous.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(previous.seat_of(&player).is_some(), EscrowError::NotInGame);
+        require!(!previous.usd_stake, EscrowError::UsdStakeUnsupported);
+        require!(
+            join_deadline > clock.unix_timestamp,
+            EscrowError::InvalidJoinDeadline
+        );
+        
+        let previous_players = &previous.players[..previous.player_count as usize];
+        allowlist.game = game.key();
+        allowlist.bump = ctx.bumps.allowlist;
+        allowlist.set_entries(previous_players)?;
+        
+        game.init(
+            game_id,
+            player,
+            ctx.accounts.usdc_mint.key(),
+            previous.max_players,
+            clock.unix_timestamp,
+            ctx.bumps.game,
+        );
+        game.label = previous.label;
+        game.stake_amount = previous.stake_amount;
+        game.min_players = previous.min_players;
+        game.fee_bps = previous.fee_bps;
+        game.bounty_bps = previous.bounty_bps;
+        game.survival_payout = previous.survival_payout;
+        game.side_stake = previous.side_stake;
+        game.rebuy_window = previous.rebuy_window;
+        game.max_rebuys = previous.max_rebuys;
+        game.payout_schedule = previous.payout_schedule.clone();
+        game.join_deadline = join_deadline;
+        game.lobby_page = Some(ctx.accounts.lobby_registry.page);
+        game.allowlisted = true;
+        game.rematch_of = Some(previous.game_id);
+        game.commit_reveal = previous.commit_reveal;
+        let seat = game.add_player(player)?;
+        game.commit_entropy(seat, commitment)?;
+        apply_withdrawal_addresses(game, seat, &ctx.accounts.profile)?;
+        
+        let game_key = game.key();
+        ctx.accounts.lobby_registry.insert(game_key)?;
+        pay_listing_fee(
+            &ctx.accounts.config,
+            &ctx.accounts.player,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+        )?;
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.stake_amount, ctx.accounts.usdc_mint.decimals)?;
+        
+        emit!(GameCreated {
+            game_id,
+            label: game.label,
+            creator: player,
+            stake_amount: game.stake_amount,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            fee_bps: game.fee_bps,
+            payout_schedule: game
+                .payout_schedule
+                .iter()
+                .copied()
+                .take_while(|&bps| bps > 0)
+                .collect(),
+            join_deadline,
+            scheduled_start: None,
+        });
+        
+        emit!(RematchCreated {
+            game_id,
+            previous_game_id: previous.game_id,
+            players: previous_players.to_vec(),
+        });
+        
+        emit!(PlayerJoined {
+            game_id,
+            player,
+            player_count: game.player_count,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Reveal a commit-reveal secret once the lobby has enough players
+    /// The commitment is `hash(secret || player)`, binding it to the seat
+    /// so another player cannot replay it to cancel the entropy out
+    pub fn reveal_entropy(ctx: Context<RevealEntropy>, secret: [u8; 32]) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(game.commit_reveal, EscrowError::InvalidCommitment);
+        require!(
+            game.player_count >= game.min_players,
+            EscrowError::NotEnoughPlayers
+        );
+        
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(game.revealed & (1 << index) == 0, EscrowError::InvalidCommitment);
+        require!(
+            hashv(&[secret.as_ref(), player.as_ref()]).to_bytes() == game.commitments[index],
+            EscrowError::InvalidCommitment
+        );
+        
+        game.commitments[index] = secret;
+        game.revealed |= 1 << index;
+        
+        emit!(EntropyRevealed {
+            game_id: game.game_id,
+            player,
+        });
+        
+        Ok(())
+    }
+
+    /// Replay a move log against the game's seed and check the settled
+    /// winner - permissionless, so anyone can prove or disprove a result
+    /// The verdict is published in `ResultVerified`
+    pub fn verify_result(ctx: Context<VerifyResult>, moves: Vec<Move>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        
+        let winner_index = engine::replay(&game.seed, game.player_count, &moves)?;
+        let replayed_winner = game.players[winner_index as usize];
+        
+        emit!(ResultVerified {
+            game_id: game.game_id,
+            claimed_winner: game.winner,
+            replayed_winner,
+            valid: game.winner == Some(replayed_winner),
+        });
+        
+        Ok(())
+    }
+
+    /// Submit this round's move in an on-chain engine game
+    /// The round resolves as soon as every living seat has moved
+    pub fn submit_engine_move(
+        ctx: Context<SubmitEngineMove>,
+        action: MoveAction,
+        x: u8,
+        y: u8,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        let player = acting_wallet(
+            &ctx.accounts.player.key(),
+            ctx.accounts.session.as_ref(),
+            SESSION_SUBMIT_MOVES,
+            clock.unix_timestamp,
+        )?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        let seat = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        let cell = Board::cell(x, y)?;
+        let seed = game.seed;
+        
+        let engine = game.engine.as_mut().ok_or(EscrowError::EngineModeRequired)?;
+        require!(engine.winner.is_none(), EscrowError::GameNotLive);
+        require!(
+            clock.unix_timestamp <= engine.round_deadline,
+            EscrowError::RoundClosed
+        );
+        let bit = 1u8 << seat;
+        require!(engine.alive & bit != 0, EscrowError::PlayerEliminated);
+        require!(engine.moved & bit == 0, EscrowError::AlreadyMoved);
+        require!(
+            action == MoveAction::Flag || !Board::restore(&seed, engine.revealed).is_revealed(cell),
+            EscrowError::CellRevealed
+        );
+        
+        engine.moves[seat] = EngineMove { action, x, y };
+        engine.moved |= bit;
+        let round_complete = engine.moved == engine.alive;
+        
+        if round_complete {
+            game.resolve_engine_round(clock.unix_timestamp)?;
+        }
+        
+        Ok(())
+    }
+
+    /// Close an engine round after its deadline - permissionless
+    /// Seats that did not move auto-play the first hidden safe cell,
+    /// as the backend does for timed-out players
+    pub fn close_engine_round(ctx: Context<CloseEngineRound>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        let engine = game.engine.as_ref().ok_or(EscrowError::EngineModeRequired)?;
+        require!(engine.winner.is_none(), EscrowError::GameNotLive);
+        require!(
+            clock.unix_timestamp > engine.round_deadline,
+            EscrowError::RoundStillOpen
+        );
+        
+        game.resolve_engine_round(clock.unix_timestamp)
+    }
+
+    /// Pay out an on-chain engine game to the winner the engine decided
+    /// Permissionless - no backend input is involved
+    /// Remaining accounts: the fee split recipients' accounts, then a
+    /// (referral, referrer balance) pair per referred player
+    pub fn settle_engine_game<'info>(ctx: Context<'_, '_, 'info, 'info, SettleEngineGame<'info>>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let engine = game.engine.as_ref().ok_or(EscrowError::EngineModeRequired)?;
+        let winner_index = engine.winner.ok_or(EscrowError::EngineGameUndecided)?;
+        let winner = game.players[winner_index as usize];
+        require_keys_eq!(
+            ctx.accounts.winner.key(),
+            game.payout_owner(winner_index as usize),
+            EscrowError::InvalidWinner
+        );
+        // SOL payouts are unwrapped to the wallet and need no token account
+        let native = game.stake_mint == native_mint::ID;
+        if !native {
+            create_payout_account(
+                &ctx.accounts.payer,
+                &ctx.accounts.winner_token_account,
+                &ctx.accounts.winner,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+                &ctx.accounts.system_program,
+            )?;
+        }
+        
+        game.winner = Some(winner);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = house_fee;
+        
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        pay_winner(
+            game,
+            winner_index as usize,
+            winner_payout,
+            &WinnerAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                winner: &ctx.accounts.winner,
+                winner_token_account: &ctx.accounts.winner_token_account,
+                pending_claim: &ctx.accounts.pending_claim,
+                unwrap_account: &ctx.accounts.unwrap_account,
+                unwrap_bump: ctx.bumps.unwrap_account,
+                payer: &ctx.accounts.payer,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer,
+        )?;
+        
+        // Season games divert a share of the rake into the season pool
+        let season_cut = pay_season_cut(
+            game,
+            winner,
+            house_fee,
+            &mut ctx.accounts.season,
+            &ctx.accounts.season_vault,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            house_fee - season_cut,
+            fee_waived,
+            ctx.remaining_accounts,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEnded {
+            game_id: game.game_id,
+            winner,
+            winner_payout,
+            house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Record a move in the game's on-chain move log
+    /// The log is an audit trail only - resolution stays with the backend
+    pub fn submit_move(ctx: Context<SubmitMove>, action: MoveAction, x: u8, y: u8) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let clock = Clock::get()?;
+        let player = acting_wallet(
+            &ctx.accounts.player.key(),
+            ctx.accounts.session.as_ref(),
+            SESSION_SUBMIT_MOVES,
+            clock.unix_timestamp,
+        )?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let seat = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        Board::cell(x, y)?;
+        
+        let mut move_log = load_move_log(&ctx.accounts.move_log)?;
+        move_log.game = game.key();
+        move_log.bump = ctx.bumps.move_log;
+        let logged = LoggedMove {
+            slot: clock.slot,
+            player: seat as u8,
+            action: action as u8,
+            x,
+            y,
+            ..LoggedMove::default()
+        };
+        move_log.push(logged)?;
+        
+        emit!(MoveSubmitted {
+            game_id: game.game_id,
+            player,
+            action,
+            x,
+            y,
+            slot: clock.slot,
+        });
+        
+        Ok(())
+    }
+
+    /// Record a batch of moves in the game's move log in one instruction
+    /// Only callable by the backend (game authority); `batch` packs each
+    /// move into `PACKED_MOVE_LEN` bytes (see `LoggedMove::unpack`) and
+    /// every move is stamped with the recording slot
+    /// `expected_nonce` must match the game's action nonce, so a replayed
+    /// batch is rejected instead of logged twice
+    pub fn submit_moves_batch(
+        ctx: Context<SubmitMovesBatch>,
+        batch: Vec<u8>,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(
+            !batch.is_empty()
+                && batch.chunks_exact(PACKED_MOVE_LEN).remainder().is_empty()
+                && batch.len() / PACKED_MOVE_LEN <= MAX_MOVE_BATCH,
+            EscrowError::InvalidMoveBatch
+        );
+        
+        let action_nonce = game.consume_nonce(expected_nonce)?;
+        let mut move_log = load_move_log(&ctx.accounts.move_log)?;
+        move_log.game = game.key();
+        move_log.bump = ctx.bumps.move_log;
+        for packed in batch.chunks_exact(PACKED_MOVE_LEN) {
+            let logged = LoggedMove::unpack(packed, clock.slot)?;
+            require!(logged.player < game.player_count, EscrowError::InvalidMoveBatch);
+            move_log.push(logged)?;
+        }
+        
+        emit!(MovesBatchSubmitted {
+            game_id: game.game_id,
+            count: (batch.len() / PACKED_MOVE_LEN) as u8,
+            slot: clock.slot,
+            action_nonce,
+        });
+        
+        Ok(())
+    }
+
+    /// Add stake tokens to the backend bond that backs result challenges
+    pub fn deposit_bond(ctx: Context<DepositBond>, amount: u64) -> Result<()> {
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.depositor_token_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.usdc_mint.decimals)?;
+        
+        emit!(BondDeposited {
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Disprove a proposed result or settlement during its dispute window
+    /// A seated player submits the move log committed by `move_root`; if
+    /// replaying it against the seed picks a winner the proposal does not
+    /// pay first, the proposal is withdrawn and the challenger is paid `config.challenge_reward`
+    /// (or what is left of it) from the backend bond
+    pub fn challenge_result(ctx: Context<ChallengeResult>, moves: Vec<Move>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let challenger = ctx.accounts.challenger.key();
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(game.seat_of(&challenger).is_some(), EscrowError::NotInGame);
+        require!(game.result_pending(), EscrowError::NoResultProposed);
+        let dispute_deadline = game.dispute_deadline.ok_or(EscrowError::NoResultProposed)?;
+        require!(
+            clock.unix_timestamp < dispute_deadline,
+            EscrowError::DisputeWindowClosed
+        );
+        require!(
+            game.move_root == Some(engine::move_root(&moves)),
+            EscrowError::InvalidMoveLog
+        );
+        
+        let winner_index = engine::replay(&game.seed, game.player_count, &moves)?;
+        let replayed_winner = game.players[winner_index as usize];
+        let proposed_winner = game.proposed_winner;
+        let disproved = match (proposed_winner, game.proposal) {
+            (Some(proposed_winner), _) => replayed_winner != proposed_winner,
+            (None, Some(settlement)) => settlement.contradicts(game, winner_index),
+            (None, None) => false,
+        };
+        require!(disproved, EscrowError::ChallengeFailed);
+        
+        game.proposed_winner = None;
+        game.proposal = None;
+        game.dispute_deadline = None;
+        game.move_root = None;
+        
+        let reward = ctx
+            .accounts
+            .config
+            .challenge_reward
+            .min(ctx.accounts.bond_vault.amount);
+        if reward > 0 {
+            let seeds = &[b"bond".as_ref(), &[ctx.bumps.bond_vault]];
+            let signer = &[&seeds[..]];
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.challenger_token_account.to_account_info(),
+                authority: ctx.accounts.bond_vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, reward, ctx.accounts.mint.decimals)?;
+        }
+        
+        emit!(ResultChallenged {
+            game_id: game.game_id,
+            challenger,
+            proposed_winner,
+            replayed_winner,
+            reward,
+        });
+        
+        Ok(())
+    }
+
+    /// Set the verifying key for proof-settled games (admin only)
+    pub fn set_settlement_verifier(
+        ctx: Context<SetSettlementVerifier>,
+        verifying_key: VerifyingKey,
+    ) -> Result<()> {
+        let verifier = &mut ctx.accounts.verifier;
+        verifier.verifying_key = verifying_key;
+        verifier.bump = ctx.bumps.verifier;
+        
+        emit!(SettlementVerifierUpdated {
+            admin: ctx.accounts.admin.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Settle the game with a succinct proof that, given the game seed and
+    /// the moves recorded in the game's on-chain move log, `winner_index` won
+    /// Permissionless and immediate - the proof replaces the dispute window.
+    /// The move root is computed from the log here, so a prover cannot
+    /// prove a win over moves the players never made
+    /// Remaining accounts: the fee split recipients' accounts, then a
+    /// (referral, referrer balance) pair per referred player
+    pub fn end_game_with_proof<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndGameWithProof<'info>>,
+        winner_index: u8,
+        seed_reveal: Option<[u8; 32]>,
+        proof: Groth16Proof,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        // A proposed result is settled or challenged through its dispute window
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(game.engine.is_none(), EscrowError::EngineSettlementRequired);
+        game.reveal_backend_seed(seed_reveal)?;
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        require!(game.paid_places() == 1, EscrowError::RankedSettlementRequired);
+        require!(!game.has_teams(), EscrowError::TeamSettlementRequired);
+        require!(!game.survival_payout, EscrowError::SurvivalSettlementRequired);
+        require!(winner_index < game.player_count, EscrowError::InvalidWinner);
+        if let Some(logged) = game.logged_placements() {
+            require!(logged[0] == winner_index, EscrowError::ResultContradictsLog);
+        }
+        
+        let move_root = ctx.accounts.move_log.load()?.root();
+        let inputs = zk::settlement_inputs(&game.game_id, &game.seed, &move_root, winner_index);
+        zk::verify(&ctx.accounts.verifier.verifying_key, &proof, &inputs)?;
+        
+        let winner = game.players[winner_index as usize];
+        require_keys_eq!(
+            ctx.accounts.winner.key(),
+            game.payout_owner(winner_index as usize),
+            EscrowError::InvalidWinner
+        );
+        // SOL payouts are unwrapped to the wallet and need no token account
+        let native = game.stake_mint == native_mint::ID;
+        if !native {
+            create_payout_account(
+                &ctx.accounts.payer,
+                &ctx.accounts.winner_token_account,
+                &ctx.accounts.winner,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+                &ctx.accounts.system_program,
+            )?;
+        }
+        
+        game.winner = Some(winner);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.move_root = Some(move_root);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let PayoutBreakdown {
+            winner_payout,
+            house_fee,
+            fee_waived,
+            ..
+        } = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = house_fee;
+        
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        pay_winner(
+            game,
+            winner_index as usize,
+            winner_payout,
+            &WinnerAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                winner: &ctx.accounts.winner,
+                winner_token_account: &ctx.accounts.winner_token_account,
+                pending_claim: &ctx.accounts.pending_claim,
+                unwrap_account: &ctx.accounts.unwrap_account,
+                unwrap_bump: ctx.bumps.unwrap_account,
+                payer: &ctx.accounts.payer,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer,
+        )?;
+        
+        // Season games divert a share of the rake into the season pool
+        let season_cut = pay_season_cut(
+            game,
+            winner,
+            house_fee,
+            &mut ctx.accounts.season,
+            &ctx.accounts.season_vault,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            signer,
+        )?;
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            house_fee - season_cut,
+            fee_waived,
+            ctx.remaining_accounts,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEnded {
+            game_id: game.game_id,
+            winner,
+            winner_payout,
+            house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create the program-owned treasury that house fees in `mint` accrue
+    /// into - required before games staked in that mint can settle
+    pub fn create_treasury(ctx: Context<CreateTreasury>) -> Result<()> {
+        emit!(TreasuryCreated {
+            mint: ctx.accounts.mint.key(),
+            treasury: ctx.accounts.treasury.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Withdraw accrued house fees from the treasury (admin only)
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"treasury",
+            mint_key.as_ref(),
+            &[ctx.bumps.treasury],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.treasury.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(TreasuryWithdrawn {
+            mint: mint_key,
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Bind the signing player to the referrer credited with a slice of
+    /// their rake; a player's referrer can only be set once
+    pub fn register_referral(ctx: Context<RegisterReferral>, referrer: Pubkey) -> Result<()> {
+        let player = ctx.accounts.player.key();
+        require_keys_neq!(referrer, player, EscrowError::SelfReferral);
+        
+        let referral = &mut ctx.accounts.referral;
+        referral.player = player;
+        referral.referrer = referrer;
+        referral.created_at = Clock::get()?.unix_timestamp;
+        referral.bump = ctx.bumps.referral;
+        
+        emit!(ReferralRegistered { player, referrer });
+        
+        Ok(())
+    }
+
+    /// Open the balance a referrer's earnings in `mint` are credited to
+    pub fn open_referrer_balance(ctx: Context<OpenReferrerBalance>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.referrer = ctx.accounts.referrer.key();
+        balance.mint = ctx.accounts.mint.key();
+        balance.claimable = 0;
+        balance.total_earned = 0;
+        balance.bump = ctx.bumps.balance;
+        
+        Ok(())
+    }
+
+    /// Create the program-owned pool that holds referrer balances in a
+    /// stake mint (admin only)
+    pub fn create_referral_pool(ctx: Context<CreateReferralPool>) -> Result<()> {
+        emit!(ReferralPoolCreated {
+            mint: ctx.accounts.mint.key(),
+            referral_pool: ctx.accounts.referral_pool.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Referrer withdraws their credited earnings from the referral pool
+    pub fn claim_referral_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        let amount = balance.claimable;
+        require!(amount > 0, EscrowError::NothingToClaim);
+        check_withdrawal_address(
+            &ctx.accounts.profile,
+            &ctx.accounts.referrer_token_account.owner,
+        )?;
+        balance.claimable = 0;
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"referral_pool",
+            mint_key.as_ref(),
+            &[ctx.bumps.referral_pool],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.referral_pool.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.referrer_token_account.to_account_info(),
+            authority: ctx.accounts.referral_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(ReferralEarningsClaimed {
+            referrer: ctx.accounts.referrer.key(),
+            mint: mint_key,
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Record the rake a player paid on a settled game in their profile
+    /// and accrue `config.rakeback_bps` of it as claimable rakeback
+    /// Only games staked in the default stake mint count
+    pub fn accrue_rakeback(ctx: Context<AccrueRakeback>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let config = &ctx.accounts.config;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require_keys_eq!(game.stake_mint, config.stake_mint, EscrowError::InvalidMint);
+        let index = game.seat_of(&player).ok_or(EscrowError::WrongPlayer)?;
+        let bit = 1u8 << index;
+        require!(
+            game.rakeback_accrued & bit == 0,
+            EscrowError::RakebackAlreadyAccrued
+        );
+        game.rakeback_accrued |= bit;
+        
+        // The player's stake-weighted part of the house fee
+        let rake = (game.house_fee as u128 * game.stake_of(index) as u128)
+            .checked_div(game.total_stakes()? as u128)
+            .unwrap_or(0) as u64;
+        let rakeback = payout::rakeback(rake, config.rakeback_bps)?;
+        
+        let profile = &mut ctx.accounts.profile;
+        profile.player = player;
+        profile.bump = ctx.bumps.profile;
+        profile.rake_paid = profile
+            .rake_paid
+            .checked_add(rake)
+            .ok_or(EscrowError::MathOverflow)?;
+        profile.rakeback_claimable = profile
+            .rakeback_claimable
+            .checked_add(rakeback)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(RakebackAccrued {
+            game_id: game.game_id,
+            player,
+            rake,
+            rakeback,
+        });
+        
+        Ok(())
+    }
+
+    /// Player withdraws their accrued rakeback from the treasury
+    pub fn claim_rakeback(ctx: Context<ClaimRakeback>) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        let amount = profile.rakeback_claimable;
+        require!(amount > 0, EscrowError::NothingToClaim);
+        let allowed = profile.withdrawal_addresses();
+        require!(
+            allowed.is_empty() || allowed.contains(&ctx.accounts.player_token_account.owner),
+            EscrowError::WithdrawalAddressNotAllowed
+        );
+        profile.rakeback_claimable = 0;
+        profile.rakeback_claimed = profile
+            .rakeback_claimed
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"treasury",
+            mint_key.as_ref(),
+            &[ctx.bumps.treasury],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.treasury.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(RakebackClaimed {
+            player: ctx.accounts.player.key(),
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the program-owned jackpot pool for a stake mint (admin only)
+    pub fn create_jackpot(ctx: Context<CreateJackpot>) -> Result<()> {
+        emit!(JackpotCreated {
+            mint: ctx.accounts.mint.key(),
+            jackpot: ctx.accounts.jackpot.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Pay the jackpot on top of the normal prize to a winner who cleared
+    /// the board without a wrong flag
+    /// Permissionless - `moves` must be the log committed by `move_root`,
+    /// and replaying it must reproduce the settled winner
+    pub fn claim_jackpot(ctx: Context<ClaimJackpot>, moves: Vec<Move>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(!game.jackpot_claimed, EscrowError::JackpotAlreadyClaimed);
+        require!(
+            game.move_root.is_some() && game.move_root == Some(engine::move_root(&moves)),
+            EscrowError::InvalidMoveLog
+        );
+        let winner = game.winner.ok_or(EscrowError::InvalidWinner)?;
+        let winner_index = game.seat_of(&winner).ok_or(EscrowError::InvalidWinner)?;
+        require!(
+            engine::is_clean_sweep(&game.seed, game.player_count, &moves, winner_index as u8)?,
+            EscrowError::JackpotNotWon
+        );
+        require!(
+            ctx.accounts.winner_token_account.owner == winner,
+            EscrowError::WrongPlayer
+        );
+        
+        let amount = ctx.accounts.jackpot.amount;
+        require!(amount > 0, EscrowError::NothingToClaim);
+        game.jackpot_claimed = true;
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"jackpot",
+            mint_key.as_ref(),
+            &[ctx.bumps.jackpot],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.jackpot.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: ctx.accounts.jackpot.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(JackpotWon {
+            game_id: game.game_id,
+            winner,
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Record the elimination of the seat at `eliminated_index`, and the
+    /// seat that caused it if another player did; in a bounty game the
+    /// eliminator earns `bounty_bps` of the stakes, claimable once the game
+    /// is finished
+    /// Only callable by authorized backend (game authority)
+    pub fn eliminate_player(
+        ctx: Context<EliminatePlayer>,
+        eliminated_index: u8,
+        eliminator_index: Option<u8>,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(eliminated_index < game.player_count, EscrowError::InvalidPlayer);
+        let eliminated_bit = 1u8 << eliminated_index;
+        require!(game.eliminated & eliminated_bit == 0, EscrowError::AlreadyEliminated);
+        if let Some(eliminator_index) = eliminator_index {
+            require!(
+                eliminator_index < game.player_count && eliminator_index != eliminated_index,
+                EscrowError::InvalidPlayer
+            );
+            require!(
+                game.eliminated & 1 << eliminator_index == 0,
+                EscrowError::AlreadyEliminated
+            );
+        }
+        // The last player standing is the winner, not an elimination
+        require!(
+            (game.eliminated | eliminated_bit).count_ones() < game.player_count as u32,
+            EscrowError::InvalidPlayer
+        );
+        
+        let action_nonce = game.consume_nonce(expected_nonce)?;
+        // A deal is agreed among the players left, so it dies with one
+        game.deal = None;
+        let position = game.eliminated.count_ones() as usize;
+        game.elimination_order[position] = eliminated_index;
+        game.eliminated |= eliminated_bit;
+        game.eliminated_at[eliminated_index as usize] = clock.unix_timestamp;
+        let bounty = match eliminator_index {
+            Some(eliminator_index) if game.bounty_bps > 0 => {
+                let bounty = payout::bounty(game.total_stakes()?, game.bounty_bps)?;
+                let earned = &mut game.bounties[eliminator_index as usize];
+                *earned = earned.checked_add(bounty).ok_or(EscrowError::MathOverflow)?;
+                bounty
+            }
+            _ => 0,
+        };
+        
+        emit!(PlayerEliminated {
+            game_id: game.game_id,
+            eliminated: game.players[eliminated_index as usize],
+            eliminator: eliminator_index.map(|index| game.players[index as usize]),
+            bounty,
+            action_nonce,
+        });
+        
+        Ok(())
+    }
+
+    /// Player withdraws the bounties they earned once the game is finished
+    /// Bounties of a cancelled or voided game are forfeited to the refunds
+    pub fn claim_bounty(ctx: Context<ClaimBounty>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.payout_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let amount = game.bounties[index];
+        require!(amount > 0, EscrowError::NothingToClaim);
+        game.bounties[index] = 0;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(BountyClaimed {
+            game_id,
+            player,
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create the program-owned insurance pool for a stake mint (admin only)
+    pub fn create_insurance_pool(ctx: Context<CreateInsurancePool>) -> Result<()> {
+        emit!(InsurancePoolCreated {
+            mint: ctx.accounts.mint.key(),
+            insurance_pool: ctx.accounts.insurance_pool.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Insured player who lost a finished game collects the partial stake
+    /// refund of `config.insurance` from the insurance pool
+    pub fn claim_insurance(ctx: Context<ClaimInsurance>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.payout_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let bit = 1u8 << index;
+        require!(game.insured & bit != 0, EscrowError::NotInsured);
+        require!(game.insurance_claimed & bit == 0, EscrowError::NothingToClaim);
+        require!(game.lost(index), EscrowError::NotInsured);
+        
+        let terms = ctx
+            .accounts
+            .config
+            .insurance
+            .ok_or(EscrowError::InsuranceUnavailable)?;
+        let refund = payout::insurance_share(game.stake_of(index), terms.refund_bps)?;
+        require!(
+            ctx.accounts.insurance_pool.amount >= refund,
+            EscrowError::InsurancePoolShort
+        );
+        game.insurance_claimed |= bit;
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"insurance",
+            mint_key.as_ref(),
+            &[ctx.bumps.insurance_pool],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.insurance_pool.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.insurance_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, refund, ctx.accounts.mint.decimals)?;
+        
+        emit!(InsuranceClaimed {
+            game_id: game.game_id,
+            player,
+            refund,
+        });
+        
+        Ok(())
+    }
+
+    /// Propose ending a live game on an agreed split of the prize pool
+    /// The proposer must still be in the game and accepts the deal at once;
+    /// eliminated seats get nothing and the splits must cover the pool
+    /// A new proposal replaces the previous one and its acceptances
+    pub fn propose_deal(ctx: Context<ProposeDeal>, splits: Vec<u16>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let proposer = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(game.engine.is_none(), EscrowError::EngineSettlementRequired);
+        require!(!game.has_teams(), EscrowError::TeamSettlementRequired);
+        require!(game.season.is_none(), EscrowError::SeasonSettlementRequired);
+        let index = game.seat_of(&proposer).ok_or(EscrowError::NotInGame)?;
+        let remaining = game.remaining();
+        require!(remaining & (1 << index) != 0, EscrowError::AlreadyEliminated);
+        require!(
+            splits.len() == game.player_count as usize
+                && splits.iter().map(|&bps| bps as u64).sum::<u64>() == BPS_DENOMINATOR
+                && splits
+                    .iter()
+                    .enumerate()
+                    .all(|(seat, &bps)| bps == 0 || remaining & (1 << seat) != 0),
+            EscrowError::InvalidDeal
+        );
+        
+        let mut deal = Deal {
+            splits: [0u16; MAX_PLAYERS],
+            accepted: 1 << index,
+        };
+        deal.splits[..splits.len()].copy_from_slice(&splits);
+        game.deal = Some(deal);
+        
+        emit!(DealProposed {
+            game_id: game.game_id,
+            proposer,
+            splits,
+        });
+        
+        Ok(())
+    }
+
+    /// Accept the proposed deal as one of the players still in the game
+    pub fn accept_deal(ctx: Context<AcceptDeal>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(game.remaining() & (1 << index) != 0, EscrowError::AlreadyEliminated);
+        let deal = game.deal.as_mut().ok_or(EscrowError::NoDeal)?;
+        deal.accepted |= 1 << index;
+        
+        emit!(DealAccepted {
+            game_id: game.game_id,
+            player,
+        });
+        
+        Ok(())
+    }
+
+    /// Pay out a deal every remaining player accepted - the house fee is
+    /// charged as usual and the rest split per the deal
+    /// Who is still in the game comes from the backend's elimination
+    /// reports, so the deal is proposed through `propose_settlement` and
+    /// can be challenged like any other result
+    /// Permissionless once the dispute window has passed; the token account
+    /// for every seat is passed as a remaining account in seat order,
+    /// followed by the pending-claim account of each frozen one (see
+    /// `open_pending_claim`), the fee split recipients' accounts and a
+    /// (referral, referrer balance) pair per referred player
+    pub fn settle_deal<'info>(ctx: Context<'_, '_, 'info, 'info, EndGameRanked<'info>>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(
+            game.take_settlement(clock.unix_timestamp)? == ProposedSettlement::Deal,
+            EscrowError::WrongSettlement
+        );
+        let deal = game.deal.ok_or(EscrowError::NoDeal)?;
+        let remaining = game.remaining();
+        require!(deal.accepted & remaining == remaining, EscrowError::DealNotAccepted);
+        let seats = game.player_count as usize;
+        require!(
+            ctx.remaining_accounts.len() >= seats + ctx.accounts.config.fee_split.len(),
+            EscrowError::InvalidPlacements
+        );
+        
+        // The biggest share is recorded as the winner and takes the dust
+        let weights = deal.splits.map(|bps| bps as u64);
+        let winner_index = (0..seats)
+            .max_by_key(|&seat| (weights[seat], std::cmp::Reverse(seat)))
+            .ok_or(EscrowError::InvalidWinner)?;
+        
+        let breakdown = payout::compute_breakdown(game, &ctx.accounts.config, clock.unix_timestamp)?;
+        game.house_fee = breakdown.house_fee;
+        let shares =
+            payout::split_by_weight(breakdown.winner_payout, &weights[..seats], winner_index)?;
+        
+        game.winner = Some(game.players[winner_index]);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(clock.unix_timestamp);
+        game.last_transition_at = clock.unix_timestamp;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let (pending_claims, rest) = split_pending_claims(
+            &ctx.remaining_accounts[..seats],
+            &ctx.remaining_accounts[seats..],
+        )?;
+        for (seat, info) in ctx.remaining_accounts[..seats].iter().enumerate() {
+            if shares[seat] == 0 {
+                continue;
+            }
+            let token_account: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(info)?;
+            require!(token_account.owner == game.payout_owner(seat), EscrowError::WrongPlayer);
+            require!(token_account.mint == game.stake_mint, EscrowError::InvalidMint);
+            if vest_payout(game, seat, shares[seat], &ctx.accounts.config)? {
+                continue;
+            }
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: payee_destination(&token_account, info, pending_claims, game_id, shares[seat])?,
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, shares[seat], ctx.accounts.mint.decimals)?;
+        }
+        
+        // Transfer house fee
+        pay_house_fee(
+            game,
+            breakdown.house_fee,
+            breakdown.fee_waived,
+            rest,
+            &HouseFeeAccounts {
+                config: &ctx.accounts.config,
+                vault: &ctx.accounts.vault,
+                treasury: &ctx.accounts.treasury,
+                creator_token_account: &ctx.accounts.creator_token_account,
+                referral_pool: &ctx.accounts.referral_pool,
+                jackpot: &ctx.accounts.jackpot,
+                mint: &ctx.accounts.mint,
+                token_program: &ctx.accounts.token_program,
+            },
+            signer,
+        )?;
+        
+        emit!(GameEndedDeal {
+            game_id,
+            players: game.players[..seats].to_vec(),
+            payouts: shares[..seats].to_vec(),
+            house_fee: breakdown.house_fee,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Opt a seated player into the game's side pot before it starts
+    /// The side stake is escrowed in the game vault alongside the stakes
+    pub fn join_side_pot(ctx: Context<JoinSidePot>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(game.side_stake > 0, EscrowError::SidePotDisabled);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(game.side_pot & (1 << index) == 0, EscrowError::AlreadyInSidePot);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.side_stake, ctx.accounts.mint.decimals)?;
+        
+        game.side_pot |= 1 << index;
+        
+        emit!(SidePotJoined {
+            game_id: game.game_id,
+            player,
+            side_stake: game.side_stake,
+            side_pot: game.side_pot_total()?,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Claim a share of the side pot of a finished game
+    /// The side pot goes to the best-placed participants, split evenly
+    /// with the dust to the lowest seat, and is never raked
+    pub fn claim_side_pot(ctx: Context<ClaimSidePot>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.payout_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let winners = game.side_pot_winners();
+        let bit = 1u8 << index;
+        require!(
+            winners & bit != 0 && game.side_pot_claimed & bit == 0,
+            EscrowError::NothingToClaim
+        );
+        game.side_pot_claimed |= bit;
+        
+        let total = game.side_pot_total()?;
+        let share = total / winners.count_ones() as u64;
+        let amount = if winners.trailing_zeros() as usize == index {
+            total - share * (winners.count_ones() as u64 - 1)
+        } else {
+            share
+        };
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(SidePotClaimed {
+            game_id,
+            player,
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Buy back into a live game after being eliminated, within
+    /// `rebuy_window` seconds of the elimination
+    /// The player re-stakes their original stake into the pot and their
+    /// seat is active again; each seat may rebuy up to `max_rebuys` times
+    pub fn rebuy(ctx: Context<Rebuy>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        let bit = 1u8 << index;
+        require!(game.eliminated & bit != 0, EscrowError::NotEliminated);
+        require!(game.rebuys[index] < game.max_rebuys, EscrowError::RebuyUnavailable);
+        require!(
+            clock.unix_timestamp <= game.eliminated_at[index] + game.rebuy_window,
+            EscrowError::RebuyUnavailable
+        );
+        
+        let amount = game.stake_of(index);
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        // Take the seat back out of the elimination log
+        let count = game.eliminated.count_ones() as usize;
+        let position = game.elimination_order[..count]
+            .iter()
+            .position(|&seat| seat as usize == index)
+            .ok_or(EscrowError::NotEliminated)?;
+        game.elimination_order.copy_within(position + 1..count, position);
+        game.elimination_order[count - 1] = 0;
+        game.eliminated &= !bit;
+        game.eliminated_at[index] = 0;
+        game.deal = None;
+        
+        game.rebuys[index] += 1;
+        game.rebuy_stakes = game
+            .rebuy_stakes
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(PlayerRebought {
+            game_id: game.game_id,
+            player,
+            amount,
+            rebuys: game.rebuys[index],
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Buy a power-up during a live game - the price is escrowed in the
+    /// game vault and added to the prize pool at settlement
+    /// `kind` is opaque to the program; the game server grants the
+    /// power-up from the emitted event
+    pub fn buy_powerup(ctx: Context<BuyPowerup>, kind: u8, price: u64) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(price > 0, EscrowError::InvalidPrizeAmount);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require!(game.eliminated & (1 << index) == 0, EscrowError::AlreadyEliminated);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, price, ctx.accounts.mint.decimals)?;
+        
+        game.powerup_spent[index] = game.powerup_spent[index]
+            .checked_add(price)
+            .ok_or(EscrowError::MathOverflow)?;
+        game.powerup_pot = game
+            .powerup_pot
+            .checked_add(price)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(PowerupPurchased {
+            game_id: game.game_id,
+            player,
+            kind,
+            price,
+            powerup_pot: game.powerup_pot,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Tip a player during a live game - any wallet may tip
+    /// Tips are held in the game vault outside the prize pool, released
+    /// to the player once the game is finished and refundable to the
+    /// tipper if it is voided
+    pub fn tip_player(ctx: Context<TipPlayer>, player_index: u8, amount: u64) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let tip = &mut ctx.accounts.tip;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.tipper_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.tipper.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        let index = player_index as usize;
+        tip.game = game.key();
+        tip.tipper = ctx.accounts.tipper.key();
+        tip.player = game.players[index];
+        tip.amount = tip
+            .amount
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        tip.bump = ctx.bumps.tip;
+        game.tips[index] = game.tips[index]
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(PlayerTipped {
+            game_id: game.game_id,
+            tipper: tip.tipper,
+            player: tip.player,
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Player withdraws the tips they received once the game is finished
+    pub fn claim_tips(ctx: Context<ClaimTips>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.payout_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let amount = game.tips[index];
+        require!(amount > 0, EscrowError::NothingToClaim);
+        game.tips[index] = 0;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(TipsClaimed {
+            game_id,
+            player,
+            amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Close a tip account once its game is over - a voided game refunds
+    /// the tip, a finished one only returns the rent since the tip now
+    /// belongs to the player
+    /// Permissionless - funds go to the tipper's own token account
+    pub fn close_tip(ctx: Context<CloseTip>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let tip = &ctx.accounts.tip;
+        
+        require!(
+            matches!(game.status, GameStatus::Finished | GameStatus::Voided),
+            EscrowError::GameNotFinished
+        );
+        if game.status == GameStatus::Finished {
+            return Ok(());
+        }
+        require!(
+            ctx.accounts.tipper_token_account.owner == tip.tipper,
+            EscrowError::WrongPlayer
+        );
+        
+        let index = game.seat_of(&tip.player).ok_or(EscrowError::NotInGame)?;
+        game.tips[index] -= tip.amount;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.tipper_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, tip.amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(TipRefunded {
+            game_id,
+            tipper: tip.tipper,
+            player: tip.player,
+            amount: tip.amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Open the spectator betting pool of a live game
+    /// Permissionless - anyone may pay the rent for a game's pool
+    pub fn create_betting_pool(ctx: Context<CreateBettingPool>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let pool = &mut ctx.accounts.betting_pool;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        
+        pool.game = game.key();
+        pool.mint = game.stake_mint;
+        pool.backed = [0u64; MAX_PLAYERS];
+        pool.total = 0;
+        pool.vig_bps = ctx.accounts.config.betting_vig_bps;
+        pool.payout_pool = 0;
+        pool.winning_seat = None;
+        pool.status = BettingStatus::Open;
+        pool.bump = ctx.bumps.betting_pool;
+        pool.vault_bump = ctx.bumps.betting_vault;
+        
+        emit!(BettingPoolCreated {
+            game_id: game.game_id,
+            betting_pool: pool.key(),
+            vig_bps: pool.vig_bps,
+        });
+        
+        Ok(())
+    }
+
+    /// Back a player of a live game in its betting pool
+    /// Bets close once a result is proposed
+    pub fn place_bet(ctx: Context<PlaceBet>, seat: u8, amount: u64) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let pool = &mut ctx.accounts.betting_pool;
+        let bet = &mut ctx.accounts.bet;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.result_pending(), EscrowError::ResultAlreadyProposed);
+        require!(pool.status == BettingStatus::Open, EscrowError::BettingClosed);
+        require!(seat < game.player_count, EscrowError::InvalidPlayer);
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.bettor_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.betting_vault.to_account_info(),
+            authority: ctx.accounts.bettor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        bet.betting_pool = pool.key();
+        bet.bettor = ctx.accounts.bettor.key();
+        bet.seat = seat;
+        bet.amount = bet.amount.checked_add(amount).ok_or(EscrowError::MathOverflow)?;
+        bet.bump = ctx.bumps.bet;
+        pool.backed[seat as usize] = pool.backed[seat as usize]
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        pool.total = pool.total.checked_add(amount).ok_or(EscrowError::MathOverflow)?;
+        
+        emit!(BetPlaced {
+            game_id: game.game_id,
+            bettor: bet.bettor,
+            player: game.players[seat as usize],
+            amount,
+            backed: pool.backed[seat as usize],
+            total: pool.total,
+        });
+        
+        Ok(())
+    }
+
+    /// Settle a betting pool against its game's recorded winner, paying
+    /// the vig to the treasury
+    /// A game voided, drawn, played in teams or won by an unbacked player
+    /// voids the pool and every bet is refunded without vig
+    /// Permissionless
+    pub fn settle_betting_pool(ctx: Context<SettleBettingPool>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let pool = &mut ctx.accounts.betting_pool;
+        
+        require!(pool.status == BettingStatus::Open, EscrowError::BettingClosed);
+        require!(
+            matches!(
+                game.status,
+                GameStatus::Finished | GameStatus::Cancelled | GameStatus::Voided
+            ),
+            EscrowError::GameNotFinished
+        );
+        
+        let winning_seat = match game.winner {
+            Some(winner) if game.draw_mask == 0 && !game.has_teams() => game.seat_of(&winner),
+            _ => None,
+        }
+        .filter(|&seat| game.status == GameStatus::Finished && pool.backed[seat] > 0);
+        
+        let Some(seat) = winning_seat else {
+            pool.status = BettingStatus::Voided;
+            emit!(BettingPoolVoided {
+                game_id: game.game_id,
+                total: pool.total,
+            });
+            return Ok(());
+        };
+        
+        let vig = payout::betting_vig(pool.total, pool.vig_bps)?;
+        pool.winning_seat = Some(seat as u8);
+        pool.payout_pool = pool.total - vig;
+        pool.status = BettingStatus::Settled;
+        
+        if vig > 0 {
+            let game_key = game.key();
+            let seeds = &[
+                b"betting_vault",
+                game_key.as_ref(),
+                &[pool.vault_bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.betting_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.betting_vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, vig, ctx.accounts.mint.decimals)?;
+        }
+        
+        emit!(BettingPoolSettled {
+            game_id: game.game_id,
+            winner: game.players[seat],
+            total: pool.total,
+            vig,
+            payout_pool: pool.payout_pool,
+        });
+        
+        Ok(())
+    }
+
+    /// Close a bet on a settled or voided pool, paying its winnings or
+    /// refund to the bettor's token account; losing bets only return rent
+    /// Permissionless - funds go to the bettor's own token account
+    pub fn claim_bet(ctx: Context<ClaimBet>) -> Result<()> {
+        let pool = &ctx.accounts.betting_pool;
+        let bet = &ctx.accounts.bet;
+        
+        let amount = match pool.status {
+            BettingStatus::Open => return err!(EscrowError::BettingNotSettled),
+            BettingStatus::Voided => bet.amount,
+            BettingStatus::Settled if pool.winning_seat == Some(bet.seat) => payout::parimutuel_share(
+                bet.amount,
+                pool.payout_pool,
+                pool.backed[bet.seat as usize],
+            )?,
+            BettingStatus::Settled => 0,
+        };
+        
+        if amount > 0 {
+            require!(
+                ctx.accounts.bettor_token_account.owner == bet.bettor,
+                EscrowError::WrongPlayer
+            );
+            let game_key = pool.game;
+            let seeds = &[
+                b"betting_vault",
+                game_key.as_ref(),
+                &[pool.vault_bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.betting_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.bettor_token_account.to_account_info(),
+                authority: ctx.accounts.betting_vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        }
+        
+        emit!(BetClaimed {
+            betting_pool: pool.key(),
+            bettor: bet.bettor,
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Player withdraws the part of their vesting winnings released so far
+    /// Winnings release linearly over the game's vesting period from
+    /// settlement
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let player = ctx.accounts.player.key();
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        let index = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.payout_owner(index),
+            EscrowError::WrongPlayer
+        );
+        let finished_at = game.finished_at.ok_or(EscrowError::GameNotFinished)?;
+        let elapsed = clock.unix_timestamp - finished_at;
+        let released = payout::vested(game.vesting[index], elapsed, game.vesting_period)?;
+        let amount = released - game.vested_claimed[index];
+        require!(amount > 0, EscrowError::NothingToClaim);
+        game.vested_claimed[index] = released;
+        
+        let game_id = game.game_id;
+        let stake_mint = game.stake_mint;
+        let seeds = &[
+            b"vault",
+            stake_mint.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(VestedClaimed {
+            game_id,
+            player,
+            amount,
+            remaining: game.vesting[index] - released,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Create an NFT-staked game - the creator escrows an NFT from one of
+    /// the config's allowed collections, and every joiner must stake one
+    /// from the same collection; the winner takes all of them
+    pub fn create_nft_game(
+        ctx: Context<CreateNftGame>,
+        nft_game_id: [u8; 8],
+        collection: Pubkey,
+        max_players: u8,
+    ) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        let clock = Clock::get()?;
+        
+        require!(
+            ctx.accounts.config.nft_collections.contains(&collection),
+            EscrowError::NftGateFailed
+        );
+        require!(
+            max_players >= MIN_PLAYERS && max_players as usize <= MAX_PLAYERS,
+            EscrowError::InvalidPlayerLimits
+        );
+        
+        let creator = ctx.accounts.creator.key();
+        nft_game.nft_game_id = nft_game_id;
+        nft_game.creator = creator;
+        nft_game.collection = collection;
+        nft_game.players = [Pubkey::default(); MAX_PLAYERS];
+        nft_game.nfts = [Pubkey::default(); MAX_PLAYERS];
+        nft_game.player_count = 0;
+        nft_game.max_players = max_players;
+        nft_game.status = GameStatus::Waiting;
+        nft_game.seed = [0u8; 32];
+        nft_game.winner = None;
+        nft_game.released = 0;
+        nft_game.created_at = clock.unix_timestamp;
+        nft_game.started_at = None;
+        nft_game.bump = ctx.bumps.nft_game;
+        
+        escrow_nft(
+            nft_game,
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.creator_nft_account,
+            &ctx.accounts.nft_escrow,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+        )?;
+        
+        emit!(NftGameCreated {
+            nft_game_id,
+            creator,
+            collection,
+            nft: ctx.accounts.nft_mint.key(),
+            max_players,
+        });
+        
+        Ok(())
+    }
+
+    /// Stake an NFT into a waiting NFT game; the game starts once every
+    /// seat is filled
+    pub fn join_nft_game(ctx: Context<JoinNftGame>) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        let clock = Clock::get()?;
+        
+        require!(nft_game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        let player = ctx.accounts.player.key();
+        require!(
+            !nft_game.players[..nft_game.player_count as usize].contains(&player),
+            EscrowError::AlreadyJoined
+        );
+        
+        escrow_nft(
+            nft_game,
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.player_nft_account,
+            &ctx.accounts.nft_escrow,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.player,
+            &ctx.accounts.token_program,
+        )?;
+        
+        emit!(NftGameJoined {
+            nft_game_id: nft_game.nft_game_id,
+            player,
+            nft: ctx.accounts.nft_mint.key(),
+            player_count: nft_game.player_count,
+        });
+        
+        if nft_game.player_count == nft_game.max_players {
+            nft_game.seed = derive_seed(&nft_game.nft_game_id, &clock, &ctx.accounts.slot_hashes)?;
+            nft_game.status = GameStatus::Live;
+            nft_game.started_at = Some(clock.unix_timestamp);
+            
+            emit!(NftGameStarted {
+                nft_game_id: nft_game.nft_game_id,
+                seed: nft_game.seed,
+                started_at: clock.unix_timestamp,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Settle an NFT game - the winner is owed every staked NFT, moved by
+    /// `release_nft`; no house fee is taken
+    /// Only callable by authorized backend (game authority)
+    pub fn end_nft_game(ctx: Context<EndNftGame>, winner_index: u8) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        
+        require!(nft_game.status == GameStatus::Live, EscrowError::GameNotLive);
+        // NFT game results carry no enclave report or referee signatures,
+        // so a deployment that requires them settles only through games
+        require!(ctx.accounts.config.tee.is_none(), EscrowError::AttestationRequired);
+        require!(ctx.accounts.config.referees.is_none(), EscrowError::RefereeQuorumNotMet);
+        require!(winner_index < nft_game.player_count, EscrowError::InvalidWinner);
+        
+        let winner = nft_game.players[winner_index as usize];
+        nft_game.winner = Some(winner);
+        nft_game.status = GameStatus::Finished;
+        
+        emit!(NftGameEnded {
+            nft_game_id: nft_game.nft_game_id,
+            winner,
+            nfts: nft_game.nfts[..nft_game.player_count as usize].to_vec(),
+        });
+        
+        Ok(())
+    }
+
+    /// Cancel an NFT game that has not filled - only callable by its creator
+    /// Every staked NFT can then be returned with `release_nft`
+    pub fn cancel_nft_game(ctx: Context<CancelNftGame>) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        
+        require!(nft_game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        nft_game.status = GameStatus::Cancelled;
+        
+        emit!(NftGameCancelled {
+            nft_game_id: nft_game.nft_game_id,
+        });
+        
+        Ok(())
+    }
+
+    /// Cancel an NFT game that overran `config.max_duration` without being
+    /// settled - permissionless, like `claim_duel_timeout_refund` for duels;
+    /// every staked NFT can then be returned with `release_nft`
+    pub fn claim_nft_game_timeout(ctx: Context<ClaimNftGameTimeout>) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        let clock = Clock::get()?;
+        
+        require!(nft_game.status == GameStatus::Live, EscrowError::GameNotLive);
+        let started_at = nft_game.started_at.ok_or(EscrowError::GameNotLive)?;
+        require!(
+            clock.unix_timestamp > started_at + ctx.accounts.config.max_duration,
+            EscrowError::GameNotTimedOut
+        );
+        nft_game.status = GameStatus::Cancelled;
+        
+        emit!(NftGameTimedOut {
+            nft_game_id: nft_game.nft_game_id,
+            started_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Move one escrowed NFT out of a settled or cancelled NFT game - to the
+    /// winner, or back to the player who staked it - and close its escrow
+    /// Permissionless - the destination is fixed by the game
+    pub fn release_nft(ctx: Context<ReleaseNft>) -> Result<()> {
+        let nft_game = &mut ctx.accounts.nft_game;
+        let nft = ctx.accounts.nft_mint.key();
+        
+        let seat = nft_game.nfts[..nft_game.player_count as usize]
+            .iter()
+            .position(|staked| *staked == nft)
+            .ok_or(EscrowError::InvalidMint)?;
+        let recipient = match nft_game.status {
+            GameStatus::Finished => nft_game.winner.ok_or(EscrowError::InvalidWinner)?,
+            GameStatus::Cancelled => nft_game.players[seat],
+            _ => return err!(EscrowError::GameNotFinished),
+        };
+        require!(
+            ctx.accounts.recipient_token_account.owner == recipient,
+            EscrowError::WrongPlayer
+        );
+        require_keys_eq!(
+            ctx.accounts.depositor.key(),
+            nft_game.players[seat],
+            EscrowError::WrongPlayer
+        );
+        let bit = 1u8 << seat;
+        require!(nft_game.released & bit == 0, EscrowError::AlreadyRefunded);
+        nft_game.released |= bit;
+        
+        let nft_game_key = nft_game.key();
+        let seeds = &[
+            b"nft_escrow",
+            nft_game_key.as_ref(),
+            nft.as_ref(),
+            &[ctx.bumps.nft_escrow],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.nft_escrow.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: ctx.accounts.nft_escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, 1, ctx.accounts.nft_mint.decimals)?;
+        
+        // The escrow's rent goes back to the player who opened it
+        let cpi_accounts_close = CloseAccount {
+            account: ctx.accounts.nft_escrow.to_account_info(),
+            destination: ctx.accounts.depositor.to_account_info(),
+            authority: ctx.accounts.nft_escrow.to_account_info(),
+        };
+        let cpi_ctx_close = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_close,
+            signer,
+        );
+        token_interface::close_account(cpi_ctx_close)?;
+        
+        emit!(NftReleased {
+            nft_game_id: nft_game.nft_game_id,
+            nft,
+            recipient,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the program-owned pool that in-program balances in a stake
+    /// mint are held in (admin only)
+    pub fn create_balance_vault(ctx: Context<CreateBalanceVault>) -> Result<()> {
+        emit!(BalanceVaultCreated {
+            mint: ctx.accounts.mint.key(),
+            balance_vault: ctx.accounts.balance_vault.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Deposit into the caller's in-program balance, which `join_game`
+    /// can debit instead of transferring from their token account
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let player_balance = &mut ctx.accounts.player_balance;
+        
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.balance_vault.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        player_balance.player = ctx.accounts.player.key();
+        player_balance.mint = ctx.accounts.mint.key();
+        player_balance.amount = player_balance
+            .amount
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        player_balance.bump = ctx.bumps.player_balance;
+        
+        emit!(BalanceDeposited {
+            player: player_balance.player,
+            mint: player_balance.mint,
+            amount,
+            balance: player_balance.amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Withdraw from the caller's in-program balance to their token account
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let player_balance = &mut ctx.accounts.player_balance;
+        
+        require!(amount > 0, EscrowError::InvalidPrizeAmount);
+        require!(player_balance.amount >= amount, EscrowError::InsufficientBalance);
+        check_withdrawal_address(&ctx.accounts.profile, &ctx.accounts.player_token_account.owner)?;
+        player_balance.amount -= amount;
+        
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"balance_vault",
+            mint_key.as_ref(),
+            &[ctx.bumps.balance_vault],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.balance_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.balance_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(BalanceWithdrawn {
+            player: player_balance.player,
+            mint: mint_key,
+            amount,
+            balance: player_balance.amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Seat a player who is not online to sign, e.g. when auto-seating
+    /// from a queue - backend authority only
+    /// The stake is pulled from the player's token account, which must
+    /// have approved the program's join delegate for at least the stake
+    pub fn join_for(ctx: Context<JoinFor>, invite_code: Option<Vec<u8>>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Waiting, EscrowError::GameNotWaiting);
+        require!(
+            clock.unix_timestamp <= game.join_cutoff(),
+            EscrowError::JoinDeadlinePassed
+        );
+        require!(!game.usd_stake, EscrowError::UsdStakeUnsupported);
+        
+        let player = ctx.accounts.player_token_account.owner;
+        let credentials = JoinCredentials {
+            allowlist: ctx.accounts.allowlist.as_ref(),
+            invite_code: invite_code.as_deref(),
+            nft_token_account: ctx.accounts.nft_token_account.as_ref(),
+            nft_metadata: ctx.accounts.nft_metadata.as_ref(),
+            gate_token_account: ctx.accounts.gate_token_account.as_ref(),
+            profile: Some(&ctx.accounts.profile),
+        };
+        check_join_gates(game, &credentials, &player)?;
+        let seat = game.seat_player(player, clock.unix_timestamp)?;
+        game.paid[seat] = game.stake_amount;
+        apply_withdrawal_addresses(game, seat, &ctx.accounts.profile)?;
+        
+        let seeds = &[b"join_delegate".as_ref(), &[ctx.bumps.join_delegate]];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.player_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.join_delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, game.stake_amount, ctx.accounts.mint.decimals)?;
+        
+        emit!(PlayerJoined {
+            game_id: game.game_id,
+            player,
+            player_count: game.player_count,
+        });
+        
+        emit!(StakePulled {
+            game_id: game.game_id,
+            player,
+            amount: game.stake_amount,
+        });
+        
+        ctx.accounts.vault_ledger.settle(game, &mut ctx.accounts.vault)?;
+        
+        Ok(())
+    }
+
+    /// Register an ephemeral keypair that may act for the caller in game
+    /// until `expires_at`, limited to the `SESSION_*` bits in `permissions`
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        expires_at: i64,
+        permissions: u8,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        
+        require!(
+            expires_at > clock.unix_timestamp
+                && expires_at - clock.unix_timestamp <= MAX_SESSION_SECS,
+            EscrowError::InvalidDuration
+        );
+        require!(
+            permissions != 0 && permissions & !(SESSION_SUBMIT_MOVES | SESSION_FORFEIT) == 0,
+            EscrowError::SessionNotPermitted
+        );
+        
+        let session = &mut ctx.accounts.session;
+        session.wallet = ctx.accounts.wallet.key();
+        session.session_key = session_key;
+        session.expires_at = expires_at;
+        session.permissions = permissions;
+        session.bump = ctx.bumps.session;
+        
+        emit!(SessionCreated {
+            wallet: session.wallet,
+            session_key,
+            expires_at,
+            permissions,
+        });
+        
+        Ok(())
+    }
+
+    /// Revoke a session key before it expires - returns the rent
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        emit!(SessionRevoked {
+            wallet: ctx.accounts.wallet.key(),
+            session_key: ctx.accounts.session.session_key,
+        });
+        
+        Ok(())
+    }
+
+    /// Concede an engine game - the seat is out and, if a single seat is
+    /// left, the engine declares it the winner
+    pub fn forfeit(ctx: Context<Forfeit>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let clock = Clock::get()?;
+        let player = acting_wallet(
+            &ctx.accounts.player.key(),
+            ctx.accounts.session.as_ref(),
+            SESSION_FORFEIT,
+            clock.unix_timestamp,
+        )?;
+        
+        require!(game.status == GameStatus::Live, EscrowError::GameNotLive);
+        require!(!game.seed_pending, EscrowError::SeedPending);
+        let seat = game.seat_of(&player).ok_or(EscrowError::NotInGame)?;
+        let game_id = game.game_id;
+        
+        let engine = game.engine.as_mut().ok_or(EscrowError::EngineModeRequired)?;
+        require!(engine.winner.is_none(), EscrowError::GameNotLive);
+        let bit = 1u8 << seat;
+        require!(engine.alive & bit != 0, EscrowError::PlayerEliminated);
+        engine.alive &= !bit;
+        engine.moved &= !bit;
+        
+        emit!(PlayerForfeited {
+            game_id,
+            player,
+            round: engine.round,
+        });
+        
+        if engine.alive.count_ones() == 1 {
+            engine.winner = Some(engine.alive.trailing_zeros() as u8);
+            emit!(EngineRoundResolved {
+                game_id,
+                round: engine.round,
+                eliminated: bit,
+                winner: engine.winner,
+            });
+        } else if engine.moved == engine.alive {
+            game.resolve_engine_round(clock.unix_timestamp)?;
+        }
+        
+        Ok(())
+    }
+
+    /// Record a finished game on a seated player's profile and their stats
+    /// for the current season - permissionless, once per seat, so the
+    /// backend can crank every seat after settlement
+    pub fn record_game_stats(ctx: Context<RecordGameStats>, player_index: u8) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        let index = player_index as usize;
+        require_keys_eq!(
+            game.players[index],
+            ctx.accounts.player.key(),
+            EscrowError::WrongPlayer
+        );
+        let bit = 1u8 << index;
+        require!(game.stats_recorded & bit == 0, EscrowError::StatsAlreadyRecorded);
+        game.stats_recorded |= bit;
+        
+        let won = !game.lost(index);
+        let staked = game.refund_of(index);
+        let winnings = game.winnings[index];
+        
+        let profile = &mut ctx.accounts.profile;
+        profile.player = game.players[index];
+        profile.bump = ctx.bumps.profile;
+        profile.games_played = profile
+            .games_played
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        if won {
+            profile.wins = profile.wins.checked_add(1).ok_or(EscrowError::MathOverflow)?;
+            profile.win_streak = profile.win_streak.saturating_add(1);
+        } else {
+            profile.losses = profile.losses.checked_add(1).ok_or(EscrowError::MathOverflow)?;
+            profile.win_streak = 0;
+        }
+        profile.total_staked = profile
+            .total_staked
+            .checked_add(staked)
+            .ok_or(EscrowError::MathOverflow)?;
+        profile.total_won = profile
+            .total_won
+            .checked_add(winnings)
+            .ok_or(EscrowError::MathOverflow)?;
+        profile.record_in_mint(game.stake_mint, staked, winnings)?;
+        
+        let season_stats = &mut ctx.accounts.season_stats;
+        season_stats.player = profile.player;
+        season_stats.season = ctx.accounts.config.stats_season;
+        season_stats.bump = ctx.bumps.season_stats;
+        season_stats.games_played = season_stats
+            .games_played
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        if won {
+            season_stats.wins = season_stats
+                .wins
+                .checked_add(1)
+                .ok_or(EscrowError::MathOverflow)?;
+        } else {
+            season_stats.losses = season_stats
+                .losses
+                .checked_add(1)
+                .ok_or(EscrowError::MathOverflow)?;
+        }
+        season_stats.total_staked = season_stats
+            .total_staked
+            .checked_add(staked)
+            .ok_or(EscrowError::MathOverflow)?;
+        season_stats.total_won = season_stats
+            .total_won
+            .checked_add(winnings)
+            .ok_or(EscrowError::MathOverflow)?;
+        
+        if let Some(leaderboard) = ctx.accounts.leaderboard.as_mut() {
+            let net_winnings = profile.net_winnings_in(&ctx.accounts.config.stake_mint);
+            leaderboard.update(profile.player, net_winnings);
+        }
+        
+        emit!(GameStatsRecorded {
+            game_id: game.game_id,
+            player: profile.player,
+            mint: game.stake_mint,
+            won,
+            staked,
+            winnings,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the global leaderboard `record_game_stats` ranks players on
+    /// (admin only)
+    pub fn create_leaderboard(ctx: Context<CreateLeaderboard>) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.entry_count = 0;
+        leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+        leaderboard.bump = ctx.bumps.leaderboard;
+        
+        Ok(())
+    }
+
+    /// Apply a finished game's placements to every seat's Elo rating -
+    /// permissionless, once per game, normally sent with the settlement
+    /// Placements come from the elimination log when complete; otherwise
+    /// the winners share first place and everyone else ties behind them
+    /// Remaining accounts: every seat's player profile, in seat order
+    pub fn update_ratings<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateRatings<'info>>) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(!game.ratings_updated, EscrowError::RatingsAlreadyUpdated);
+        let seats = game.player_count as usize;
+        require!(
+            ctx.remaining_accounts.len() == seats,
+            EscrowError::ProfileAccountsRequired
+        );
+        game.ratings_updated = true;
+        
+        let mut ranks = [0u8; MAX_PLAYERS];
+        match game.logged_placements() {
+            Some(placements) => {
+                for (place, &seat) in placements.iter().enumerate() {
+                    ranks[seat as usize] = place as u8;
+                }
+            }
+            None => {
+                for (seat, rank) in ranks.iter_mut().enumerate().take(seats) {
+                    *rank = game.lost(seat) as u8;
+                }
+            }
+        }
+        
+        let mut profiles = Vec::with_capacity(seats);
+        let mut ratings = [0u32; MAX_PLAYERS];
+        for (seat, info) in ctx.remaining_accounts.iter().enumerate() {
+            let profile: Account<PlayerProfile> = Account::try_from(info)?;
+            require_keys_eq!(profile.player, game.players[seat], EscrowError::WrongPlayer);
+            ratings[seat] = profile.current_rating();
+            profiles.push(profile);
+        }
+        
+        let deltas = rating::rating_deltas(&ratings[..seats], &ranks[..seats]);
+        for (seat, profile) in profiles.iter_mut().enumerate() {
+            profile.rating = ratings[seat]
+                .saturating_add_signed(deltas[seat])
+                .max(rating::MIN_RATING);
+            profile.rated_games = profile.rated_games.saturating_add(1);
+            profile.exit(&crate::ID)?;
+            
+            emit!(RatingUpdated {
+                game_id: game.game_id,
+                player: profile.player,
+                rating: profile.rating,
+                change: deltas[seat],
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Close the current stats season and start the next - admin only
+    /// Lifetime profile stats carry on; season stats start from zero
+    pub fn start_stats_season(ctx: Context<StartStatsSeason>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let clock = Clock::get()?;
+        
+        config.stats_season = config
+            .stats_season
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        config.stats_season_started_at = clock.unix_timestamp;
+        
+        emit!(StatsSeasonStarted {
+            season: config.stats_season,
+            started_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Unlock an achievement a player earned in `game` - permissionless,
+    /// once per player and kind, so the backend can crank it after
+    /// `record_game_stats`
+    pub fn unlock_achievement(
+        ctx: Context<UnlockAchievement>,
+        player_index: u8,
+        kind: AchievementKind,
+    ) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let profile = &ctx.accounts.profile;
+        let clock = Clock::get()?;
+        
+        require!(game.status == GameStatus::Finished, EscrowError::GameNotFinished);
+        require!(player_index < game.player_count, EscrowError::InvalidPlayer);
+        let index = player_index as usize;
+        let player = game.players[index];
+        require_keys_eq!(player, profile.player, EscrowError::WrongPlayer);
+        require!(
+            game.stats_recorded & (1 << index) != 0,
+            EscrowError::AchievementNotEarned
+        );
+        
+        let earned = match kind {
+            AchievementKind::FirstWin => !game.lost(index),
+            AchievementKind::WinStreak => {
+                !game.lost(index) && profile.win_streak >= ACHIEVEMENT_WIN_STREAK
+            }
+            AchievementKind::JackpotClear => game.jackpot_claimed && game.winner == Some(player),
+        };
+        require!(earned, EscrowError::AchievementNotEarned);
+        
+        let achievement = &mut ctx.accounts.achievement;
+        achievement.player = player;
+        achievement.kind = kind;
+        achievement.game_id = game.game_id;
+        achievement.unlocked_at = clock.unix_timestamp;
+        achievement.bump = ctx.bumps.achievement;
+        
+        emit!(AchievementUnlocked {
+            player,
+            kind,
+            game_id: game.game_id,
+        });
+        
+        Ok(())
+    }
+
+    /// Register `operator` as an agent key whose winnings are paid to the
+    /// signing payout wallet - the operator can then join and play games
+    /// without ever holding the winnings
+    pub fn register_agent(ctx: Context<RegisterAgent>, operator: Pubkey) -> Result<()> {
+        let agent = &mut ctx.accounts.agent;
+        agent.operator = operator;
+        agent.payout_wallet = ctx.accounts.payout_wallet.key();
+        agent.bump = ctx.bumps.agent;
+        
+        emit!(AgentRegistered {
+            operator,
+            payout_wallet: agent.payout_wallet,
+        });
+        
+        Ok(())
+    }
+
+    /// Deregister an agent - payout wallet only; games it already joined
+    /// keep paying the payout wallet
+    pub fn close_agent(ctx: Context<CloseAgent>) -> Result<()> {
+        emit!(AgentClosed {
+            operator: ctx.accounts.agent.operator,
+            payout_wallet: ctx.accounts.payout_wallet.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Replace the wallets the caller's payouts and balance withdrawals may
+    /// go to - an empty list lifts the restriction
+    /// Narrowing the list only needs the player; adding an address or
+    /// lifting the restriction also needs one of the registered addresses
+    /// to sign as approver, so a compromised hot wallet cannot redirect
+    /// its own payouts
+    /// Seats already taken keep the payout wallet they joined with
+    pub fn set_withdrawal_addresses(
+        ctx: Context<SetWithdrawalAddresses>,
+        addresses: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            addresses.len() <= MAX_WITHDRAWAL_ADDRESSES
+                && addresses.iter().all(|address| *address != Pubkey::default()),
+            EscrowError::InvalidWithdrawalAddresses
+        );
+        
+        let profile = &mut ctx.accounts.profile;
+        let current = profile.withdrawal_addresses();
+        let loosens = !current.is_empty()
+            && (addresses.is_empty() || addresses.iter().any(|address| !current.contains(address)));
+        if loosens {
+            let approver = ctx
+                .accounts
+                .approver
+                .as_ref()
+                .ok_or(EscrowError::WithdrawalChangeNotApproved)?;
+            require!(
+                current.contains(&approver.key()),
+                EscrowError::WithdrawalChangeNotApproved
+            );
+        }
+        
+        profile.player = ctx.accounts.player.key();
+        profile.bump = ctx.bumps.profile;
+        profile.withdrawal_addresses = [Pubkey::default(); MAX_WITHDRAWAL_ADDRESSES];
+        profile.withdrawal_addresses[..addresses.len()].copy_from_slice(&addresses);
+        profile.withdrawal_address_count = addresses.len() as u8;
+        
+        emit!(WithdrawalAddressesSet {
+            player: profile.player,
+            addresses,
+        });
+        
+        Ok(())
+    }
+
+    /// Open a page of the caller's game index - a new page is opened
+    /// whenever the last one fills up
+    pub fn create_game_index_page(ctx: Context<CreateGameIndexPage>, page: u16) -> Result<()> {
+        let game_index = &mut ctx.accounts.game_index;
+        
+        game_index.player = ctx.accounts.player.key();
+        game_index.page = page;
+        game_index.count = 0;
+        game_index.games = [Pubkey::default(); GAME_INDEX_PAGE_SIZE];
+        game_index.bump = ctx.bumps.game_index;
+        
+        Ok(())
+    }
+
+    /// Replace a settled game with its compact history record, closing the
+    /// game and returning its rent to the creator
+    /// Callable by the creator or the game authority, once the game's vault
+    /// balance is spent, `GAME_CLOSE_DELAY_SECS` have passed since it ended
+    /// and any betting pool on it is settled
+    pub fn archive_game(ctx: Context<ArchiveGame>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        game.check_closable(clock.unix_timestamp)?;
+        check_betting_pool_settled(&ctx.accounts.betting_pool)?;
+        
+        let archive = &mut ctx.accounts.archive;
+        archive.game = game.key();
+        archive.game_id = game.game_id;
+        archive.status = game.status;
+        archive.stake_mint = game.stake_mint;
+        archive.player_count = game.player_count;
+        let seats = game.players.len();
+        archive.players[..seats].copy_from_slice(&game.players);
+        archive.paid[..seats].copy_from_slice(&game.paid);
+        archive.winnings[..seats].copy_from_slice(&game.winnings);
+        archive.winner = game.winner;
+        archive.house_fee = game.house_fee;
+        archive.seed = game.seed;
+        archive.created_at = game.created_at;
+        archive.started_at = game.started_at;
+        archive.ended_at = game.finished_at.or(game.cancelled_at);
+        archive.bump = ctx.bumps.archive;
+        
+        emit!(GameArchived {
+            game_id: game.game_id,
+            archive: archive.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Close a settled game, returning its rent to the creator
+    /// Callable by the creator or the game authority, under the same
+    /// conditions as `archive_game`
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        let game = &ctx.accounts.game;
+        let clock = Clock::get()?;
+        
+        game.check_closable(clock.unix_timestamp)?;
+        check_betting_pool_settled(&ctx.accounts.betting_pool)?;
+        
+        emit!(GameClosed {
+            game_id: game.game_id,
+            creator: game.creator,
+        });
+        
+        Ok(())
+    }
+
+    /// Upgrade a game created under an older account layout to the
+    /// current one, so in-flight games survive schema changes
+    /// Anyone may call this; the payer covers any extra rent and rent
+    /// freed by a smaller layout or the closed per-game vault goes back
+    /// to the creator
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        let info = ctx.accounts.game.to_account_info();
+        
+        let (version, mut game) = legacy::decode_game(&info.try_borrow_data()?)?;
+        require_keys_eq!(
+            ctx.accounts.creator.key(),
+            game.creator,
+            EscrowError::Unauthorized
+        );
+        require_keys_eq!(
+            ctx.accounts.mint.key(),
+            game.stake_mint,
+            EscrowError::InvalidMint
+        );
+        
+        // Every version before 3 held its funds in a vault of its own:
+        // move them to the shared vault and close the old one
+        let (legacy_vault, legacy_bump) =
+            Pubkey::find_program_address(&[b"vault", game.game_id.as_ref()], ctx.program_id);
+        require_keys_eq!(
+            ctx.accounts.legacy_vault.key(),
+            legacy_vault,
+            EscrowError::InvalidLegacyVault
+        );
+        let game_id = game.game_id;
+        let seeds = &[
+            b"vault",
+            game_id.as_ref(),
+            &[legacy_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.legacy_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.legacy_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(
+            cpi_ctx,
+            ctx.accounts.legacy_vault.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        
+        let cpi_accounts_close = CloseAccount {
+            account: ctx.accounts.legacy_vault.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: ctx.accounts.legacy_vault.to_account_info(),
+        };
+        let cpi_ctx_close = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_close,
+            signer,
+        );
+        token_interface::close_account(cpi_ctx_close)?;
+        ctx.accounts.vault_ledger.settle(&mut game, &mut ctx.accounts.vault)?;
+        
+        let space = Game::space(game.max_players);
+        let rent = Rent::get()?.minimum_balance(space);
+        let lamports = info.lamports();
+        if rent > lamports {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: info.clone(),
+            };
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+            );
+            system_program::transfer(cpi_ctx, rent - lamports)?;
+        } else {
+            info.sub_lamports(lamports - rent)?;
+            ctx.accounts.creator.add_lamports(lamports - rent)?;
+        }
+        info.realloc(space, false)?;
+        game.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        
+        emit!(GameMigrated {
+            game: info.key(),
+            from_version: version,
+            to_version: GAME_VERSION,
+        });
+        
+        Ok(())
+    }
+
+    /// Create the shared vault and its ledger for a stake mint, holding
+    /// the funds of every game staked in it (admin only)
+    pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
+        let mint = ctx.accounts.mint.key();
+        require!(ctx.accounts.config.accepts_mint(&mint), EscrowError::InvalidMint);
+        
+        let vault_ledger = &mut ctx.accounts.vault_ledger;
+        vault_ledger.mint = mint;
+        vault_ledger.liabilities = 0;
+        vault_ledger.bump = ctx.bumps.vault_ledger;
+        
+        emit!(VaultCreated {
+            mint,
+            vault: ctx.accounts.vault.key(),
+        });
+        
+        Ok(())
+    }
+
+    /// Fold a player's stats bucket for a delisted mint into the bucket of
+    /// `into`, or drop it, freeing the slot - admin only
+    /// Lifetime totals across mints are left as they are
+    pub fn merge_stats_bucket(
+        ctx: Context<MergeStatsBucket>,
+        mint: Pubkey,
+        into: Option<Pubkey>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        
+        require!(!config.accepts_mint(&mint), EscrowError::MintStillListed);
+        if let Some(into) = into {
+            require!(config.accepts_mint(&into), EscrowError::InvalidMint);
+        }
+        let profile = &mut ctx.accounts.profile;
+        let merged = profile.merge_mint_stats(&mint, into)?;
+        
+        emit!(StatsBucketMerged {
+            player: profile.player,
+            mint,
+            into,
+            staked: merged.staked,
+            won: merged.won,
+        });
+        
+        Ok(())
+    }
+}
+
+// === Helpers ===
+
+/// Generate a board seed from the most recent slot hash + game id
+/// A leader can still bias this - use a VRF source where that matters
+fn derive_seed(game_id: &[u8; 8], clock: &Clock, slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let recent = recent_slot_hash(slot_hashes)?;
+    Ok(hashv(&[
+        game_id,
+        &recent,
+        &clock.slot.to_le_bytes(),
+        &clock.unix_timestamp.to_le_bytes(),
+    ])
+    .to_bytes())
+}
+
+/// Validate lobby size limits and the payout schedule
+/// Shared by `create_game` and `create_game_template`
+fn validate_lobby(min_players: u8, max_players: u8, payout_schedule: &[u16]) -> Result<()> {
+    // Every paid place must exist in the smallest allowed lobby, and
+    // the places must share out the whole prize pool left after rake.
+    // Payouts go by position, so an unpaid place may not sit between or
+    // before paid ones
+    require!(
+        !payout_schedule.is_empty()
+            && payout_schedule.len() <= min_players as usize
+            && !payout_schedule.contains(&0),
+        EscrowError::InvalidPayoutSchedule
+    );
+    let schedule_total: u64 = payout_schedule.iter().map(|&bps| bps as u64).sum();
+    require!(
+        schedule_total == BPS_DENOMINATOR,
+        EscrowError::InvalidPayoutSchedule
+    );
+    require!(
+        min_players >= MIN_PLAYERS
+            && min_players <= max_players
+            && max_players as usize <= MAX_PLAYERS,
+        EscrowError::InvalidPlayerLimits
+    );
+    Ok(())
+}
+
+/// What a joiner presents to pass a game's join gates
+struct JoinCredentials<'a, 'info> {
+    allowlist: Option<&'a Account<'info, Allowlist>>,
+    invite_code: Option<&'a [u8]>,
+    nft_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    nft_metadata: Option<&'a Account<'info, MetadataAccount>>,
+    gate_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    profile: Option<&'a AccountInfo<'info>>,
+}
+
+/// Check that `player` may take a seat in `game`
+/// Shared by every instruction that seats a player
+fn check_join_gates(
+    game: &Account<Game>,
+    credentials: &JoinCredentials,
+    player: &Pubkey,
+) -> Result<()> {
+    if game.allowlisted {
+        let allowlist = credentials.allowlist.ok_or(EscrowError::NotAllowlisted)?;
+        require_keys_eq!(allowlist.game, game.key(), EscrowError::NotAllowlisted);
+        require!(allowlist.contains(player), EscrowError::NotAllowlisted);
+    }
+    if let Some(expected) = game.invite_code_hash {
+        let code = credentials.invite_code.ok_or(EscrowError::InvalidInviteCode)?;
+        require!(hash(code).to_bytes() == expected, EscrowError::InvalidInviteCode);
+    }
+    if let Some(collection) = game.nft_collection {
+        // The joiner must hold an NFT whose metadata is verified as part
+        // of the gating collection
+        let token_account = credentials
+            .nft_token_account
+            .ok_or(EscrowError::NftGateFailed)?;
+        let metadata = credentials.nft_metadata.ok_or(EscrowError::NftGateFailed)?;
+        require!(
+            token_account.owner == *player && token_account.amount >= 1,
+            EscrowError::NftGateFailed
+        );
+        require_keys_eq!(metadata.mint, token_account.mint, EscrowError::NftGateFailed);
+        require!(
+            metadata
+                .collection
+                .as_ref()
+                .is_some_and(|c| c.verified && c.key == collection),
+            EscrowError::NftGateFailed
+        );
+    }
+    if let Some(gate) = game.balance_gate {
+        let token_account = credentials
+            .gate_token_account
+            .ok_or(EscrowError::BalanceGateFailed)?;
+        require!(
+            token_account.owner == *player
+                && token_account.mint == gate.mint
+                && token_account.amount >= gate.min_amount,
+            EscrowError::BalanceGateFailed
+        );
+    }
+    if let Some(band) = game.rating_band {
+        let profile = credentials.profile.ok_or(EscrowError::ProfileAccountsRequired)?;
+        let (address, _) = Pubkey::find_program_address(&[b"profile", player.as_ref()], &ID);
+        require_keys_eq!(profile.key(), address, EscrowError::ProfileAccountsRequired);
+        require!(band.admits(profile_rating(profile)?), EscrowError::RatingOutOfBand);
+    }
+    Ok(())
+}
+
+/// Rating on the profile at `profile`, or the starting rating if the
+/// player has no profile
+fn profile_rating(profile: &AccountInfo) -> Result<u32> {
+    if profile.data_is_empty() {
+        return Ok(rating::INITIAL_RATING);
+    }
+    require_keys_eq!(*profile.owner, crate::ID, EscrowError::WrongPlayer);
+    let data = profile.try_borrow_data()?;
+    Ok(PlayerProfile::try_deserialize(&mut &data[..])?.current_rating())
+}
+
+/// Withdrawal destinations registered on the profile at `profile`, empty
+/// if the player has no profile or registered none
+fn withdrawal_addresses(profile: &AccountInfo) -> Result<Vec<Pubkey>> {
+    if profile.data_is_empty() {
+        return Ok(Vec::new());
+    }
+    require_keys_eq!(*profile.owner, crate::ID, EscrowError::WrongPlayer);
+    let data = profile.try_borrow_data()?;
+    let profile = PlayerProfile::try_deserialize(&mut &data[..])?;
+    Ok(profile.withdrawal_addresses().to_vec())
+}
+
+/// Require the betting pool at `betting_pool` to be settled or voided, so
+/// its bets no longer need the game; a game without one passes
+fn check_betting_pool_settled(betting_pool: &AccountInfo) -> Result<()> {
+    if betting_pool.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*betting_pool.owner, crate::ID, EscrowError::InvalidGameAccount);
+    let data = betting_pool.try_borrow_data()?;
+    let pool = BettingPool::try_deserialize(&mut &data[..])?;
+    require!(pool.status != BettingStatus::Open, EscrowError::BettingNotSettled);
+    Ok(())
+}
+
+/// Require a payout to `destination` to go to one of the withdrawal
+/// addresses registered on `profile`, if it has any
+fn check_withdrawal_address(profile: &AccountInfo, destination: &Pubkey) -> Result<()> {
+    let allowed = withdrawal_addresses(profile)?;
+    require!(
+        allowed.is_empty() || allowed.contains(destination),
+        EscrowError::WithdrawalAddressNotAllowed
+    );
+    Ok(())
+}
+
+/// Point a seat's payouts at the first of its player's withdrawal
+/// addresses, unless the seat already pays to one of them
+/// `profile` must be the player's profile PDA, which need not exist
+fn apply_withdrawal_addresses(game: &mut Game, seat: usize, profile: &AccountInfo) -> Result<()> {
+    let (address, _) =
+        Pubkey::find_program_address(&[b"profile", game.players[seat].as_ref()], &ID);
+    require_keys_eq!(profile.key(), address, EscrowError::ProfileAccountsRequired);
+    
+    let allowed = withdrawal_addresses(profile)?;
+    if !allowed.is_empty() && !allowed.contains(&game.payout_owner(seat)) {
+        game.payout_wallets[seat] = allowed[0];
+    }
+    Ok(())
+}
+
+/// Charge `payer` the config's listing fee for putting a lobby on a
+/// registry page
+fn pay_listing_fee<'info>(
+    config: &Config,
+    payer: &Signer<'info>,
+    admin: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if config.listing_fee == 0 {
+        return Ok(());
+    }
+    let cpi_accounts = system_program::Transfer {
+        from: payer.to_account_info(),
+        to: admin.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, config.listing_fee)
+}
+
+/// Borrow a game's move log for appending, initializing it on first use
+fn load_move_log<'a>(move_log: &'a AccountLoader<MoveLog>) -> Result<RefMut<'a, MoveLog>> {
+    match move_log.load_mut() {
+        Ok(loaded) => Ok(loaded),
+        Err(_) => move_log.load_init(),
+    }
+}
+
+/// Wallet acting through `signer`: the signer itself, or the wallet that
+/// registered it as an unexpired session key allowed `permission`
+fn acting_wallet(
+    signer: &Pubkey,
+    session: Option<&Account<SessionKey>>,
+    permission: u8,
+    now: i64,
+) -> Result<Pubkey> {
+    let Some(session) = session else {
+        return Ok(*signer);
+    };
+    require!(now <= session.expires_at, EscrowError::SessionExpired);
+    require!(
+        session.permissions & permission != 0,
+        EscrowError::SessionNotPermitted
+    );
+    Ok(session.wallet)
+}
+
+/// Check that `nft_account` holds a verified piece of the NFT game's
+/// collection, move it into its escrow and seat the staker
+/// Shared by `create_nft_game` and `join_nft_game`
+fn escrow_nft<'info>(
+    nft_game: &mut NftGame,
+    metadata: &Account<'info, MetadataAccount>,
+    nft_account: &InterfaceAccount<'info, TokenAccount>,
+    nft_escrow: &InterfaceAccount<'info, TokenAccount>,
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    player: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    require!(
+        nft_mint.decimals == 0 && nft_mint.supply == 1,
+        EscrowError::NftGateFailed
+    );
+    require_keys_eq!(metadata.mint, nft_mint.key(), EscrowError::NftGateFailed);
+    require!(
+        metadata
+            .collection
+            .as_ref()
+            .is_some_and(|c| c.verified && c.key == nft_game.collection),
+        EscrowError::NftGateFailed
+    );
+    
+    let cpi_accounts = TransferChecked {
+        from: nft_account.to_account_info(),
+        mint: nft_mint.to_account_info(),
+        to: nft_escrow.to_account_info(),
+        authority: player.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        cpi_accounts,
+    );
+    token_interface::transfer_checked(cpi_ctx, 1, nft_mint.decimals)?;
+    
+    let seat = nft_game.player_count as usize;
+    nft_game.players[seat] = player.key();
+    nft_game.nfts[seat] = nft_mint.key();
+    nft_game.player_count += 1;
+    Ok(())
+}
+
+/// Refund the stake of the waiting player at `index` and free their seat
+/// Shared by `leave_game`, `kick_player`, and the strike auto-kick
+fn unseat_and_refund<'info>(
+    game: &mut Account<'info, Game>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    player_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    vault_bump: u8,
+    index: usize,
+) -> Result<u64> {
+    let stake_mint = game.stake_mint;
+    let seeds = &[
+        b"vault",
+        stake_mint.as_ref(),
+        &[vault_bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: player_token_account.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    let refunded = game.refund_of(index);
+    token_interface::transfer_checked(cpi_ctx, refunded, mint.decimals)?;
+    
+    game.remove_player(index);
+    Ok(refunded)
+}
+
+/// Record a payout of `amount` as seat `index`'s winnings and hold it
+/// back in the vault to vest when it is above the config's vesting
+/// threshold
+/// Returns whether it vested, in which case it must not be paid out now
+fn vest_payout(game: &mut Game, index: usize, amount: u64, config: &Config) -> Result<bool> {
+    game.winnings[index] = game.winnings[index]
+        .checked_add(amount)
+        .ok_or(EscrowError::MathOverflow)?;
+    
+    let Some(terms) = config.vesting else {
+        return Ok(false);
+    };
+    if game.stake_mint != config.stake_mint || amount <= terms.threshold {
+        return Ok(false);
+    }
+    
+    game.vesting[index] = game.vesting[index]
+        .checked_add(amount)
+        .ok_or(EscrowError::MathOverflow)?;
+    game.vesting_period = terms.period;
+    
+    emit!(PayoutVested {
+        game_id: game.game_id,
+        player: game.players[index],
+        amount,
+        period: terms.period,
+    });
+    
+    Ok(true)
+}
+
+/// Token-2022 mints that withhold a fee on transfer would leave the vault
+/// short of the pot it accounts for, so they cannot back a game
+fn reject_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    if mint.to_account_info().owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(
+        state.get_extension::<TransferFeeConfig>().is_err(),
+        EscrowError::UnsupportedMintExtension
+    );
+    Ok(())
+}
+
+/// Create `owner`'s associated token account for `mint` unless it already
+/// exists, so paying a fresh wallet needs no out-of-band setup
+fn create_payout_account<'info>(
+    payer: &impl ToAccountInfo<'info>,
+    token_account: &UncheckedAccount<'info>,
+    owner: &impl ToAccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let owner = owner.to_account_info();
+    require_keys_eq!(
+        token_account.key(),
+        get_associated_token_address_with_program_id(owner.key, &mint.key(), &token_program.key()),
+        EscrowError::InvalidWinner
+    );
+    
+    let cpi_accounts = associated_token::Create {
+        payer: payer.to_account_info(),
+        associated_token: token_account.to_account_info(),
+        authority: owner,
+        mint: mint.to_account_info(),
+        system_program: system_program.to_account_info(),
+        token_program: token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
+    associated_token::create_idempotent(cpi_ctx)
+}
+
+/// Pay the creator their `game.creator_fee_bps` share of a house fee out
+/// of the vault, returning the amount paid
+fn pay_creator_fee<'info>(
+    game: &Game,
+    house_fee: u64,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    creator_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    let creator_fee = payout::creator_cut(house_fee, game.creator_fee_bps)?;
+    if creator_fee == 0 {
+        return Ok(0);
+    }
+    
+    let creator_token_account = creator_token_account
+        .as_ref()
+        .ok_or(EscrowError::CreatorAccountRequired)?;
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: creator_token_account.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, creator_fee, mint.decimals)?;
+    
+    emit!(CreatorFeePaid {
+        game_id: game.game_id,
+        creator: game.creator,
+        amount: creator_fee,
+    });
+    
+    Ok(creator_fee)
+}
+
+/// Split the settlement accounts that follow the winners' into the fee
+/// split recipients' accounts and the (referral, referrer balance) pairs
+fn split_fee_accounts<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    config: &Config,
+) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+    require!(
+        accounts.len() >= config.fee_split.len(),
+        EscrowError::InvalidFeeRecipient
+    );
+    let (fee_accounts, referral_accounts) = accounts.split_at(config.fee_split.len());
+    require!(
+        referral_accounts.chunks_exact(2).remainder().is_empty(),
+        EscrowError::InvalidReferral
+    );
+    Ok((fee_accounts, referral_accounts))
+}
+
+/// Credit each referred player's referrer with `config.referral_bps` of
+/// the player's stake-weighted part of a house fee, moving the credited
+/// amount from the vault into the referral pool
+/// `referral_accounts` holds a (referral, referrer balance) pair per
+/// referred player; returns the total credited
+#[allow(clippy::too_many_arguments)]
+fn pay_referral_fees<'info>(
+    game: &Game,
+    house_fee: u64,
+    referral_accounts: &'info [AccountInfo<'info>],
+    config: &Config,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    referral_pool: &Option<InterfaceAccount<'info, TokenAccount>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    // The house fee comes out of stakes, so a zero fee also means no stakes
+    if referral_accounts.is_empty() || config.referral_bps == 0 || house_fee == 0 {
+        return Ok(0);
+    }
+    
+    let stakes = game.total_stakes()?;
+    let mut credited_seats = 0u8;
+    let mut total = 0u64;
+    for pair in referral_accounts.chunks_exact(2) {
+        let referral: Account<Referral> = Account::try_from(&pair[0])?;
+        let mut balance: Account<ReferrerBalance> = Account::try_from(&pair[1])?;
+        let index = game
+            .seat_of(&referral.player)
+            .ok_or(EscrowError::InvalidReferral)?;
+        require!(credited_seats & (1 << index) == 0, EscrowError::InvalidReferral);
+        credited_seats |= 1 << index;
+        require!(
+            balance.referrer == referral.referrer && balance.mint == mint.key(),
+            EscrowError::InvalidReferral
+        );
+        
+        let amount = payout::referral_cut(
+            house_fee,
+            game.stake_of(index),
+            stakes,
+            config.referral_bps,
+        )?;
+        balance.claimable = balance
+            .claimable
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        balance.total_earned = balance
+            .total_earned
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        balance.exit(&crate::ID)?;
+        total += amount;
+        
+        emit!(ReferralCredited {
+            game_id: game.game_id,
+            player: referral.player,
+            referrer: referral.referrer,
+            amount,
+        });
+    }
+    
+    if total > 0 {
+        let referral_pool = referral_pool
+            .as_ref()
+            .ok_or(EscrowError::ReferralPoolRequired)?;
+        let cpi_accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: referral_pool.to_account_info(),
+            authority: vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, total, mint.decimals)?;
+    }
+    
+    Ok(total)
+}
+
+/// Divert the `config.jackpot_bps` share of a house fee from the vault
+/// into the jackpot pool, returning the amount diverted
+fn pay_jackpot_fee<'info>(
+    house_fee: u64,
+    config: &Config,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    jackpot: &Option<InterfaceAccount<'info, TokenAccount>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    let jackpot_fee = payout::jackpot_cut(house_fee, config.jackpot_bps)?;
+    if jackpot_fee == 0 {
+        return Ok(0);
+    }
+    
+    let jackpot = jackpot.as_ref().ok_or(EscrowError::JackpotRequired)?;
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: jackpot.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, jackpot_fee, mint.decimals)?;
+    
+    Ok(jackpot_fee)
+}
+
+/// Pay a house fee out of a vault: burn the `config.rake_burn` share if
+/// the vault holds the native token (the mint must then be writable),
+/// then each `config.fee_split` share of the rest to its recipient's token
+/// account, passed in `fee_accounts` in split order, and the remainder to
+/// the treasury
+#[allow(clippy::too_many_arguments)]
+fn distribute_house_fee<'info>(
+    house_fee: u64,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    treasury: &InterfaceAccount<'info, TokenAccount>,
+    fee_accounts: &[AccountInfo<'info>],
+    config: &Config,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut house_fee = house_fee;
+    if let Some(rake_burn) = config.rake_burn.filter(|burn| burn.mint == mint.key()) {
+        let burned = payout::fee_share(house_fee, rake_burn.bps);
+        let cpi_accounts = Burn {
+            mint: mint.to_account_info(),
+            from: vault.to_account_info(),
+            authority: vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::burn(cpi_ctx, burned)?;
+        house_fee -= burned;
+        
+        emit!(RakeBurned {
+            mint: mint.key(),
+            amount: burned,
+        });
+    }
+    
+    let fee_split = &config.fee_split;
+    require!(
+        fee_accounts.len() == fee_split.len(),
+        EscrowError::InvalidFeeRecipient
+    );
+    
+    let mut remainder = house_fee;
+    for (share, info) in fee_split.iter().zip(fee_accounts) {
+        {
+            require_keys_eq!(*info.owner, token_program.key(), EscrowError::InvalidFeeRecipient);
+            let data = info.try_borrow_data()?;
+            let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?.base;
+            require!(
+                account.owner == share.recipient && account.mint == mint.key(),
+                EscrowError::InvalidFeeRecipient
+            );
+        }
+        
+        let amount = payout::fee_share(house_fee, share.bps);
+        remainder -= amount;
+        let cpi_accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: info.clone(),
+            authority: vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+    }
+    
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: treasury.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, remainder, mint.decimals)
+}
+
+/// Accounts the house fee of a settlement is paid through
+struct HouseFeeAccounts<'a, 'info> {
+    config: &'a Config,
+    vault: &'a InterfaceAccount<'info, TokenAccount>,
+    treasury: &'a InterfaceAccount<'info, TokenAccount>,
+    creator_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    referral_pool: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    jackpot: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Pay a settlement's house fee out of the vault: the creator, referral
+/// and jackpot cuts, then the rest through `distribute_house_fee`
+/// `settlement_accounts` holds the fee split recipients' accounts, then a
+/// (referral, referrer balance) pair per referred player; a waived part
+/// of the fee is reported as `RakeWaived`
+fn pay_house_fee<'info>(
+    game: &Game,
+    house_fee: u64,
+    fee_waived: u64,
+    settlement_accounts: &'info [AccountInfo<'info>],
+    accounts: &HouseFeeAccounts<'_, 'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let (fee_accounts, referral_accounts) =
+        split_fee_accounts(settlement_accounts, accounts.config)?;
+    let creator_fee = pay_creator_fee(
+        game,
+        house_fee,
+        accounts.vault,
+        accounts.creator_token_account,
+        accounts.mint,
+        accounts.token_program,
+        signer,
+    )?;
+    let referral_fee = pay_referral_fees(
+        game,
+        house_fee,
+        referral_accounts,
+        accounts.config,
+        accounts.vault,
+        accounts.referral_pool,
+        accounts.mint,
+        accounts.token_program,
+        signer,
+    )?;
+    let jackpot_fee = pay_jackpot_fee(
+        house_fee,
+        accounts.config,
+        accounts.vault,
+        accounts.jackpot,
+        accounts.mint,
+        accounts.token_program,
+        signer,
+    )?;
+    distribute_house_fee(
+        house_fee - creator_fee - referral_fee - jackpot_fee,
+        accounts.vault,
+        accounts.treasury,
+        fee_accounts,
+        accounts.config,
+        accounts.mint,
+        accounts.token_program,
+        signer,
+    )?;
+    
+    if fee_waived > 0 {
+        emit!(RakeWaived {
+            id: game.game_id,
+            fee_waived,
+        });
+    }
+    Ok(())
+}
+
+/// Accounts the winner of a single-winner settlement is paid through
+struct WinnerAccounts<'a, 'info> {
+    config: &'a Config,
+    vault: &'a InterfaceAccount<'info, TokenAccount>,
+    winner: &'a UncheckedAccount<'info>,
+    winner_token_account: &'a UncheckedAccount<'info>,
+    pending_claim: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    unwrap_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    unwrap_bump: u8,
+    payer: &'a Signer<'info>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    system_program: &'a Program<'info, System>,
+}
+
+/// Pay `winner_payout` to the winner in seat `winner_index` out of the
+/// vault
+/// Winnings above the vesting threshold stay in the vault; a frozen
+/// destination diverts them to the winner's pending-claim account, and
+/// SOL is unwrapped through the game's transit account to the wallet
+fn pay_winner<'info>(
+    game: &mut Game,
+    winner_index: usize,
+    winner_payout: u64,
+    accounts: &WinnerAccounts<'_, 'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let game_id = game.game_id;
+    let native = game.stake_mint == native_mint::ID;
+    let paid_out = if vest_payout(game, winner_index, winner_payout, accounts.config)? {
+        0
+    } else {
+        winner_payout
+    };
+    
+    // A frozen destination would fail the transfer and wedge the game,
+    // so the winnings wait in the winner's pending-claim account instead
+    let destination = if native {
+        accounts
+            .unwrap_account
+            .as_ref()
+            .ok_or(EscrowError::UnwrapAccountRequired)?
+            .to_account_info()
+    } else if is_frozen(accounts.winner_token_account)? {
+        let pending_claim = accounts
+            .pending_claim
+            .as_ref()
+            .ok_or(EscrowError::PendingClaimRequired)?;
+        emit!(PayoutDiverted {
+            game_id,
+            player: game.players[winner_index],
+            amount: paid_out,
+        });
+        pending_claim.to_account_info()
+    } else {
+        accounts.winner_token_account.to_account_info()
+    };
+    
+    let cpi_accounts = TransferChecked {
+        from: accounts.vault.to_account_info(),
+        mint: accounts.mint.to_account_info(),
+        to: destination,
+        authority: accounts.vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, paid_out, accounts.mint.decimals)?;
+    
+    if native {
+        // Closing the transit account hands its lamports to the payer,
+        // who forwards the winnings - the payer keeps only its rent back
+        let unwrap_account = accounts
+            .unwrap_account
+            .as_ref()
+            .ok_or(EscrowError::UnwrapAccountRequired)?;
+        let unwrap_seeds = &[
+            b"unwrap",
+            game_id.as_ref(),
+            &[accounts.unwrap_bump],
+        ];
+        let unwrap_signer = &[&unwrap_seeds[..]];
+        let cpi_ctx_close = CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: unwrap_account.to_account_info(),
+                destination: accounts.payer.to_account_info(),
+                authority: unwrap_account.to_account_info(),
+            },
+            unwrap_signer,
+        );
+        token_interface::close_account(cpi_ctx_close)?;
+        
+        let cpi_ctx_forward = CpiContext::new(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.payer.to_account_info(),
+                to: accounts.winner.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx_forward, paid_out)?;
+    }
+    Ok(())
+}
+
+/// Record a season game's result on its season and move the season's
+/// share of `house_fee` from the vault into the season vault, returning
+/// the share; zero for a game outside any season
+#[allow(clippy::too_many_arguments)]
+fn pay_season_cut<'info>(
+    game: &Game,
+    winner: Pubkey,
+    house_fee: u64,
+    season: &mut Option<Account<'info, Season>>,
+    season_vault: &Option<InterfaceAccount<'info, TokenAccount>>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    let Some(season_key) = game.season else {
+        return Ok(0);
+    };
+    let season = season.as_mut().ok_or(EscrowError::InvalidSeason)?;
+    require_keys_eq!(season.key(), season_key, EscrowError::InvalidSeason);
+    let season_vault = season_vault.as_ref().ok_or(EscrowError::InvalidSeason)?;
+    require_keys_eq!(
+        season_vault.key(),
+        season.vault_address(&ID)?,
+        EscrowError::InvalidSeason
+    );
+    let season_cut = season.record_game(game, winner, house_fee)?;
+    if season_cut == 0 {
+        return Ok(0);
+    }
+    
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: season_vault.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, season_cut, mint.decimals)?;
+    Ok(season_cut)
+}
+
+/// Whether a token account is frozen, which makes transfers into it fail
+fn is_frozen(token_account: &AccountInfo) -> Result<bool> {
+    let data = token_account.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(state.base.is_frozen())
+}
+
+/// Split the pending-claim accounts passed after a settlement's payee
+/// token accounts off the front of `accounts` - one for each of `payees`
+/// that is frozen, in any order
+fn split_pending_claims<'a, 'info>(
+    payees: &[AccountInfo<'info>],
+    accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+    let mut frozen = 0;
+    for info in payees {
+        if is_frozen(info)? {
+            frozen += 1;
+        }
+    }
+    require!(accounts.len() >= frozen, EscrowError::PendingClaimRequired);
+    Ok(accounts.split_at(frozen))
+}
+
+/// The account a payee's share of `amount` is sent to - their token
+/// account, or their pending-claim account among `pending_claims` when the
+/// token account is frozen and the transfer would wedge the settlement
+fn payee_destination<'info>(
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    info: &AccountInfo<'info>,
+    pending_claims: &[AccountInfo<'info>],
+    game_id: [u8; 8],
+    amount: u64,
+) -> Result<AccountInfo<'info>> {
+    if !token_account.is_frozen() {
+        return Ok(info.clone());
+    }
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            b"pending_claim",
+            token_account.mint.as_ref(),
+            token_account.owner.as_ref(),
+        ],
+        &ID,
+    );
+    let pending_claim = pending_claims
+        .iter()
+        .find(|claim| claim.key() == address)
+        .ok_or(EscrowError::PendingClaimRequired)?;
+    
+    emit!(PayoutDiverted {
+        game_id,
+        player: token_account.owner,
+        amount,
+    });
+    
+    Ok(pending_claim.clone())
+}
+
+/// Remove a game from the open-lobby registry page listing it
+/// A no-op for games that were never listed
+fn delist_lobby(
+    game: &mut Account<Game>,
+    registry: Option<&mut Account<LobbyRegistry>>,
+) -> Result<()> {
+    let Some(page) = game.lobby_page else {
+        return Ok(());
+    };
+    let registry = registry.ok_or(EscrowError::InvalidLobbyRegistry)?;
+    require!(registry.page == page, EscrowError::InvalidLobbyRegistry);
+    
+    registry.remove(&game.key());
+    game.lobby_page = None;
+    Ok(())
+}
+
+// === Accounts ===
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: CreateGameParams)]
+pub struct CreateGame<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Game::space(params.max_players),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Config stake mint or an allowlisted one; the native mint stakes SOL
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// Registry page the new lobby is listed on
+    #[account(mut)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    
+    /// CHECK: Config admin, paid the listing fee
+    #[account(mut, address = config.admin @ EscrowError::Unauthorized)]
+    pub admin: UncheckedAccount<'info>,
+    
+    /// Season the game counts towards, if any
+    pub season: Option<Account<'info, Season>>,
+}
+
+#[derive(Accounts)]
+pub struct JoinGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Stake source - omit to pay a SOL-staked game in plain SOL; the
+    /// sponsor's token account when the stake is sponsored
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// Pays the stake on the player's behalf, if sponsored
+    pub sponsor: Option<Signer<'info>>,
+    
+    /// Registration of `player` as an agent operator - winnings then go
+    /// to its payout wallet
+    #[account(seeds = [b"agent", player.key().as_ref()], bump = agent.bump)]
+    pub agent: Option<Account<'info, Agent>>,
+    
+    /// CHECK: Player profile PDA, read for its withdrawal allowlist and
+    /// rating if it exists
+    #[account(seeds = [b"profile", player.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    /// Page of the player's game index the game is appended to
+    #[account(
+        mut,
+        seeds = [
+            b"game_index",
+            player.key().as_ref(),
+            game_index.page.to_le_bytes().as_ref(),
+        ],
+        bump = game_index.bump,
+    )]
+    pub game_index: Option<Box<Account<'info, PlayerGameIndex>>>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    
+    /// Allowlist of a private game - required when the game has one
+    pub allowlist: Option<Account<'info, Allowlist>>,
+    
+    /// Joiner's NFT token account - required for collection-gated games
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Metaplex metadata of the joiner's NFT
+    pub nft_metadata: Option<Account<'info, MetadataAccount>>,
+    
+    /// Joiner's account of the gating token - required for balance-gated games
+    pub gate_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Pyth SOL/USD price update - required for USD-staked games,
+    /// validated by `PythPrice::load`
+    pub price_update: Option<UncheckedAccount<'info>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    /// Insurance pool the premium is paid into - required when insuring
+    #[account(
+        mut,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+    )]
+    pub insurance_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Joiner's in-program balance - the stake is debited from it when
+    /// passed
+    #[account(
+        mut,
+        seeds = [b"balance", player.key().as_ref(), mint.key().as_ref()],
+        bump = player_balance.bump,
+    )]
+    pub player_balance: Option<Account<'info, PlayerBalance>>,
+    
+    /// Pool holding in-program balances - required with `player_balance`
+    #[account(
+        mut,
+        seeds = [b"balance_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub balance_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct KickPlayer<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub creator: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReplacePlayer<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = substitute_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub substitute_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub substitute: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Allowlist of a private game - required when the game has one
+    pub allowlist: Option<Account<'info, Allowlist>>,
+    
+    /// Joiner's NFT token account - required for collection-gated games
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Metaplex metadata of the joiner's NFT
+    pub nft_metadata: Option<Account<'info, MetadataAccount>>,
+    
+    /// Joiner's account of the gating token - required for balance-gated games
+    pub gate_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Substitute's profile PDA, read for its withdrawal allowlist
+    /// and rating if it exists
+    #[account(seeds = [b"profile", substitute.key().as_ref()], bump)]
+    pub substitute_profile: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: VRF randomness account - required for VRF randomness
+    /// sources and validated per provider in the handler
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    /// Registry page listing the game - required while the game is listed
+    #[account(mut)]
+    pub lobby_registry: Option<Account<'info, LobbyRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeResult<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: instructions sysvar, read for enclave and referee signatures
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeResult<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Creator's token account - required when the game pays a creator fee
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == game.creator @ EscrowError::WrongPlayer,
+        constraint = creator_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Pool holding referrer balances - required when referral pairs are passed
+    #[account(
+        mut,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub referral_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Jackpot pool - required when `config.jackpot_bps` is set
+    #[account(
+        mut,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Season the game counts towards - required when `game.season` is set
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+    
+    #[account(mut)]
+    pub season_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// CHECK: Winner's associated token account for the stake mint,
+    /// created if missing and address-checked by `create_payout_account`
+    #[account(mut)]
+    pub winner_token_account: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPendingClaim<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pending_claim", mint.key().as_ref(), player.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pending_claim,
+    )]
+    pub pending_claim: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Only seeds the pending-claim address
+    pub player: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPendingPayout<'info> {
+    #[account(
+        mut,
+        seeds = [b"pending_claim", mint.key().as_ref(), player.key().as_ref()],
+        bump,
+    )]
+    pub pending_claim: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// CHECK: Player profile PDA, read for its withdrawal allowlist if it
+    /// exists
+    #[account(seeds = [b"profile", player.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EndGameRanked<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Creator's token account - required when the game pays a creator fee
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == game.creator @ EscrowError::WrongPlayer,
+        constraint = creator_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Pool holding referrer balances - required when referral pairs are passed
+    #[account(
+        mut,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub referral_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Jackpot pool - required when `config.jackpot_bps` is set
+    #[account(
+        mut,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AssignTeams<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewPayout<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    pub authority: Signer<'info>,
+    
+    /// Registry page listing the game - required while the game is listed
+    #[account(mut)]
+    pub lobby_registry: Option<Account<'info, LobbyRegistry>>,
+    
+    /// Template the game was spawned from - required when its creator
+    /// cancels a spawned lobby
+    pub template: Option<Account<'info, GameTemplate>>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// Registry page listing the game - required while the game is listed
+    #[account(mut)]
+    pub lobby_registry: Option<Account<'info, LobbyRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTimeoutRefund<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct VoidGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundPlayer<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct IssueStrike<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Refund destination - required when the strike reaches
+    /// STRIKE_THRESHOLD and the player is kicked
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    /// CHECK: Seated player the strike is against, checked against the seat
+    pub player: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateParlay<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Parlay::INIT_SPACE,
+        seeds = [b"parlay", owner.key().as_ref()],
+        bump
+    )]
+    pub parlay: Account<'info, Parlay>,
+    
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"parlay_vault", owner.key().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = parlay_vault,
+    )]
+    pub parlay_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWithParlay<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay", owner.key().as_ref()],
+        bump = parlay.bump,
+        has_one = owner,
+    )]
+    pub parlay: Account<'info, Parlay>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay_vault", owner.key().as_ref()],
+        bump = parlay.vault_bump,
+    )]
+    pub parlay_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    /// CHECK: Owner's profile PDA, read for its withdrawal allowlist and
+    /// rating if it exists
+    #[account(seeds = [b"profile", owner.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Allowlist of a private game - required when the game has one
+    pub allowlist: Option<Account<'info, Allowlist>>,
+    
+    /// Joiner's NFT token account - required for collection-gated games
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Metaplex metadata of the joiner's NFT
+    pub nft_metadata: Option<Account<'info, MetadataAccount>>,
+    
+    /// Joiner's account of the gating token - required for balance-gated games
+    pub gate_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseParlay<'info> {
+    #[account(
+        mut,
+        seeds = [b"parlay", owner.key().as_ref()],
+        bump = parlay.bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub parlay: Account<'info, Parlay>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay_vault", owner.key().as_ref()],
+        bump = parlay.vault_bump,
+    )]
+    pub parlay_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(address = parlay_vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FlagStuck<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+#[instruction(duel_id: [u8; 8])]
+pub struct CreateDuel<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Duel::INIT_SPACE,
+        seeds = [b"duel", duel_id.as_ref()],
+        bump
+    )]
+    pub duel: Account<'info, Duel>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct JoinAndStartDuel<'info> {
+    #[account(mut)]
+    pub duel: Account<'info, Duel>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", duel.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", duel.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(mut)]
+    pub opponent_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub opponent: Signer<'info>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    #[account(address = duel.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EndDuel<'info> {
+    #[account(mut)]
+    pub duel: Account<'info, Duel>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", duel.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", duel.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(mut)]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(address = duel.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDuel<'info> {
+    #[account(
+        mut,
+        constraint = duel.players[0] == creator.key() @ EscrowError::Unauthorized,
+        close = creator,
+    )]
+    pub duel: Account<'info, Duel>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", duel.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", duel.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(mut)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(address = duel.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: [u8; 8])]
+pub struct CreateSeries<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Series::INIT_SPACE,
+        seeds = [b"series", series_id.as_ref()],
+        bump
+    )]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"series_vault", series_id.as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSeries<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        mut,
+        seeds = [b"series_vault", series.series_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ManageSeries<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSeriesGame<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Game::space(series.player_count),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordSeriesGame<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VoidSeries<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SettleSeries<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        mut,
+        seeds = [b"series_vault", series.series_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSeries<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundSeriesPlayer<'info> {
+    #[account(mut)]
+    pub series: Account<'info, Series>,
+    
+    #[account(
+        mut,
+        seeds = [b"series_vault", series.series_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: [u8; 8])]
+pub struct CreateTournament<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Tournament::INIT_SPACE,
+        seeds = [b"tournament", tournament_id.as_ref()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"tournament_vault", tournament_id.as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct JoinTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.tournament_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct StartTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTournamentMatch<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Game::space(2),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AdvanceTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.tournament_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub champion_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VoidTournament<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RefundTournamentEntrant<'info> {
+    #[account(mut)]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.tournament_id.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(season_id: [u8; 8])]
+pub struct CreateSeason<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Season::INIT_SPACE,
+        seeds = [b"season", season_id.as_ref()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+    
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"season_vault", season_id.as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSeason<'info> {
+    #[account(mut)]
+    pub season: Account<'info, Season>,
+    
+    #[account(
+        mut,
+        seeds = [b"season_vault", season.season_id.as_ref()],
+        bump = season.vault_bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(stake_amount: u64)]
+pub struct CreateQueue<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Queue::INIT_SPACE,
+        seeds = [b"queue", stake_amount.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queue: Account<'info, Queue>,
+    
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"queue_vault", stake_amount.to_le_bytes().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct JoinQueue<'info> {
+    #[account(mut)]
+    pub queue: Account<'info, Queue>,
+    
+    #[account(
+        mut,
+        seeds = [b"queue_vault", queue.stake_amount.to_le_bytes().as_ref()],
+        bump = queue.vault_bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveQueue<'info> {
+    #[account(mut)]
+    pub queue: Account<'info, Queue>,
+    
+    #[account(
+        mut,
+        seeds = [b"queue_vault", queue.stake_amount.to_le_bytes().as_ref()],
+        bump = queue.vault_bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ EscrowError::WrongPlayer,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FormGame<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(mut)]
+    pub queue: Account<'info, Queue>,
+    
+    #[account(
+        mut,
+        seeds = [b"queue_vault", queue.stake_amount.to_le_bytes().as_ref()],
+        bump = queue.vault_bump,
+    )]
+    pub queue_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Game::space(QUEUE_SIZE as u8),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u16)]
+pub struct CreateLobbyPage<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LobbyRegistry::INIT_SPACE,
+        seeds = [b"lobby_registry", page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGameCounter<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GameCounter::INIT_SPACE,
+        seeds = [b"game_counter"],
+        bump
+    )]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: [u8; 8])]
+pub struct CreateGameTemplate<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + GameTemplate::INIT_SPACE,
+        seeds = [b"template", template_id.as_ref()],
+        bump
+    )]
+    pub template: Account<'info, GameTemplate>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpawnFromTemplate<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(mut)]
+    pub template: Account<'info, GameTemplate>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Game::space(template.max_players),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    /// Registry page the new lobby is listed on
+    #[account(mut)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    
+    /// CHECK: Config admin, paid the listing fee
+    #[account(mut, address = config.admin @ EscrowError::Unauthorized)]
+    pub admin: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGameTemplate<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ EscrowError::Unauthorized,
+    )]
+    pub template: Account<'info, GameTemplate>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAllowlist<'info> {
+    #[account(
+        mut,
+        has_one = creator @ EscrowError::Unauthorized,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Allowlist::INIT_SPACE,
+        seeds = [b"allowlist", game.key().as_ref()],
+        bump
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAllowlist<'info> {
+    #[account(
+        has_one = creator @ EscrowError::Unauthorized,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"allowlist", game.key().as_ref()],
+        bump = allowlist.bump,
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+    
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundPrize<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimPrize<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AddToPot<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + PotContribution::INIT_SPACE,
+        seeds = [b"contribution", game.key().as_ref(), sponsor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, PotContribution>,
+    
+    #[account(
+        mut,
+        constraint = sponsor_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub sponsor_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimContribution<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        close = sponsor,
+        seeds = [b"contribution", game.key().as_ref(), sponsor.key().as_ref()],
+        bump = contribution.bump,
+    )]
+    pub contribution: Account<'info, PotContribution>,
+    
+    #[account(
+        mut,
+        constraint = sponsor_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub sponsor_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: receives the contribution's rent; bound by the PDA seeds
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRematch<'info> {
+    #[account(mut, seeds = [b"game_counter"], bump = game_counter.bump)]
+    pub game_counter: Account<'info, GameCounter>,
+    
+    #[account(constraint = previous_game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub previous_game: Box<Account<'info, Game>>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = Game::space(previous_game.max_players),
+        seeds = [b"game", game_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game: Box<Account<'info, Game>>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", usdc_mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = 8 + Allowlist::INIT_SPACE,
+        seeds = [b"allowlist", game.key().as_ref()],
+        bump
+    )]
+    pub allowlist: Box<Account<'info, Allowlist>>,
+    
+    #[account(address = previous_game.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    /// Registry page the new lobby is listed on
+    #[account(mut)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    
+    /// CHECK: Config admin, paid the listing fee
+    #[account(mut, address = config.admin @ EscrowError::Unauthorized)]
+    pub admin: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// CHECK: Player profile PDA, read for its withdrawal allowlist if it
+    /// exists
+    #[account(seeds = [b"profile", player.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RevealEntropy<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleRandomness<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// CHECK: VRF randomness account recorded at start, validated per
+    /// provider in the handler
+    pub randomness_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyResult<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitEngineMove<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// The player, or a session key acting for them
+    pub player: Signer<'info>,
+    
+    /// Session registering `player` as a key of the seated wallet
+    #[account(
+        seeds = [b"session", session.wallet.as_ref(), player.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEngineRound<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+pub struct SettleEngineGame<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// CHECK: Winner's wallet, or their registered payout wallet if they
+    /// joined as an agent, checked against the game's winner
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+    
+    /// CHECK: Winner's associated token account for the stake mint,
+    /// created if missing and address-checked by `create_payout_account`
+    #[account(mut)]
+    pub winner_token_account: UncheckedAccount<'info>,
+    
+    /// Pays for the winner's token account if it has to be created
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// Winner's pending-claim account - required when their token account
+    /// is frozen, so the winnings can be held for `claim_pending_payout`
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"pending_claim", mint.key().as_ref(), winner.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pending_claim,
+    )]
+    pub pending_claim: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Transit account a SOL payout is unwrapped through - required for
+    /// SOL-staked games and closed again before the instruction ends
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"unwrap", game.game_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = unwrap_account,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Creator's token account - required when the game pays a creator fee
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == game.creator @ EscrowError::WrongPlayer,
+        constraint = creator_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Pool holding referrer balances - required when referral pairs are passed
+    #[account(
+        mut,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub referral_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Jackpot pool - required when `config.jackpot_bps` is set
+    #[account(
+        mut,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    /// Season the game counts towards - required when `game.season` is set
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+    
+    #[account(mut)]
+    pub season_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMove<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + MoveLog::SIZE,
+        seeds = [b"move_log", game.key().as_ref()],
+        bump
+    )]
+    pub move_log: AccountLoader<'info, MoveLog>,
+    
+    /// The player, or a session key acting for them
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// Session registering `player` as a key of the seated wallet
+    #[account(
+        seeds = [b"session", session.wallet.as_ref(), player.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMovesBatch<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MoveLog::SIZE,
+        seeds = [b"move_log", game.key().as_ref()],
+        bump
+    )]
+    pub move_log: AccountLoader<'info, MoveLog>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositBond<'info> {
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        seeds = [b"bond"],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = bond_vault,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeResult<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(mut, seeds = [b"bond"], bump)]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        constraint = challenger_token_account.owner == challenger.key() @ EscrowError::WrongPlayer,
+    )]
+    pub challenger_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub challenger: Signer<'info>,
+    
+    #[account(address = bond_vault.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetSettlementVerifier<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + SettlementVerifier::INIT_SPACE,
+        seeds = [b"settlement_verifier"],
+        bump
+    )]
+    pub verifier: Box<Account<'info, SettlementVerifier>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndGameWithProof<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// Moves the proof replays
+    #[account(seeds = [b"move_log", game.key().as_ref()], bump)]
+    pub move_log: AccountLoader<'info, MoveLog>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// CHECK: Winner's wallet, or their registered payout wallet if they
+    /// joined as an agent, checked against the game's winner
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+    
+    /// CHECK: Winner's associated token account for the stake mint,
+    /// created if missing and address-checked by `create_payout_account`
+    #[account(mut)]
+    pub winner_token_account: UncheckedAccount<'info>,
+    
+    /// Pays for the winner's token account if it has to be created
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// Winner's pending-claim account - required when their token account
+    /// is frozen, so the winnings can be held for `claim_pending_payout`
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"pending_claim", mint.key().as_ref(), winner.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pending_claim,
+    )]
+    pub pending_claim: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Transit account a SOL payout is unwrapped through - required for
+    /// SOL-staked games and closed again before the instruction ends
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"unwrap", game.game_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = unwrap_account,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Program-owned treasury the house fee accrues into
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Creator's token account - required when the game pays a creator fee
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == game.creator @ EscrowError::WrongPlayer,
+        constraint = creator_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Pool holding referrer balances - required when referral pairs are passed
+    #[account(
+        mut,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub referral_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Jackpot pool - required when `config.jackpot_bps` is set
+    #[account(
+        mut,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(seeds = [b"settlement_verifier"], bump = verifier.bump)]
+    pub verifier: Box<Account<'info, SettlementVerifier>>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    /// Season the game counts towards - required when `game.season` is set
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+    
+    #[account(mut)]
+    pub season_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTreasury<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferral<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = 8 + Referral::INIT_SPACE,
+        seeds = [b"referral", player.key().as_ref()],
+        bump,
+    )]
+    pub referral: Account<'info, Referral>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReferrerBalance<'info> {
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferrerBalance::INIT_SPACE,
+        seeds = [b"referrer_balance", referrer.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub balance: Account<'info, ReferrerBalance>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateReferralPool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = referral_pool,
+    )]
+    pub referral_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralEarnings<'info> {
+    #[account(
+        mut,
+        seeds = [b"referrer_balance", referrer.key().as_ref(), mint.key().as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, ReferrerBalance>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub referral_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub referrer: Signer<'info>,
+    
+    /// CHECK: Referrer's profile PDA, read for its withdrawal allowlist if
+    /// it exists
+    #[account(seeds = [b"profile", referrer.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueRakeback<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRakeback<'info> {
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = config.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    pub player: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateJackpot<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = jackpot,
+    )]
+    pub jackpot: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimJackpot<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"jackpot", mint.key().as_ref()],
+        bump,
+    )]
+    pub jackpot: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EliminatePlayer<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBounty<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateInsurancePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = insurance_pool,
+    )]
+    pub insurance_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+    )]
+    pub insurance_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeDeal<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptDeal<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSidePot<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ EscrowError::WrongPlayer,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSidePot<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Rebuy<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ EscrowError::WrongPlayer,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BuyPowerup<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ EscrowError::WrongPlayer,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(player_index: u8)]
+pub struct TipPlayer<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        init_if_needed,
+        payer = tipper,
+        space = 8 + Tip::INIT_SPACE,
+        seeds = [b"tip", game.key().as_ref(), tipper.key().as_ref(), &[player_index]],
+        bump
+    )]
+    pub tip: Account<'info, Tip>,
+    
+    #[account(
+        mut,
+        constraint = tipper_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub tipper_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTips<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTip<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        close = tipper,
+        has_one = game,
+        has_one = tipper,
+    )]
+    pub tip: Account<'info, Tip>,
+    
+    #[account(
+        mut,
+        constraint = tipper_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub tipper_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: receives the tip's rent; bound by `has_one`
+    #[account(mut)]
+    pub tipper: UncheckedAccount<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateBettingPool<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BettingPool::INIT_SPACE,
+        seeds = [b"betting_pool", game.key().as_ref()],
+        bump
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+    
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"betting_vault", game.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = betting_vault,
+    )]
+    pub betting_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(seat: u8)]
+pub struct PlaceBet<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"betting_pool", game.key().as_ref()],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"betting_vault", game.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub betting_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + Bet::INIT_SPACE,
+        seeds = [b"bet", betting_pool.key().as_ref(), bettor.key().as_ref(), &[seat]],
+        bump
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    #[account(
+        mut,
+        constraint = bettor_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub bettor_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleBettingPool<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"betting_pool", game.key().as_ref()],
+        bump = betting_pool.bump,
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"betting_vault", game.key().as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub betting_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBet<'info> {
+    pub betting_pool: Account<'info, BettingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"betting_vault", betting_pool.game.as_ref()],
+        bump = betting_pool.vault_bump,
+    )]
+    pub betting_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        close = bettor,
+        has_one = betting_pool,
+        has_one = bettor,
+    )]
+    pub bet: Account<'info, Bet>,
+    
+    #[account(
+        mut,
+        constraint = bettor_token_account.mint == betting_pool.mint @ EscrowError::InvalidMint,
+    )]
+    pub bettor_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: receives the bet's rent; bound by `has_one`
+    #[account(mut)]
+    pub bettor: UncheckedAccount<'info>,
+    
+    #[account(address = betting_pool.mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Token account of the seat's payout wallet, which is the player
+    /// unless they joined through an agent or with withdrawal addresses
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player: Signer<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(nft_game_id: [u8; 8])]
+pub struct CreateNftGame<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + NftGame::INIT_SPACE,
+        seeds = [b"nft_game", nft_game_id.as_ref()],
+        bump
+    )]
+    pub nft_game: Account<'info, NftGame>,
+    
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"nft_escrow", nft_game.key().as_ref(), nft_mint.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = nft_escrow,
+    )]
+    pub nft_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    
+    pub nft_metadata: Account<'info, MetadataAccount>,
+    
+    #[account(
+        mut,
+        constraint = creator_nft_account.mint == nft_mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinNftGame<'info> {
+    #[account(mut)]
+    pub nft_game: Account<'info, NftGame>,
+    
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"nft_escrow", nft_game.key().as_ref(), nft_mint.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = nft_escrow,
+    )]
+    pub nft_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    
+    pub nft_metadata: Account<'info, MetadataAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_nft_account.mint == nft_mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub player_nft_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// CHECK: SlotHashes sysvar, read for seed entropy
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndNftGame<'info> {
+    #[account(mut)]
+    pub nft_game: Account<'info, NftGame>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelNftGame<'info> {
+    #[account(mut, has_one = creator @ EscrowError::Unauthorized)]
+    pub nft_game: Account<'info, NftGame>,
+    
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimNftGameTimeout<'info> {
+    #[account(mut)]
+    pub nft_game: Account<'info, NftGame>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseNft<'info> {
+    #[account(mut)]
+    pub nft_game: Account<'info, NftGame>,
+    
+    #[account(
+        mut,
+        seeds = [b"nft_escrow", nft_game.key().as_ref(), nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub nft_escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == nft_mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: player who staked the NFT, receives the escrow's rent;
+    /// checked against the game's seat
+    #[account(mut)]
+    pub depositor: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateBalanceVault<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"balance_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = balance_vault,
+    )]
+    pub balance_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(constraint = config.accepts_mint(&mint.key()) @ EscrowError::MintNotAllowed)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerBalance::INIT_SPACE,
+        seeds = [b"balance", player.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+    
+    #[account(
+        mut,
+        seeds = [b"balance_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub balance_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"balance", player.key().as_ref(), mint.key().as_ref()],
+        bump = player_balance.bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+    
+    #[account(
+        mut,
+        seeds = [b"balance_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub balance_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key() @ EscrowError::InvalidMint,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub player: Signer<'info>,
+    
+    /// CHECK: Player profile PDA, read for its withdrawal allowlist if it
+    /// exists
+    #[account(seeds = [b"profile", player.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct JoinFor<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", game.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", game.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Stake source - the seat goes to its owner
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game.stake_mint @ EscrowError::InvalidMint,
+        constraint = player_token_account.delegate == COption::Some(join_delegate.key())
+            @ EscrowError::StakeNotDelegated,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Profile PDA of the stake source's owner, read for its
+    /// withdrawal allowlist and rating if it exists
+    #[account(seeds = [b"profile", player_token_account.owner.as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA players approve as delegate of their stake; signs the
+    /// transfer and holds no data
+    #[account(seeds = [b"join_delegate"], bump)]
+    pub join_delegate: UncheckedAccount<'info>,
+    
+    #[account(address = game.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Allowlist of a private game - required when the game has one
+    pub allowlist: Option<Account<'info, Allowlist>>,
+    
+    /// Joiner's NFT token account - required for collection-gated games
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Metaplex metadata of the joiner's NFT
+    pub nft_metadata: Option<Account<'info, MetadataAccount>>,
+    
+    /// Joiner's account of the gating token - required for balance-gated games
+    pub gate_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + SessionKey::INIT_SPACE,
+        seeds = [b"session", wallet.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        seeds = [b"session", wallet.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        close = wallet,
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Forfeit<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// The player, or a session key acting for them
+    pub player: Signer<'info>,
+    
+    /// Session registering `player` as a key of the seated wallet
+    #[account(
+        seeds = [b"session", session.wallet.as_ref(), player.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+}
+
+#[derive(Accounts)]
+pub struct RecordGameStats<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    /// The player's stats for the current season
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + SeasonStats::INIT_SPACE,
+        seeds = [
+            b"season_stats",
+            player.key().as_ref(),
+            config.stats_season.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub season_stats: Account<'info, SeasonStats>,
+    
+    /// CHECK: Seated player the profile belongs to, checked against the seat
+    pub player: UncheckedAccount<'info>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    /// Global leaderboard, re-ranked with the player's new net winnings in
+    /// the default stake mint
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Option<Box<Account<'info, Leaderboard>>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLeaderboard<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Leaderboard::INIT_SPACE,
+        seeds = [b"leaderboard"],
+        bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRatings<'info> {
+    #[account(
+        mut,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+pub struct StartStatsSeason<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(player_index: u8, kind: AchievementKind)]
+pub struct UnlockAchievement<'info> {
+    #[account(constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated)]
+    pub game: Account<'info, Game>,
+    
+    #[account(seeds = [b"profile", profile.player.as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Achievement::INIT_SPACE,
+        seeds = [b"achievement", profile.player.as_ref(), &[kind as u8]],
+        bump
+    )]
+    pub achievement: Account<'info, Achievement>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct RegisterAgent<'info> {
+    #[account(
+        init,
+        payer = payout_wallet,
+        space = 8 + Agent::INIT_SPACE,
+        seeds = [b"agent", operator.as_ref()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(mut)]
+    pub payout_wallet: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.operator.as_ref()],
+        bump = agent.bump,
+        has_one = payout_wallet @ EscrowError::Unauthorized,
+        close = payout_wallet,
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(mut)]
+    pub payout_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalAddresses<'info> {
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    /// One of the registered withdrawal addresses - required when the new
+    /// list adds an address or lifts the restriction
+    pub approver: Option<Signer<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u16)]
+pub struct CreateGameIndexPage<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PlayerGameIndex::INIT_SPACE,
+        seeds = [b"game_index", player.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub game_index: Box<Account<'info, PlayerGameIndex>>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveGame<'info> {
+    #[account(
+        mut,
+        close = creator,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + GameArchive::INIT_SPACE,
+        seeds = [b"archive", game.game_id.as_ref()],
+        bump
+    )]
+    pub archive: Account<'info, GameArchive>,
+    
+    /// CHECK: Game creator, refunded the game rent
+    #[account(mut, address = game.creator @ EscrowError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+    
+    /// CHECK: Betting pool PDA of the game, checked to be settled in the
+    /// handler if it exists
+    #[account(seeds = [b"betting_pool", game.key().as_ref()], bump)]
+    pub betting_pool: UncheckedAccount<'info>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        constraint = payer.key() == game.creator || payer.key() == config.authority
+            @ EscrowError::Unauthorized,
+    )]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(
+        mut,
+        close = creator,
+        constraint = game.version == GAME_VERSION @ EscrowError::GameNotMigrated,
+    )]
+    pub game: Account<'info, Game>,
+    
+    /// CHECK: Game creator, refunded the game rent
+    #[account(mut, address = game.creator @ EscrowError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+    
+    /// CHECK: Betting pool PDA of the game, checked to be settled in the
+    /// handler if it exists
+    #[account(seeds = [b"betting_pool", game.key().as_ref()], bump)]
+    pub betting_pool: UncheckedAccount<'info>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        constraint = closer.key() == game.creator || closer.key() == config.authority
+            @ EscrowError::Unauthorized,
+    )]
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    /// CHECK: Game in any layout version, checked by owner here and by
+    /// discriminator in the handler
+    #[account(mut, owner = crate::ID @ EscrowError::InvalidGameAccount)]
+    pub game: UncheckedAccount<'info>,
+    
+    /// Vault the game held its funds in before version 3, checked
+    /// against the game in the handler
+    #[account(mut)]
+    pub legacy_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", mint.key().as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    /// Stake mint of the game, checked in the handler
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: Game creator, refunded rent freed by the migration; checked
+    /// against the game in the handler
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VaultLedger::INIT_SPACE,
+        seeds = [b"vault_ledger", mint.key().as_ref()],
+        bump
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDuelTimeoutRefund<'info> {
+    #[account(mut)]
+    pub duel: Account<'info, Duel>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", duel.stake_mint.as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault_ledger", duel.stake_mint.as_ref()],
+        bump = vault_ledger.bump,
+    )]
+    pub vault_ledger: Account<'info, VaultLedger>,
+    
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == duel.players[0] @ EscrowError::WrongPlayer,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = opponent_token_account.owner == duel.players[1] @ EscrowError::WrongPlayer,
+    )]
+    pub opponent_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(address = duel.stake_mint @ EscrowError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MergeStatsBucket<'info> {
+    #[account(
+        mut,
+        seeds = [b"profile", profile.player.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ EscrowError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+// === Instruction Params ===
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    /// Backend authority allowed to start, settle, and moderate games
+    pub authority: Pubkey,
+    /// Default stake mint
+    pub stake_mint: Pubkey,
+    /// Further mints games may be staked in
+    pub stake_mints: Vec<Pubkey>,
+    /// Collections whose NFTs may be staked in NFT games
+    pub nft_collections: Vec<Pubkey>,
+    /// Maximum house fee a game may be created with
+    pub max_fee_bps: u16,
+    /// Maximum share of the house fee a game may pay its creator
+    pub max_creator_fee_bps: u16,
+    /// Share of a referred player's part of the house fee credited to
+    /// their referrer
+    pub referral_bps: u16,
+    /// Share of the house fee a player paid in the default stake mint that
+    /// accrues back to them as rakeback
+    pub rakeback_bps: u16,
+    /// Share of the house fee diverted into the mint's jackpot pool
+    pub jackpot_bps: u16,
+    /// Optional loss insurance joiners can buy, if offered
+    pub insurance: Option<InsuranceTerms>,
+    /// Share of every spectator betting pool paid to the treasury
+    pub betting_vig_bps: u16,
+    /// Large payouts in the default stake mint vest instead of paying out
+    pub vesting: Option<VestingTerms>,
+    /// Seconds a live game may run before players can claim refunds
+    pub max_duration: i64,
+    /// Where board seeds come from
+    pub randomness_source: RandomnessSource,
+    /// Seconds a proposed result can be disputed before it is finalized
+    pub dispute_window: i64,
+    /// Paid from the backend bond to a player who disproves a result
+    pub challenge_reward: u64,
+    /// Attested enclave that must sign every proposed result, if any
+    pub tee: Option<TeeConfig>,
+    /// Referees that must co-sign every proposed result, if any
+    pub referees: Option<RefereeQuorum>,
+    /// Shares of every house fee paid out at settlement; the treasury
+    /// keeps the remainder
+    pub fee_split: Vec<FeeShare>,
+    /// Part of the house fee burned for games staked in the native token
+    pub rake_burn: Option<RakeBurn>,
+    /// House fee by stake size for games in the default stake mint,
+    /// ascending by `min_stake`
+    pub fee_tiers: Vec<FeeTier>,
+    /// Promotional window in which settlement charges no house fee
+    pub rake_free_window: Option<RakeFreeWindow>,
+    /// Seconds a player who left or was kicked from a waiting game keeps
+    /// a seat held for `rejoin_game` (0 for no hold)
+    pub rejoin_grace: i64,
+    /// Lamports charged for listing a lobby on the registry, paid to the
+    /// admin so pages cannot be filled with throwaway lobbies for free
+    pub listing_fee: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateGameParams {
+    /// Optional caller-supplied label for the game
+    pub label: Option<[u8; 8]>,
+    /// Stake amount per player (in USDC lamports)
+    pub stake_amount: u64,
+    /// Players required before the game can start
+    pub min_players: u8,
+    /// Seats available in the game
+    pub max_players: u8,
+    /// House fee in basis points (at most `config.max_fee_bps`)
+    pub fee_bps: u16,
+    /// Payout split per placement in bps of the prize pool after fees
+    pub payout_schedule: Vec<u16>,
+    /// Unix timestamp after which joins close and anyone may expire the lobby
+    pub join_deadline: i64,
+    /// Optional start time; joins close `SCHEDULED_JOIN_BUFFER_SECS` before
+    /// it and `start_game` is rejected until it passes
+    pub scheduled_start: Option<i64>,
+    /// SHA-256 of an invite code; when set, joins must present the code
+    pub invite_code_hash: Option<[u8; 32]>,
+    /// Verified Metaplex collection joiners must hold an NFT from
+    pub nft_collection: Option<Pubkey>,
+    /// Minimum balance of an SPL token joiners must hold
+    pub balance_gate: Option<BalanceGate>,
+    /// Ratings joiners must fall in
+    pub rating_band: Option<RatingBand>,
+    /// Require every player to commit at join and reveal before start,
+    /// mixing their entropy into the board seed
+    pub commit_reveal: bool,
+    /// Play the game through the on-chain engine instead of the backend
+    pub on_chain_engine: bool,
+    /// `stake_amount` is in USD cents, paid in SOL at the Pyth price on join
+    pub usd_stake: bool,
+    /// Share of the house fee paid to the creator at settlement, in basis
+    /// points (at most `config.max_creator_fee_bps`)
+    pub creator_fee_bps: u16,
+    /// Share of the stakes paid to a player for each elimination they
+    /// cause, in basis points; 0 plays winner-take-all
+    pub bounty_bps: u16,
+    /// Split the prize pool by how long each player survived, settled
+    /// with `end_game_survival`
+    pub survival_payout: bool,
+    /// Optional side stake in base units of the stake mint, paid by the
+    /// players who opt in with `join_side_pot`; 0 disables the side pot
+    pub side_stake: u64,
+    /// Seconds after an elimination the player may `rebuy`; 0 disables
+    /// rebuys
+    pub rebuy_window: i64,
+    /// Rebuys each seat may make in the game
+    pub max_rebuys: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateSeriesParams {
+    /// Buy-in per player covering every game in the series
+    pub buy_in: u64,
+    /// House fee in basis points (at most `config.max_fee_bps`)
+    pub fee_bps: u16,
+    /// Seats in the series; all must be filled before it starts
+    pub max_players: u8,
+    /// Game wins needed to take the series
+    pub wins_required: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateSeasonParams {
+    /// Share of each season game's house fee paid into the prize pool (bps)
+    pub rake_share_bps: u16,
+    /// Points awarded to the winner of a season game
+    pub win_points: u32,
+    /// Points awarded to every player of a season game
+    pub play_points: u32,
+    /// Season games can be created from this timestamp
+    pub starts_at: i64,
+    /// Season games can no longer be created after, and the season can be
+    /// finalized from, this timestamp
+    pub ends_at: i64,
+    /// Prize split per final standing in bps of the prize pool
+    pub payout_schedule: Vec<u16>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GameTemplateParams {
+    /// Stake amount per player (in USDC lamports)
+    pub stake_amount: u64,
+    /// Players required before a spawned game can start
+    pub min_players: u8,
+    /// Seats available in a spawned game
+    pub max_players: u8,
+    /// House fee in basis points (at most `config.max_fee_bps`)
+    pub fee_bps: u16,
+    /// Payout split per placement in bps of the prize pool after fees
+    pub payout_schedule: Vec<u16>,
+    /// Scheduled start of the first spawned game
+    pub first_start: i64,
+    /// Seconds between scheduled starts
+    pub cadence_secs: i64,
+    /// How long before its start a lobby may be spawned
+    pub lead_secs: i64,
+    /// Seconds after its start a lobby that never started can be expired
+    pub expiry_secs: i64,
+}
+
+/// Kind of move a player submits in a round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum MoveAction {
+    #[default]
+    Click,
+    Flag,
+}
+
+/// One entry of a game's move log
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Move {
+    /// Round the move was played in, starting at 1
+    pub round: u16,
+    /// Seat index of the player
+    pub player: u8,
+    pub action: MoveAction,
+    pub x: u8,
+    pub y: u8,
+}
+
+// === State ===
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// Admin allowed to update the config
+    pub admin: Pubkey,
+    /// Backend authority allowed to start, settle, and moderate games
+    pub authority: Pubkey,
+    /// Default stake mint
+    pub stake_mint: Pubkey,
+    /// Further mints games may be staked in
+    #[max_len(MAX_STAKE_MINTS)]
+    pub stake_mints: Vec<Pubkey>,
+    /// Collections whose NFTs may be staked in NFT games
+    #[max_len(MAX_NFT_COLLECTIONS)]
+    pub nft_collections: Vec<Pubkey>,
+    /// Maximum house fee a game may be created with
+    pub max_fee_bps: u16,
+    /// Maximum share of the house fee a game may pay its creator
+    pub max_creator_fee_bps: u16,
+    /// Share of a referred player's part of the house fee credited to
+    /// their referrer
+    pub referral_bps: u16,
+    /// Share of the house fee a player paid in the default stake mint that
+    /// accrues back to them as rakeback
+    pub rakeback_bps: u16,
+    /// Share of the house fee diverted into the mint's jackpot pool
+    pub jackpot_bps: u16,
+    /// Optional loss insurance joiners can buy, if offered
+    pub insurance: Option<InsuranceTerms>,
+    /// Share of every spectator betting pool paid to the treasury
+    pub betting_vig_bps: u16,
+    /// Large payouts in the default stake mint vest instead of paying out
+    pub vesting: Option<VestingTerms>,
+    /// Seconds a live game may run before players can claim refunds
+    pub max_duration: i64,
+    /// Where board seeds come from
+    pub randomness_source: RandomnessSource,
+    /// Seconds a proposed result can be disputed before it is finalized
+    pub dispute_window: i64,
+    /// Paid from the backend bond to a player who disproves a result
+    pub challenge_reward: u64,
+    /// Attested enclave that must sign every proposed result, if any
+    pub tee: Option<TeeConfig>,
+    /// Referees that must co-sign every proposed result, if any
+    pub referees: Option<RefereeQuorum>,
+    /// Shares of every house fee paid out at settlement; the treasury
+    /// keeps the remainder
+    #[max_len(MAX_FEE_SHARES)]
+    pub fee_split: Vec<FeeShare>,
+    /// Part of the house fee burned for games staked in the native token
+    pub rake_burn: Option<RakeBurn>,
+    /// House fee by stake size for games in the default stake mint,
+    /// ascending by `min_stake`
+    #[max_len(MAX_FEE_TIERS)]
+    pub fee_tiers: Vec<FeeTier>,
+    /// Promotional window in which settlement charges no house fee
+    pub rake_free_window: Option<RakeFreeWindow>,
+    /// Seconds a player who left or was kicked from a waiting game keeps
+    /// a seat held for `rejoin_game` (0 for no hold)
+    pub rejoin_grace: i64,
+    /// Lamports charged for listing a lobby on the registry, paid to the
+    /// admin so pages cannot be filled with throwaway lobbies for free
+    pub listing_fee: u64,
+    /// Current stats season, bumped by `start_stats_season`
+    pub stats_season: u16,
+    /// When the current stats season started
+    pub stats_season_started_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Config {
+    /// Whether games may be staked in `mint`
+    pub fn accepts_mint(&self, mint: &Pubkey) -> bool {
+        *mint == self.stake_mint || self.stake_mints.contains(mint)
+    }
+    
+    /// Validate and store admin-settable parameters
+    pub fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        require!(
+            params.max_fee_bps as u64 <= BPS_DENOMINATOR
+                && params.max_creator_fee_bps as u64
+                    + params.referral_bps as u64
+                    + params.jackpot_bps as u64
+                    <= BPS_DENOMINATOR
+                && params.rakeback_bps as u64 <= BPS_DENOMINATOR
+                && params.betting_vig_bps as u64 <= BPS_DENOMINATOR,
+            EscrowError::InvalidFeeBps
+        );
+        require!(params.max_duration > 0, EscrowError::InvalidDuration);
+        require!(params.dispute_window >= 0, EscrowError::InvalidDuration);
+        require!(params.rejoin_grace >= 0, EscrowError::InvalidDuration);
+        require!(
+            params.stake_mints.len() <= MAX_STAKE_MINTS,
+            EscrowError::InvalidStakeMints
+        );
+        for (i, stake_mint) in params.stake_mints.iter().enumerate() {
+            require!(
+                *stake_mint != params.stake_mint && !params.stake_mints[..i].contains(stake_mint),
+                EscrowError::InvalidStakeMints
+            );
+        }
+        require!(
+            params.nft_collections.len() <= MAX_NFT_COLLECTIONS,
+            EscrowError::InvalidNftCollections
+        );
+        require!(
+            params.fee_split.len() <= MAX_FEE_SHARES
+                && params.fee_split.iter().map(|share| share.bps as u64).sum::<u64>()
+                    <= BPS_DENOMINATOR,
+            EscrowError::InvalidFeeSplit
+        );
+        if let Some(rake_burn) = &params.rake_burn {
+            require!(
+                rake_burn.bps as u64 <= BPS_DENOMINATOR,
+                EscrowError::InvalidFeeBps
+            );
+        }
+        require!(
+            params.fee_tiers.len() <= MAX_FEE_TIERS,
+            EscrowError::InvalidFeeTiers
+        );
+        for (i, tier) in params.fee_tiers.iter().enumerate() {
+            require!(
+                tier.fee_bps <= params.max_fee_bps
+                    && (i == 0 || params.fee_tiers[i - 1].min_stake < tier.min_stake),
+                EscrowError::InvalidFeeTiers
+            );
+        }
+        if let Some(insurance) = &params.insurance {
+            require!(
+                insurance.premium_bps as u64 <= BPS_DENOMINATOR
+                    && insurance.refund_bps as u64 <= BPS_DENOMINATOR,
+                EscrowError::InvalidFeeBps
+            );
+        }
+        if let Some(vesting) = &params.vesting {
+            require!(vesting.period > 0, EscrowError::InvalidDuration);
+        }
+        if let Some(window) = &params.rake_free_window {
+            require!(window.start < window.end, EscrowError::InvalidRakeFreeWindow);
+        }
+        if let Some(referees) = &params.referees {
+            require!(
+                referees.count as usize <= MAX_REFEREES
+                    && referees.threshold > 0
+                    && referees.threshold <= referees.count,
+                EscrowError::InvalidRefereeQuorum
+            );
+            // A repeated key would count one signature twice
+            let keys = &referees.keys[..referees.count as usize];
+            for (i, key) in keys.iter().enumerate() {
+                require!(!keys[..i].contains(key), EscrowError::InvalidRefereeQuorum);
+            }
+        }
+        
+        self.authority = params.authority;
+        self.stake_mint = params.stake_mint;
+        self.stake_mints = params.stake_mints.clone();
+        self.nft_collections = params.nft_collections.clone();
+        self.max_fee_bps = params.max_fee_bps;
+        self.max_creator_fee_bps = params.max_creator_fee_bps;
+        self.referral_bps = params.referral_bps;
+        self.rakeback_bps = params.rakeback_bps;
+        self.jackpot_bps = params.jackpot_bps;
+        self.insurance = params.insurance;
+        self.betting_vig_bps = params.betting_vig_bps;
+        self.vesting = params.vesting;
+        self.max_duration = params.max_duration;
+        self.randomness_source = params.randomness_source;
+        self.dispute_window = params.dispute_window;
+        self.challenge_reward = params.challenge_reward;
+        self.tee = params.tee;
+        self.referees = params.referees;
+        self.fee_split = params.fee_split.clone();
+        self.rake_burn = params.rake_burn;
+        self.fee_tiers = params.fee_tiers.clone();
+        self.rake_free_window = params.rake_free_window;
+        self.rejoin_grace = params.rejoin_grace;
+        self.listing_fee = params.listing_fee;
+        Ok(())
+    }
+    
+    /// Whether settlement at `now` falls in the rake-free window
+    pub fn is_rake_free(&self, now: i64) -> bool {
+        self.rake_free_window
+            .is_some_and(|window| window.start <= now && now < window.end)
+    }
+    
+    /// House fee charged when `game` settles: its own `fee_bps`, lowered
+    /// to the fee tier its stake falls in when staked in the default mint
+    pub fn settlement_fee_bps(&self, game: &Game) -> u16 {
+        if game.stake_mint != self.stake_mint || game.usd_stake {
+            return game.fee_bps;
+        }
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_stake <= game.stake_amount)
+            .map_or(game.fee_bps, |tier| tier.fee_bps.min(game.fee_bps))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Game {
+    /// Account layout version, always the first field so `migrate_game`
+    /// can read it from any layout
+    pub version: u8,
+    /// Unique game identifier, assigned by the game counter
+    pub game_id: [u8; 8],
+    /// Optional caller-supplied label, metadata only
+    pub label: Option<[u8; 8]>,
+    /// Game creator
+    pub creator: Pubkey,
+    /// Stake amount per player (in base units of `stake_mint`, or USD
+    /// cents when `usd_stake` is set)
+    pub stake_amount: u64,
+    /// Mint of the vault - the config stake mint, or wrapped SOL
+    pub stake_mint: Pubkey,
+    /// `stake_amount` is in USD cents, converted to lamports at each join
+    pub usd_stake: bool,
+    /// Base units each seat paid in (parallel to `players`)
+    #[max_len(MAX_PLAYERS)]
+    pub paid: Vec<u64>,
+    /// Number of players joined
+    pub player_count: u8,
+    /// Players required before the game can start
+    pub min_players: u8,
+    /// Seats available in this game
+    pub max_players: u8,
+    /// House fee in basis points, snapshotted from creation
+    pub fee_bps: u16,
+    /// Share of the house fee paid to the creator, in basis points
+    pub creator_fee_bps: u16,
+    /// Share of the stakes paid per elimination, in basis points
+    /// (0 disables bounties)
+    pub bounty_bps: u16,
+    /// Split the prize pool by how long each player survived
+    pub survival_payout: bool,
+    /// Side stake per side-pot participant, in base units (0 if disabled)
+    pub side_stake: u64,
+    /// Seconds after elimination a rebuy stays open (0 disables rebuys)
+    pub rebuy_window: i64,
+    /// Rebuys allowed per seat
+    pub max_rebuys: u8,
+    /// Ranked payout split (bps of the prize pool per placement)
+    #[max_len(MAX_PLAYERS)]
+    pub payout_schedule: Vec<u16>,
+    /// Joins close after this timestamp; the lobby can then be expired
+    pub join_deadline: i64,
+    /// Earliest time the game may start, if scheduled
+    pub scheduled_start: Option<i64>,
+    /// Player pubkeys (first `player_count` entries are seated)
+    #[max_len(MAX_PLAYERS)]
+    pub players: Vec<Pubkey>,
+    /// Game status
+    pub status: GameStatus,
+    /// Seed for board generation (revealed after game starts)
+    pub seed: [u8; 32],
+    /// Winner pubkey (set after game ends)
+    pub winner: Option<Pubkey>,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Start timestamp
+    pub started_at: Option<i64>,
+    /// Settlement timestamp
+    pub finished_at: Option<i64>,
+    /// Cancellation (or void) timestamp
+    pub cancelled_at: Option<i64>,
+    /// Timestamp of the most recent status change
+    pub last_transition_at: i64,
+    /// Bitmask of seats refunded after cancellation
+    pub refunded: u8,
+    /// Conditions raised by `flag_stuck` (STUCK_* bits)
+    pub stuck_flags: u8,
+    /// Bitmask of seats that shared a drawn result (0 if not a draw)
+    pub draw_mask: u8,
+    /// Team id per seat (all zero when the game is not played in teams)
+    #[max_len(MAX_PLAYERS)]
+    pub teams: Vec<u8>,
+    /// Team that won a team game (0 otherwise)
+    pub winning_team: u8,
+    /// Moderation strikes per seat (parallel to `players`)
+    #[max_len(MAX_PLAYERS)]
+    pub strikes: Vec<u8>,
+    /// Next expected nonce for repeatable authority-signed actions
+    pub action_nonce: u64,
+    /// Tournament this game is a bracket match for
+    pub tournament: Option<Pubkey>,
+    /// Bracket round of the tournament match
+    pub tournament_round: u8,
+    /// Season this game accrues points and rake towards
+    pub season: Option<Pubkey>,
+    /// Open-lobby registry page listing this game while it is waiting
+    pub lobby_page: Option<u16>,
+    /// Only wallets on the game's allowlist may join
+    pub allowlisted: bool,
+    /// SHA-256 of the invite code joiners must present, if invite-only
+    pub invite_code_hash: Option<[u8; 32]>,
+    /// Metaplex collection joiners must hold an NFT from, if gated
+    pub nft_collection: Option<Pubkey>,
+    /// Minimum token balance joiners must hold, if gated
+    pub balance_gate: Option<BalanceGate>,
+    /// Prize added by the house on top of player stakes (fee-free)
+    pub funded_prize: u64,
+    /// Prize added by sponsors through `add_to_pot` (fee-free)
+    pub sponsored_pot: u64,
+    /// Game this game is a rematch of
+    pub rematch_of: Option<[u8; 8]>,
+    /// Players contribute seed entropy by commit-reveal
+    pub commit_reveal: bool,
+    /// Per seat: entropy commitment, replaced by the secret once revealed
+    #[max_len(MAX_PLAYERS)]
+    pub commitments: Vec<[u8; 32]>,
+    /// Bitmask of seats that have revealed
+    pub revealed: u8,
+    /// Randomness provider the seed was drawn from at start
+    pub randomness_source: RandomnessSource,
+    /// VRF randomness account the seed is drawn from
+    pub randomness_account: Option<Pubkey>,
+    /// Slot the randomness request was committed in
+    pub randomness_slot: u64,
+    /// Started but the seed has not been revealed yet
+    pub seed_pending: bool,
+    /// Hash of the backend seed, revealed at settlement
+    pub seed_commitment: Option<[u8; 32]>,
+    /// Round state when the game is played by the on-chain engine
+    pub engine: Option<EngineState>,
+    /// Merkle root of the complete move log, committed at settlement
+    pub move_root: Option<[u8; 32]>,
+    /// Winner proposed by the backend, paid out after the dispute window
+    pub proposed_winner: Option<Pubkey>,
+    /// A proposed result can be finalized from this timestamp
+    pub dispute_deadline: Option<i64>,
+    /// Winnings recorded at settlement that the winner has not claimed
+    pub unclaimed: u64,
+    /// House fee charged at settlement
+    pub house_fee: u64,
+    /// Bitmask of seats that accrued rakeback on this game
+    pub rakeback_accrued: u8,
+    /// The winner was paid the jackpot for this game
+    pub jackpot_claimed: bool,
+    /// Bitmask of seats reported eliminated
+    pub eliminated: u8,
+    /// When each eliminated seat went out, valid where `eliminated` is set
+    #[max_len(MAX_PLAYERS)]
+    pub eliminated_at: Vec<i64>,
+    /// Seats in the order they were eliminated, one per `eliminated` bit
+    #[max_len(MAX_PLAYERS)]
+    pub elimination_order: Vec<u8>,
+    /// Bounties each seat earned, claimable once the game is finished
+    #[max_len(MAX_PLAYERS)]
+    pub bounties: Vec<u64>,
+    /// Bitmask of seats that paid the insurance premium
+    pub insured: u8,
+    /// Bitmask of insured seats paid their loss refund
+    pub insurance_claimed: u8,
+    /// Split of the pot proposed by the remaining players, if any
+    pub deal: Option<Deal>,
+    /// Bitmask of seats that paid the side stake
+    pub side_pot: u8,
+    /// Bitmask of side pot winners that claimed their share
+    pub side_pot_claimed: u8,
+    /// Rebuys made per seat
+    #[max_len(MAX_PLAYERS)]
+    pub rebuys: Vec<u8>,
+    /// Base units re-staked through rebuys, part of the pot
+    pub rebuy_stakes: u64,
+    /// Base units each seat spent on power-ups
+    #[max_len(MAX_PLAYERS)]
+    pub powerup_spent: Vec<u64>,
+    /// Power-up revenue added to the prize pool (fee-free)
+    pub powerup_pot: u64,
+    /// Spectator tips held for each seat, outside the prize pool
+    #[max_len(MAX_PLAYERS)]
+    pub tips: Vec<u64>,
+    /// Winnings per seat held back to vest, released by `claim_vested`
+    #[max_len(MAX_PLAYERS)]
+    pub vesting: Vec<u64>,
+    /// Vested winnings each seat has claimed
+    #[max_len(MAX_PLAYERS)]
+    pub vested_claimed: Vec<u64>,
+    /// Wallet that paid each seat's stake on the player's behalf, refunded
+    /// in their place (default when the player paid)
+    #[max_len(MAX_PLAYERS)]
+    pub stake_sponsors: Vec<Pubkey>,
+    /// Registered payout wallet of each agent seat, paid its winnings in
+    /// place of the operator key (default for other seats)
+    #[max_len(MAX_PLAYERS)]
+    pub payout_wallets: Vec<Pubkey>,
+    /// Prize paid or vested to each seat at settlement
+    #[max_len(MAX_PLAYERS)]
+    pub winnings: Vec<u64>,
+    /// Bitmask of seats whose result was recorded on their profile
+    pub stats_recorded: u8,
+    /// The result has been applied to the players' ratings
+    pub ratings_updated: bool,
+    /// Seconds over which `vesting` releases, counted from settlement
+    pub vesting_period: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Tokens this game holds in the shared vault of its stake mint
+    /// Kept after `bump` so version 2 accounts upgrade in place
+    pub vault_balance: u64,
+    /// Settlement proposed for a game not paid to a single winner, executed
+    /// once `dispute_deadline` has passed
+    pub proposal: Option<ProposedSettlement>,
+    /// Seats held for players who recently left or were kicked, so they
+    /// can `rejoin_game` ahead of new joiners
+    pub seat_holds: [SeatHold; MAX_SEAT_HOLDS],
+    /// Ratings joiners must fall in
+    pub rating_band: Option<RatingBand>,
+}
+
+impl Game {
+    /// Bytes one seat takes across the per-seat arrays
+    pub const SEAT_SPACE: usize =
+        8 + 2 + 32 + 1 + 1 + 32 + 8 + 1 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 32 + 8;
+    
+    /// Account space for a game with `seats` seats, so small lobbies do
+    /// not pay rent on the full `MAX_PLAYERS` arrays
+    pub fn space(seats: u8) -> usize {
+        8 + Self::INIT_SPACE - MAX_PLAYERS.saturating_sub(seats as usize) * Self::SEAT_SPACE
+    }
+    
+    /// Reset every field for a fresh lobby with `max_players` seats; the
+    /// creation paths then fill in the settings they take from their source
+    pub fn init(
+        &mut self,
+        game_id: [u8; 8],
+        creator: Pubkey,
+        stake_mint: Pubkey,
+        max_players: u8,
+        now: i64,
+        bump: u8,
+    ) {
+        let seats = max_players as usize;
+        self.version = GAME_VERSION;
+        self.game_id = game_id;
+        self.label = None;
+        self.creator = creator;
+        self.stake_amount = 0;
+        self.stake_mint = stake_mint;
+        self.usd_stake = false;
+        self.paid = vec![0u64; seats];
+        self.min_players = max_players;
+        self.max_players = max_players;
+        self.fee_bps = 0;
+        self.creator_fee_bps = 0;
+        self.bounty_bps = 0;
+        self.survival_payout = false;
+        self.side_stake = 0;
+        self.rebuy_window = 0;
+        self.max_rebuys = 0;
+        self.payout_schedule = vec![0u16; seats];
+        self.join_deadline = now;
+        self.scheduled_start = None;
+        self.player_count = 0;
+        self.players = vec![Pubkey::default(); seats];
+        self.status = GameStatus::Waiting;
+        self.seed = [0u8; 32];
+        self.winner = None;
+        self.created_at = now;
+        self.started_at = None;
+        self.finished_at = None;
+        self.cancelled_at = None;
+        self.last_transition_at = now;
+        self.refunded = 0;
+        self.stuck_flags = 0;
+        self.draw_mask = 0;
+        self.teams = vec![0u8; seats];
+        self.winning_team = 0;
+        self.strikes = vec![0u8; seats];
+        self.action_nonce = 0;
+        self.tournament = None;
+        self.tournament_round = 0;
+        self.season = None;
+        self.lobby_page = None;
+        self.allowlisted = false;
+        self.invite_code_hash = None;
+        self.nft_collection = None;
+        self.balance_gate = None;
+        self.funded_prize = 0;
+        self.sponsored_pot = 0;
+        self.rematch_of = None;
+        self.commit_reveal = false;
+        self.commitments = vec![[0u8; 32]; seats];
+        self.revealed = 0;
+        self.randomness_source = RandomnessSource::SlotHash;
+        self.randomness_account = None;
+        self.randomness_slot = 0;
+        self.seed_pending = false;
+        self.seed_commitment = None;
+        self.engine = None;
+        self.move_root = None;
+        self.proposed_winner = None;
+        self.dispute_deadline = None;
+        self.unclaimed = 0;
+        self.house_fee = 0;
+        self.rakeback_accrued = 0;
+        self.jackpot_claimed = false;
+        self.eliminated = 0;
+        self.eliminated_at = vec![0i64; seats];
+        self.elimination_order = vec![0u8; seats];
+        self.bounties = vec![0u64; seats];
+        self.insured = 0;
+        self.insurance_claimed = 0;
+        self.deal = None;
+        self.side_pot = 0;
+        self.side_pot_claimed = 0;
+        self.rebuys = vec![0u8; seats];
+        self.rebuy_stakes = 0;
+        self.powerup_spent = vec![0u64; seats];
+        self.powerup_pot = 0;
+        self.tips = vec![0u64; seats];
+        self.vesting = vec![0u64; seats];
+        self.vested_claimed = vec![0u64; seats];
+        self.vesting_period = 0;
+        self.stake_sponsors = vec![Pubkey::default(); seats];
+        self.payout_wallets = vec![Pubkey::default(); seats];
+        self.winnings = vec![0u64; seats];
+        self.stats_recorded = 0;
+        self.ratings_updated = false;
+        self.bump = bump;
+        self.vault_balance = 0;
+        self.proposal = None;
+        self.seat_holds = [SeatHold::default(); MAX_SEAT_HOLDS];
+        self.rating_band = None;
+    }
+    
+    /// Number of placements that receive a share of the prize pool
+    /// Places are paid by position, so this runs up to the last paid place
+    pub fn paid_places(&self) -> usize {
+        self.payout_schedule
+            .iter()
+            .take(self.player_count as usize)
+            .rposition(|&bps| bps > 0)
+            .map_or(0, |last| last + 1)
+    }
+    
+    /// Whether a proposed result or settlement is in its dispute window
+    pub fn result_pending(&self) -> bool {
+        self.proposed_winner.is_some() || self.proposal.is_some()
+    }
+    
+    /// Drop any proposed result or settlement and its dispute window
+    pub fn clear_proposal(&mut self) {
+        self.proposed_winner = None;
+        self.proposal = None;
+        self.dispute_deadline = None;
+    }
+    
+    /// Require the game to be settled, its vault balance spent and
+    /// `GAME_CLOSE_DELAY_SECS` past its end before it is archived or closed
+    pub fn check_closable(&self, now: i64) -> Result<()> {
+        require!(
+            matches!(
+                self.status,
+                GameStatus::Finished | GameStatus::Cancelled | GameStatus::Voided
+            ),
+            EscrowError::GameNotSettled
+        );
+        require!(self.vault_balance == 0, EscrowError::VaultNotEmpty);
+        let ended_at = self
+            .finished_at
+            .or(self.cancelled_at)
+            .ok_or(EscrowError::GameNotSettled)?;
+        require!(
+            now >= ended_at + GAME_CLOSE_DELAY_SECS,
+            EscrowError::CloseDelayNotPassed
+        );
+        Ok(())
+    }
+    
+    /// Take the proposed settlement once its dispute window has passed
+    pub fn take_settlement(&mut self, now: i64) -> Result<ProposedSettlement> {
+        let settlement = self.proposal.ok_or(EscrowError::NoResultProposed)?;
+        let dispute_deadline = self.dispute_deadline.ok_or(EscrowError::NoResultProposed)?;
+        require!(now >= dispute_deadline, EscrowError::DisputeWindowOpen);
+        self.proposal = None;
+        self.dispute_deadline = None;
+        Ok(settlement)
+    }
+    
+    /// Last moment a player may join - the join deadline, or the
+    /// scheduled start less the join buffer if earlier
+    pub fn join_cutoff(&self) -> i64 {
+        match self.scheduled_start {
+            Some(start) => self.join_deadline.min(start - SCHEDULED_JOIN_BUFFER_SECS),
+            None => self.join_deadline,
+        }
+    }
+    
+    /// Whether seats have been assigned to teams
+    pub fn has_teams(&self) -> bool {
+        self.teams[0] != 0
+    }
+    
+    /// Seat `player` in the next free slot and return the seat index
+    pub fn add_player(&mut self, player: Pubkey) -> Result<usize> {
+        require!(self.player_count < self.max_players, EscrowError::GameFull);
+        
+        // Check player hasn't already joined
+        for i in 0..self.player_count as usize {
+            require!(self.players[i] != player, EscrowError::AlreadyJoined);
+        }
+        
+        let index = self.player_count as usize;
+        self.players[index] = player;
+        self.player_count += 1;
+        
+        // Roster changed - team assignment must be redone
+        self.teams.fill(0);
+        Ok(index)
+    }
+    
+    /// Seat a joining `player` in a free seat that is not held for another
+    /// player's rejoin, releasing `player`'s own hold first
+    pub fn seat_player(&mut self, player: Pubkey, now: i64) -> Result<usize> {
+        if let Some(slot) = self.seat_hold_of(&player, now) {
+            self.seat_holds[slot] = SeatHold::default();
+        }
+        require!(self.player_count < self.max_players, EscrowError::GameFull);
+        require!(
+            self.player_count + self.held_seats(now) < self.max_players,
+            EscrowError::SeatHeld
+        );
+        self.add_player(player)
+    }
+    
+    /// Hold a seat for `player`, who just left or was kicked, until
+    /// `expires_at`; replaces their earlier hold, else a free or expired
+    /// one, else the hold expiring soonest
+    pub fn hold_seat(&mut self, player: Pubkey, now: i64, expires_at: i64) {
+        if expires_at <= now {
+            return;
+        }
+        let slot = self
+            .seat_holds
+            .iter()
+            .position(|hold| hold.player == player)
+            .or_else(|| self.seat_holds.iter().position(|hold| hold.expires_at <= now))
+            .unwrap_or_else(|| {
+                (0..MAX_SEAT_HOLDS)
+                    .min_by_key(|&slot| self.seat_holds[slot].expires_at)
+                    .unwrap_or(0)
+            });
+        self.seat_holds[slot] = SeatHold { player, expires_at };
+    }
+    
+    /// Slot of `player`'s seat hold, if it has not expired by `now`
+    pub fn seat_hold_of(&self, player: &Pubkey, now: i64) -> Option<usize> {
+        self.seat_holds
+            .iter()
+            .position(|hold| hold.player == *player && hold.expires_at > now)
+    }
+    
+    /// Seats held for departed players at `now`
+    pub fn held_seats(&self, now: i64) -> u8 {
+        self.seat_holds
+            .iter()
+            .filter(|hold| hold.expires_at > now)
+            .count() as u8
+    }
+    
+    /// Check `expected` against the current action nonce and advance it
+    /// Returns the nonce consumed by this action
+    pub fn consume_nonce(&mut self, expected: u64) -> Result<u64> {
+        require!(self.action_nonce == expected, EscrowError::NonceMismatch);
+        self.action_nonce = self
+            .action_nonce
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(expected)
+    }
+    
+    /// Base units seat `index` paid in and is refunded
+    pub fn stake_of(&self, index: usize) -> u64 {
+        if self.usd_stake {
+            self.paid[index]
+        } else {
+            self.stake_amount
+        }
+    }
+    
+    /// Wallet refunds of seat `index` go to: its stake sponsor, if any,
+    /// else the player
+    pub fn refund_owner(&self, index: usize) -> Pubkey {
+        if self.stake_sponsors[index] == Pubkey::default() {
+            self.players[index]
+        } else {
+            self.stake_sponsors[index]
+        }
+    }
+    
+    /// Wallet winnings of seat `index` are paid to: the agent's registered
+    /// payout wallet, if any, else the player
+    pub fn payout_owner(&self, index: usize) -> Pubkey {
+        if self.payout_wallets[index] == Pubkey::default() {
+            self.players[index]
+        } else {
+            self.payout_wallets[index]
+        }
+    }
+    
+    /// Base units seat `index` is refunded: the stake, once per rebuy,
+    /// its power-up purchases and any side stake
+    pub fn refund_of(&self, index: usize) -> u64 {
+        let stakes = self.stake_of(index) * (1 + self.rebuys[index] as u64)
+            + self.powerup_spent[index];
+        if self.side_pot & (1 << index) != 0 {
+            stakes + self.side_stake
+        } else {
+            stakes
+        }
+    }
+    
+    /// Side stakes held in the vault
+    pub fn side_pot_total(&self) -> Result<u64> {
+        self.side_stake
+            .checked_mul(self.side_pot.count_ones() as u64)
+            .ok_or(EscrowError::MathOverflow.into())
+    }
+    
+    /// Side pot participants sharing the side pot: the winner if they
+    /// opted in, else the drawn seats, else those never eliminated, else
+    /// the last one eliminated
+    pub fn side_pot_winners(&self) -> u8 {
+        let winner = self
+            .winner
+            .and_then(|winner| self.seat_of(&winner))
+            .map_or(0, |seat| 1u8 << seat);
+        if self.side_pot & winner != 0 {
+            return winner;
+        }
+        if self.side_pot & self.draw_mask != 0 {
+            return self.side_pot & self.draw_mask;
+        }
+        let standing = self.side_pot & !self.eliminated & !winner;
+        if standing != 0 {
+            return standing;
+        }
+        self.elimination_order[..self.eliminated.count_ones() as usize]
+            .iter()
+            .rev()
+            .find(|&&seat| self.side_pot & (1 << seat) != 0)
+            .map_or(0, |&seat| 1 << seat)
+    }
+    
+    /// Base units staked by all seated players, rebuys included
+    pub fn total_stakes(&self) -> Result<u64> {
+        let stakes = if self.usd_stake {
+            self.paid[..self.player_count as usize]
+                .iter()
+                .try_fold(0u64, |total, &paid| total.checked_add(paid))
+        } else {
+            self.stake_amount.checked_mul(self.player_count as u64)
+        };
+        stakes
+            .and_then(|stakes| stakes.checked_add(self.rebuy_stakes))
+            .ok_or(EscrowError::MathOverflow.into())
+    }
+    
+    /// Placements read off a complete elimination log: the last seat
+    /// standing first, then the others in reverse elimination order
+    /// `None` until every seat but one has been reported eliminated
+    pub fn logged_placements(&self) -> Option<Vec<u8>> {
+        let eliminated = self.eliminated.count_ones() as usize;
+        if self.player_count < 2 || eliminated + 1 != self.player_count as usize {
+            return None;
+        }
+        let survivor = (0..self.player_count).find(|&seat| self.eliminated & (1 << seat) == 0)?;
+        let mut placements = vec![survivor];
+        placements.extend(self.elimination_order[..eliminated].iter().rev());
+        Some(placements)
+    }
+    
+    /// Whether the seat at `index` lost a finished game: neither the
+    /// winner, nor in a draw, nor on the winning team
+    pub fn lost(&self, index: usize) -> bool {
+        let player = self.players[index];
+        let drawn = self.draw_mask & (1 << index) != 0;
+        let on_winning_team = self.has_teams() && self.teams[index] == self.winning_team;
+        self.winner != Some(player) && !drawn && !on_winning_team
+    }
+    
+    /// Bitmask of seats not reported eliminated
+    pub fn remaining(&self) -> u8 {
+        ((1u16 << self.player_count) - 1) as u8 & !self.eliminated
+    }
+    
+    /// Stuck conditions (STUCK_* bits) that hold at `now`
+    pub fn stuck_flags_at(&self, now: i64) -> Result<u8> {
+        let mut flags = 0u8;
+        match self.status {
+            GameStatus::Waiting => {
+                if now - self.created_at > STUCK_WAITING_SECS {
+                    flags |= STUCK_WAITING_TOO_LONG;
+                }
+            }
+            GameStatus::Live => {
+                let started_at = self.started_at.unwrap_or(self.created_at);
+                if now - started_at > STUCK_LIVE_SECS {
+                    flags |= STUCK_LIVE_TOO_LONG;
+                }
+            }
+            GameStatus::Cancelled | GameStatus::Voided => {
+                let cancelled_at = self.cancelled_at.unwrap_or(self.last_transition_at);
+                let all_refunded = (1u16 << self.player_count) - 1;
+                if now - cancelled_at > STUCK_REFUND_SECS
+                    && u16::from(self.refunded) != all_refunded
+                {
+                    flags |= STUCK_UNREFUNDED;
+                }
+            }
+            GameStatus::Finished => {
+                // Unclaimed and vesting winnings, bounties, tips and side
+                // pot shares are owed, not stuck
+                let side_pot_owed = if self.side_pot_claimed == 0 {
+                    self.side_pot_total()?
+                } else {
+                    0
+                };
+                let owed = self
+                    .unclaimed
+                    .saturating_add(self.total_bounties()?)
+                    .saturating_add(self.unvested()?)
+                    .saturating_add(self.total_tips()?)
+                    .saturating_add(side_pot_owed);
+                if self.vault_balance > owed {
+                    flags |= STUCK_VAULT_NOT_EMPTY;
+                }
+            }
+        }
+        Ok(flags)
+    }
+    
+    /// Bounties earned and not yet claimed
+    pub fn total_bounties(&self) -> Result<u64> {
+        self.bounties
+            .iter()
+            .try_fold(0u64, |total, &bounty| total.checked_add(bounty))
+            .ok_or(EscrowError::MathOverflow.into())
+    }
+    
+    /// Vesting winnings not yet claimed
+    pub fn unvested(&self) -> Result<u64> {
+        self.vesting
+            .iter()
+            .zip(self.vested_claimed.iter())
+            .try_fold(0u64, |total, (&vesting, &claimed)| total.checked_add(vesting - claimed))
+            .ok_or(EscrowError::MathOverflow.into())
+    }
+    
+    /// Spectator tips held in the vault
+    pub fn total_tips(&self) -> Result<u64> {
+        self.tips
+            .iter()
+            .try_fold(0u64, |total, &tip| total.checked_add(tip))
+            .ok_or(EscrowError::MathOverflow.into())
+    }
+    
+    /// Seat index of `player`, if seated
+    pub fn seat_of(&self, player: &Pubkey) -> Option<usize> {
+        self.players[..self.player_count as usize]
+            .iter()
+            .position(|p| p == player)
+    }
+    
+    /// Record the entropy commitment of the player seated at `index`
+    /// Required in commit-reveal games, ignored otherwise
+    pub fn commit_entropy(&mut self, index: usize, commitment: Option<[u8; 32]>) -> Result<()> {
+        if self.commit_reveal {
+            self.commitments[index] = commitment.ok_or(EscrowError::InvalidCommitment)?;
+        }
+        Ok(())
+    }
+    
+    /// Check the backend's seed against its commitment from `start_game`
+    /// and publish it; games without a commitment must not pass one
+    pub fn reveal_backend_seed(&mut self, seed_reveal: Option<[u8; 32]>) -> Result<()> {
+        let (Some(commitment), Some(seed)) = (self.seed_commitment, seed_reveal) else {
+            require!(seed_reveal.is_none(), EscrowError::InvalidSeedReveal);
+            return Ok(());
+        };
+        require!(
+            self.seed_pending && hash(&seed).to_bytes() == commitment,
+            EscrowError::InvalidSeedReveal
+        );
+        
+        self.seed = if self.commit_reveal {
+            self.mix_revealed_entropy(seed)
+        } else {
+            seed
+        };
+        self.seed_pending = false;
+        
+        emit!(GameSeeded {
+            game_id: self.game_id,
+            seed: self.seed,
+        });
+        Ok(())
+    }
+    
+    /// Open the first engine round once the seed is known
+    pub fn open_engine(&mut self, now: i64) {
+        let alive = ((1u16 << self.player_count) - 1) as u8;
+        if let Some(engine) = self.engine.as_mut() {
+            *engine = EngineState {
+                round: 1,
+                round_deadline: now + ENGINE_ROUND_SECS,
+                alive,
+                ..EngineState::default()
+            };
+        }
+    }
+    
+    /// Resolve the current engine round in seat order, auto-playing seats
+    /// that did not move, then open the next round unless it was decisive
+    pub fn resolve_engine_round(&mut self, now: i64) -> Result<()> {
+        let game_id = self.game_id;
+        let seed = self.seed;
+        let engine = self.engine.as_mut().ok_or(EscrowError::EngineModeRequired)?;
+        let mut board = Board::restore(&seed, engine.revealed);
+        
+        let mut clicks = [(0u8, 0usize); MAX_PLAYERS];
+        let mut click_count = 0;
+        for seat in 0..MAX_PLAYERS {
+            let bit = 1u8 << seat;
+            if engine.alive & bit == 0 {
+                continue;
+            }
+            let cell = if engine.moved & bit != 0 {
+                let EngineMove { action, x, y } = engine.moves[seat];
+                if action == MoveAction::Flag {
+                    continue;
+                }
+                Board::cell(x, y)?
+            } else {
+                match board.first_hidden_safe_cell() {
+                    Some(cell) => cell,
+                    None => continue,
+                }
+            };
+            clicks[click_count] = (seat as u8, cell);
+            click_count += 1;
+        }
+        
+        let alive_before = engine.alive;
+        engine.winner = engine::resolve_round(
+            &mut board,
+            &mut engine.alive,
+            clicks[..click_count].iter().copied(),
+        );
+        engine.revealed = board.revealed();
+        engine.moved = 0;
+        
+        emit!(EngineRoundResolved {
+            game_id,
+            round: engine.round,
+            eliminated: alive_before & !engine.alive,
+            winner: engine.winner,
+        });
+        
+        if engine.winner.is_none() {
+            engine.round += 1;
+            engine.round_deadline = now + ENGINE_ROUND_SECS;
+        }
+        Ok(())
+    }
+    
+    /// XOR every revealed secret into `seed`
+    pub fn mix_revealed_entropy(&self, mut seed: [u8; 32]) -> [u8; 32] {
+        for secret in self.commitments[..self.player_count as usize].iter() {
+            for (byte, s) in seed.iter_mut().zip(secret.iter()) {
+                *byte ^= s;
+            }
+        }
+        seed
+    }
+    
+    /// Add a moderation strike to seat `index`, returning its total
+    pub fn add_strike(&mut self, index: usize) -> u8 {
+        self.strikes[index] = self.strikes[index].saturating_add(1);
+        self.strikes[index]
+    }
+    
+    /// Remove the player at `index`, shifting later seats down
+    pub fn remove_player(&mut self, index: usize) {
+        let count = self.player_count as usize;
+        for i in index..count - 1 {
+            self.players[i] = self.players[i + 1];
+            self.strikes[i] = self.strikes[i + 1];
+            self.commitments[i] = self.commitments[i + 1];
+            self.paid[i] = self.paid[i + 1];
+            self.stake_sponsors[i] = self.stake_sponsors[i + 1];
+            self.payout_wallets[i] = self.payout_wallets[i + 1];
+        }
+        self.players[count - 1] = Pubkey::default();
+        self.strikes[count - 1] = 0;
+        self.paid[count - 1] = 0;
+        self.stake_sponsors[count - 1] = Pubkey::default();
+        self.payout_wallets[count - 1] = Pubkey::default();
+        self.commitments[count - 1] = [0u8; 32];
+        self.player_count -= 1;
+        
+        let below = self.revealed & ((1u16 << index) - 1) as u8;
+        let above = ((self.revealed as u16 >> (index + 1)) << index) as u8;
+        self.revealed = below | above;
+        let below = self.insured & ((1u16 << index) - 1) as u8;
+        let above = ((self.insured as u16 >> (index + 1)) << index) as u8;
+        self.insured = below | above;
+        let below = self.side_pot & ((1u16 << index) - 1) as u8;
+        let above = ((self.side_pot as u16 >> (index + 1)) << index) as u8;
+        self.side_pot = below | above;
+        
+        // Roster changed - team assignment must be redone
+        self.teams.fill(0);
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Parlay {
+    /// Parlay owner, seated in every game the parlay enters
+    pub owner: Pubkey,
+    /// Maximum stake the parlay will pay for a single game
+    pub max_stake: u64,
+    /// Number of games the parlay may still enter
+    pub games_remaining: u8,
+    /// Number of games entered so far
+    pub entered_count: u8,
+    /// Game ids entered by this parlay
+    pub entered_games: [[u8; 8]; MAX_PARLAY_GAMES],
+    /// PDA bump
+    pub bump: u8,
+    /// Parlay vault PDA bump
+    pub vault_bump: u8,
+}
+
+impl Parlay {
+    /// Record entering game `game_id` at `stake_amount` with `balance` left
+    /// in the parlay vault - the stake must fit the parlay's maximum and
+    /// balance, and a game must remain
+    pub fn enter(&mut self, game_id: [u8; 8], stake_amount: u64, balance: u64) -> Result<()> {
+        require!(self.games_remaining > 0, EscrowError::ParlayExhausted);
+        require!(stake_amount <= self.max_stake, EscrowError::StakeAboveParlayMax);
+        require!(stake_amount <= balance, EscrowError::InsufficientBalance);
+        
+        let entered = self
+            .entered_games
+            .get_mut(self.entered_count as usize)
+            .ok_or(EscrowError::ParlayExhausted)?;
+        *entered = game_id;
+        self.entered_count += 1;
+        self.games_remaining -= 1;
+        Ok(())
+    }
+    
+    /// Check the parlay is done with `game`, read from the account at
+    /// `key`: either it no longer sponsors a seat in it, or the game is
+    /// over and, if cancelled or voided, has refunded the parlay `vault`
+    pub fn check_settled(&self, key: &Pubkey, game: &Game, vault: &Pubkey) -> Result<()> {
+        let (address, _) = Pubkey::find_program_address(&[b"game", game.game_id.as_ref()], &ID);
+        require_keys_eq!(*key, address, EscrowError::InvalidParlayGame);
+        
+        // Leaving or being kicked refunds the seat on the spot
+        let Some(seat) = (0..game.player_count as usize)
+            .find(|&seat| game.players[seat] == self.owner && game.stake_sponsors[seat] == *vault)
+        else {
+            return Ok(());
+        };
+        match game.status {
+            GameStatus::Waiting | GameStatus::Live => err!(EscrowError::ParlayGameOpen),
+            GameStatus::Cancelled | GameStatus::Voided => {
+                require!(game.refunded & (1 << seat) != 0, EscrowError::ParlayRefundPending);
+                Ok(())
+            }
+            GameStatus::Finished => Ok(()),
+        }
+    }
+}
+
+/// Heads-up game - a lighter layout than `Game` for the 2-player format
+#[account]
+#[derive(InitSpace)]
+pub struct Duel {
+    /// Unique duel identifier
+    pub duel_id: [u8; 8],
+    /// Creator (seat 0) and opponent (seat 1)
+    pub players: [Pubkey; 2],
+    /// Stake amount per player (in USDC lamports)
+    pub stake_amount: u64,
+    /// House fee in basis points
+    pub fee_bps: u16,
+    /// Duel status
+    pub status: GameStatus,
+    /// Seed for board generation (set when the opponent joins)
+    pub seed: [u8; 32],
+    /// Winner pubkey (set after settlement)
+    pub winner: Option<Pubkey>,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Start timestamp
+    pub started_at: Option<i64>,
+    /// Mint the stakes are held in
+    pub stake_mint: Pubkey,
+    /// Base units this duel holds in the shared vault for its stake mint
+    pub vault_balance: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Best-of-N series - one escrowed buy-in across linked games
+#[account]
+#[derive(InitSpace)]
+pub struct Series {
+    /// Unique series identifier
+    pub series_id: [u8; 8],
+    /// Series creator
+    pub creator: Pubkey,
+    /// Buy-in per player (in USDC lamports)
+    pub buy_in: u64,
+    /// House fee in basis points
+    pub fee_bps: u16,
+    /// Seats in the series
+    pub max_players: u8,
+    /// Game wins needed to take the series
+    pub wins_required: u8,
+    /// Number of players joined
+    pub player_count: u8,
+    /// Player pubkeys
+    pub players: [Pubkey; MAX_PLAYERS],
+    /// Game wins per seat
+    pub wins: [u8; MAX_PLAYERS],
+    /// Games recorded so far
+    pub games_played: u8,
+    /// Series status
+    pub status: GameStatus,
+    /// Seed for the current game's board
+    pub seed: [u8; 32],
+    /// Series winner (set once a player reaches `wins_required`)
+    pub winner: Option<Pubkey>,
+    /// Bitmask of seats refunded after cancellation
+    pub refunded: u8,
+    /// Next expected nonce for `record_series_game`
+    pub action_nonce: u64,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Game the series is currently playing, until it is recorded
+    pub current_game: Option<Pubkey>,
+    /// When the series started or last recorded a game
+    pub last_recorded_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Series {
+    /// Check `expected` against the current action nonce and advance it
+    pub fn consume_nonce(&mut self, expected: u64) -> Result<u64> {
+        require!(self.action_nonce == expected, EscrowError::NonceMismatch);
+        self.action_nonce = self
+            .action_nonce
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(expected)
+    }
+}
+
+/// Single-elimination tournament - entry fees escrowed until a champion
+/// emerges from heads-up bracket matches
+#[account]
+#[derive(InitSpace)]
+pub struct Tournament {
+    /// Unique tournament identifier
+    pub tournament_id: [u8; 8],
+    /// Tournament creator
+    pub creator: Pubkey,
+    /// Entry fee per player (in USDC lamports)
+    pub entry_fee: u64,
+    /// House fee in basis points
+    pub fee_bps: u16,
+    /// Bracket size (power of two)
+    pub max_entrants: u8,
+    /// Number of entrants joined
+    pub entrant_count: u8,
+    /// Entrant pubkeys in bracket order
+    pub entrants: [Pubkey; MAX_TOURNAMENT_ENTRANTS],
+    /// Bitmask of entrants still in the bracket
+    pub alive: u16,
+    /// Current bracket round
+    pub round: u8,
+    /// Tournament status
+    pub status: GameStatus,
+    /// Champion (set when one entrant remains)
+    pub champion: Option<Pubkey>,
+    /// Bitmask of entrants refunded after cancellation
+    pub refunded: u16,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// When the bracket last started or advanced
+    pub last_advanced_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Tournament {
+    /// Bracket index of `player`, if entered
+    pub fn entrant_index(&self, player: &Pubkey) -> Option<usize> {
+        self.entrants[..self.entrant_count as usize]
+            .iter()
+            .position(|p| p == player)
+    }
+    
+    /// Whether `first` and `second` meet in the current round - they must
+    /// be alive and paired as consecutive alive entrants
+    pub fn is_bracket_pair(&self, first: u8, second: u8) -> bool {
+        let alive: Vec<u8> = (0..self.entrant_count)
+            .filter(|&i| self.alive & (1 << i) != 0)
+            .collect();
+        alive
+            .chunks(2)
+            .any(|pair| pair.len() == 2 && pair[0] == first && pair[1] == second)
+    }
+}
+
+/// A player's accumulated season points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct SeasonStanding {
+    pub player: Pubkey,
+    pub points: u32,
+}
+
+/// Season / league - accrues points and a share of rake from opted-in
+/// games and pays the pool out by final standings
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    /// Unique season identifier
+    pub season_id: [u8; 8],
+    /// Share of each game's house fee paid into the prize pool (bps)
+    pub rake_share_bps: u16,
+    /// Points for winning a season game
+    pub win_points: u32,
+    /// Points for playing a season game
+    pub play_points: u32,
+    /// Season start timestamp
+    pub starts_at: i64,
+    /// Season end timestamp
+    pub ends_at: i64,
+    /// Prize split per final standing (bps)
+    pub payout_schedule: [u16; MAX_PLAYERS],
+    /// Season status (Live until finalized)
+    pub status: GameStatus,
+    /// Total rake accrued into the prize pool
+    pub prize_pool: u64,
+    /// Number of ranked players
+    pub entry_count: u8,
+    /// Points per ranked player, in order of first appearance
+    pub standings: [SeasonStanding; MAX_SEASON_PLAYERS],
+    /// PDA bump
+    pub bump: u8,
+    /// Vault PDA bump
+    pub vault_bump: u8,
+}
+
+impl Season {
+    /// Whether new games may join the season at `now`
+    pub fn is_active(&self, now: i64) -> bool {
+        self.status == GameStatus::Live && now >= self.starts_at && now < self.ends_at
+    }
+    
+    /// Address of the season vault
+    pub fn vault_address(&self, program_id: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(
+            &[b"season_vault", self.season_id.as_ref(), &[self.vault_bump]],
+            program_id,
+        )
+        .map_err(|_| error!(EscrowError::InvalidSeason))
+    }
+    
+    /// Award points for a settled game and return the rake share owed to
+    /// the prize pool
+    /// A finalized season no longer accrues; players beyond
+    /// `MAX_SEASON_PLAYERS` are not ranked
+    pub fn record_game(&mut self, game: &Game, winner: Pubkey, house_fee: u64) -> Result<u64> {
+        if self.status != GameStatus::Live {
+            return Ok(0);
+        }
+        
+        for &player in game.players[..game.player_count as usize].iter() {
+            let points = if player == winner {
+                self.win_points.saturating_add(self.play_points)
+            } else {
+                self.play_points
+            };
+            self.award(player, points);
+        }
+        
+        let cut = payout::season_cut(house_fee, self.rake_share_bps)?;
+        self.prize_pool = self
+            .prize_pool
+            .checked_add(cut)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(cut)
+    }
+    
+    fn award(&mut self, player: Pubkey, points: u32) {
+        let count = self.entry_count as usize;
+        match self.standings[..count].iter().position(|s| s.player == player) {
+            Some(index) => {
+                let standing = &mut self.standings[index];
+                standing.points = standing.points.saturating_add(points);
+            }
+            None if count < MAX_SEASON_PLAYERS => {
+                self.standings[count] = SeasonStanding { player, points };
+                self.entry_count += 1;
+            }
+            None => {}
+        }
+    }
+    
+    /// Standing indices ordered by points, earliest entry first on ties
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.entry_count as usize).collect();
+        ranking.sort_by(|&a, &b| self.standings[b].points.cmp(&self.standings[a].points));
+        ranking
+    }
+    
+    /// Number of standings that receive a prize
+    /// Places are paid by position, so this runs up to the last paid place
+    pub fn paid_places(&self) -> usize {
+        self.payout_schedule
+            .iter()
+            .take(self.entry_count as usize)
+            .rposition(|&bps| bps > 0)
+            .map_or(0, |last| last + 1)
+    }
+}
+
+/// Sit-and-go queue for one stake tier - entrants' stakes wait in the
+/// queue vault until a full table is formed into a game
+#[account]
+#[derive(InitSpace)]
+pub struct Queue {
+    /// Stake per entrant (in USDC lamports)
+    pub stake_amount: u64,
+    /// House fee of formed games in basis points
+    pub fee_bps: u16,
+    /// Seconds a formed game has to start before it can be expired
+    pub join_window: i64,
+    /// Number of queued entrants
+    pub entrant_count: u8,
+    /// Queued entrants in arrival order
+    pub entrants: [Pubkey; QUEUE_SIZE],
+    /// Games formed from this queue
+    pub games_formed: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// Vault PDA bump
+    pub vault_bump: u8,
+}
+
+/// One page of the open-lobby registry - every waiting game created
+/// through `create_game` is listed on a page until it starts, is
+/// cancelled, or expires
+/// Each listing costs its creator the config's `listing_fee`
+#[account]
+#[derive(InitSpace)]
+pub struct LobbyRegistry {
+    /// Page number (part of the PDA seeds)
+    pub page: u16,
+    /// Number of listed lobbies
+    pub count: u8,
+    /// Listed game accounts; order is not preserved on removal
+    pub lobbies: [Pubkey; LOBBY_PAGE_SIZE],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl LobbyRegistry {
+    /// List a game on this page
+    pub fn insert(&mut self, game: Pubkey) -> Result<()> {
+        require!(
+            (self.count as usize) < LOBBY_PAGE_SIZE,
+            EscrowError::LobbyRegistryFull
+        );
+        self.lobbies[self.count as usize] = game;
+        self.count += 1;
+        Ok(())
+    }
+    
+    /// Remove a game from this page, filling its slot with the last entry
+    pub fn remove(&mut self, game: &Pubkey) {
+        let count = self.count as usize;
+        if let Some(index) = self.lobbies[..count].iter().position(|g| g == game) {
+            self.lobbies[index] = self.lobbies[count - 1];
+            self.lobbies[count - 1] = Pubkey::default();
+            self.count -= 1;
+        }
+    }
+}
+
+/// Global game ID counter - game PDAs are derived from `next_id` so IDs
+/// can never collide
+#[account]
+#[derive(InitSpace)]
+pub struct GameCounter {
+    /// ID the next game will take
+    pub next_id: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl GameCounter {
+    /// Take the next game ID (little-endian bytes of the counter)
+    pub fn take_id(&mut self) -> Result<[u8; 8]> {
+        let game_id = self.next_id.to_le_bytes();
+        self.next_id = self.next_id.checked_add(1).ok_or(EscrowError::MathOverflow)?;
+        Ok(game_id)
+    }
+}
+
+/// Recurring game template - lobbies are spawned permissionlessly on a
+/// fixed cadence with the template's stake, fee and payout schedule
+#[account]
+#[derive(InitSpace)]
+pub struct GameTemplate {
+    /// Unique template identifier (also the label of spawned games)
+    pub template_id: [u8; 8],
+    /// Template creator
+    pub creator: Pubkey,
+    /// Stake amount per player (in USDC lamports)
+    pub stake_amount: u64,
+    /// Minimum players to start
+    pub min_players: u8,
+    /// Maximum seats
+    pub max_players: u8,
+    /// House fee in basis points
+    pub fee_bps: u16,
+    /// Payout split per placement (bps)
+    pub payout_schedule: [u16; MAX_PLAYERS],
+    /// Number of placements in the payout schedule
+    pub schedule_len: u8,
+    /// Scheduled start of the next lobby to spawn
+    pub next_start: i64,
+    /// Seconds between scheduled starts
+    pub cadence_secs: i64,
+    /// How long before its start a lobby may be spawned
+    pub lead_secs: i64,
+    /// Seconds after its start an unstarted lobby can be expired
+    pub expiry_secs: i64,
+    /// Lobbies spawned so far
+    pub games_spawned: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl GameTemplate {
+    /// Move `next_start` past slots whose joins have already closed
+    pub fn skip_missed(&mut self, now: i64) -> Result<()> {
+        let closes_at = self.next_start - SCHEDULED_JOIN_BUFFER_SECS;
+        if closes_at <= now {
+            let missed = (now - closes_at) / self.cadence_secs + 1;
+            self.next_start = missed
+                .checked_mul(self.cadence_secs)
+                .and_then(|skip| self.next_start.checked_add(skip))
+                .ok_or(EscrowError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Record a spawned lobby and move on to the next slot
+    pub fn advance(&mut self) -> Result<()> {
+        self.games_spawned = self
+            .games_spawned
+            .checked_add(1)
+            .ok_or(EscrowError::MathOverflow)?;
+        self.next_start = self
+            .next_start
+            .checked_add(self.cadence_secs)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Wallets allowed to join a private game
+#[account]
+#[derive(InitSpace)]
+pub struct Allowlist {
+    /// Game this allowlist gates
+    pub game: Pubkey,
+    /// Number of allowed wallets
+    pub count: u8,
+    /// Allowed wallets
+    pub entries: [Pubkey; MAX_ALLOWLIST],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Allowlist {
+    /// Replace the allowed wallets
+    pub fn set_entries(&mut self, entries: &[Pubkey]) -> Result<()> {
+        require!(entries.len() <= MAX_ALLOWLIST, EscrowError::AllowlistFull);
+        self.entries = [Pubkey::default(); MAX_ALLOWLIST];
+        self.entries[..entries.len()].copy_from_slice(entries);
+        self.count = entries.len() as u8;
+        Ok(())
+    }
+    
+    /// Whether `player` is allowed to join
+    pub fn contains(&self, player: &Pubkey) -> bool {
+        self.entries[..self.count as usize].contains(player)
+    }
+}
+
+/// Minimum balance of `mint` a joiner must hold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct BalanceGate {
+    pub mint: Pubkey,
+    pub min_amount: u64,
+}
+
+/// Inclusive range of profile ratings a game admits
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RatingBand {
+    pub min_rating: u32,
+    pub max_rating: u32,
+}
+
+impl RatingBand {
+    /// Whether a player rated `rating` may join
+    pub fn admits(&self, rating: u32) -> bool {
+        self.min_rating <= rating && rating <= self.max_rating
+    }
+}
+
+/// A sponsor's running contribution to one game's prize pool
+#[account]
+#[derive(InitSpace)]
+pub struct PotContribution {
+    /// Sponsored game
+    pub game: Pubkey,
+    /// Sponsoring wallet
+    pub sponsor: Pubkey,
+    /// Total contributed
+    pub amount: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Source of board seeds, chosen per deployment in the config
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RandomnessSource {
+    /// Derived from chain state at `start_game`
+    SlotHash,
+    /// Switchboard On-Demand randomness, revealed after start
+    SwitchboardVrf,
+    /// ORAO VRF request seeded with the game address, fulfilled after start
+    OraoVrf,
+    /// Chain state mixed with secrets revealed by every player
+    CommitReveal,
+    /// Backend seed, committed by hash at start and revealed at settlement
+    BackendCommit,
+}
+
+/// A seat's move in the current round of an engine game
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct EngineMove {
+    pub action: MoveAction,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// Round state of a game played by the on-chain engine
+/// The board itself is regenerated from the game seed when needed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct EngineState {
+    /// Current round, starting at 1 once the seed is known
+    pub round: u16,
+    /// Moves for the current round close after this timestamp
+    pub round_deadline: i64,
+    /// Bitmap of revealed cells
+    pub revealed: u128,
+    /// Bitmask of seats still in the game
+    pub alive: u8,
+    /// Bitmask of seats that have moved this round
+    pub moved: u8,
+    /// Each seat's move this round, valid where `moved` is set
+    pub moves: [EngineMove; MAX_PLAYERS],
+    /// Winning seat once the engine has decided the game
+    pub winner: Option<u8>,
+}
+
+/// A move recorded in a game's move log
+/// Plain repr(C) fields with explicit padding, as the log is zero-copy
+#[zero_copy]
+#[derive(Default)]
+pub struct LoggedMove {
+    /// Slot the move landed in
+    pub slot: u64,
+    /// Seat index of the player
+    pub player: u8,
+    /// `MoveAction` as its discriminant
+    pub action: u8,
+    pub x: u8,
+    pub y: u8,
+    pub _padding: [u8; 4],
+}
+
+/// Append-only record of the moves submitted in a game
+/// Zero-copy, so appending never deserializes the whole log
+#[account(zero_copy)]
+pub struct MoveLog {
+    /// Game the moves belong to
+    pub game: Pubkey,
+    /// Recorded moves, oldest first
+    pub moves: [LoggedMove; MAX_LOGGED_MOVES],
+    /// Number of recorded moves
+    pub count: u16,
+    /// PDA bump
+    pub bump: u8,
+    pub _padding: [u8; 5],
+}
+
+impl LoggedMove {
+    /// Decode a packed batch move: the seat in the low bits of the first
+    /// byte with the top bit set for a flag, then x and y
+    pub fn unpack(packed: &[u8], slot: u64) -> Result<Self> {
+        let action = if packed[0] & 0x80 != 0 {
+            MoveAction::Flag
+        } else {
+            MoveAction::Click
+        };
+        let (x, y) = (packed[1], packed[2]);
+        Board::cell(x, y)?;
+        Ok(Self {
+            slot,
+            player: packed[0] & 0x7f,
+            action: action as u8,
+            x,
+            y,
+            ..Self::default()
+        })
+    }
+}
+
+impl MoveLog {
+    /// Size of the account data after the discriminator
+    pub const SIZE: usize = std::mem::size_of::<Self>();
+    
+    /// Merkle root of the recorded moves, leaves in log order
+    pub fn root(&self) -> [u8; 32] {
+        engine::merkle_root(
+            self.moves[..self.count as usize]
+                .iter()
+                .map(|logged| {
+                    hashv(&[
+                        &logged.slot.to_le_bytes(),
+                        &[logged.player, logged.action, logged.x, logged.y],
+                    ])
+                    .to_bytes()
+                })
+                .collect(),
+        )
+    }
+    
+    /// Append a move
+    pub fn push(&mut self, logged: LoggedMove) -> Result<()> {
+        let index = self.count as usize;
+        require!(index < MAX_LOGGED_MOVES, EscrowError::MoveLogFull);
+        self.moves[index] = logged;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Verifying key for proof-settled games
+#[account]
+#[derive(InitSpace)]
+pub struct SettlementVerifier {
+    pub verifying_key: VerifyingKey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Enclave the game server runs in, fixed by its attested measurement
+/// The SGX quote binding `enclave_key` to `measurement` is checked when the
+/// admin registers the key; the chain then checks each result report
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct TeeConfig {
+    /// Expected enclave measurement (MRENCLAVE)
+    pub measurement: [u8; 32],
+    /// Ed25519 key generated inside the enclave
+    pub enclave_key: Pubkey,
+}
+
+impl TeeConfig {
+    /// The report the enclave signs for a result
+    pub fn result_report(&self, game_id: &[u8; 8], winner: &Pubkey, move_root: &[u8; 32]) -> Vec<u8> {
+        [
+            b"happybomber:result".as_ref(),
+            &self.measurement,
+            game_id,
+            winner.as_ref(),
+            move_root,
+        ]
+        .concat()
+    }
+    
+    /// The report the enclave signs for a proposed settlement
+    pub fn settlement_report(
+        &self,
+        game_id: &[u8; 8],
+        settlement: &ProposedSettlement,
+        move_root: &[u8; 32],
+    ) -> Result<Vec<u8>> {
+        Ok([
+            b"happybomber:settlement".as_ref(),
+            &self.measurement,
+            game_id,
+            &settlement.try_to_vec()?,
+            move_root,
+        ]
+        .concat())
+    }
+}
+
+/// Seat a waiting game keeps open for a departed player until `expires_at`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct SeatHold {
+    pub player: Pubkey,
+    pub expires_at: i64,
+}
+
+/// A recipient's cut of every house fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct FeeShare {
+    /// Wallet whose token account receives the share
+    pub recipient: Pubkey,
+    /// Share of the house fee in basis points
+    pub bps: u16,
+}
+
+/// Payouts above `threshold` base units are held in the game vault and
+/// released linearly over `period` seconds from settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct VestingTerms {
+    pub threshold: u64,
+    pub period: i64,
+}
+
+/// Loss insurance: a premium paid on join into the mint's insurance pool
+/// buys a partial stake refund if the player loses
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct InsuranceTerms {
+    /// Premium in basis points of the player's stake
+    pub premium_bps: u16,
+    /// Refund on a loss in basis points of the player's stake
+    pub refund_bps: u16,
+}
+
+/// House fee charged on games staked at least `min_stake` per seat
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct FeeTier {
+    /// Smallest per-seat stake in the tier, in base units of the default
+    /// stake mint
+    pub min_stake: u64,
+    /// House fee in basis points (at most `config.max_fee_bps`)
+    pub fee_bps: u16,
+}
+
+/// Time range, `start` inclusive and `end` exclusive, in which no house
+/// fee is charged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RakeFreeWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Burn of a share of the house fee on games staked in `mint`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RakeBurn {
+    /// Our native token - the only mint whose rake is burned
+    pub mint: Pubkey,
+    /// Share of the house fee burned, in basis points
+    pub bps: u16,
+}
+
+/// M-of-N referee keys that co-sign results
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RefereeQuorum {
+    /// Referee ed25519 keys (first `count` entries are used)
+    pub keys: [Pubkey; MAX_REFEREES],
+    pub count: u8,
+    /// Distinct referee signatures a result needs
+    pub threshold: u8,
+}
+
+impl RefereeQuorum {
+    /// The message referees sign for a result
+    pub fn result_message(game_id: &[u8; 8], winner: &Pubkey) -> Vec<u8> {
+        [b"happybomber:referee".as_ref(), game_id, winner.as_ref()].concat()
+    }
+    
+    /// The message referees sign for a proposed settlement
+    pub fn settlement_message(game_id: &[u8; 8], settlement: &ProposedSettlement) -> Result<Vec<u8>> {
+        Ok([
+            b"happybomber:referee-settlement".as_ref(),
+            game_id,
+            &settlement.try_to_vec()?,
+        ]
+        .concat())
+    }
+    
+    /// Number of distinct referees among `signers`
+    pub fn approvals(&self, signers: &[Pubkey]) -> u8 {
+        self.keys[..self.count as usize]
+            .iter()
+            .filter(|key| signers.contains(key))
+            .count() as u8
+    }
+}
+
+/// Binds a player to the referrer credited with a slice of their rake
+#[account]
+#[derive(InitSpace)]
+pub struct Referral {
+    /// Referred player
+    pub player: Pubkey,
+    /// Referrer credited at settlement
+    pub referrer: Pubkey,
+    /// Registration timestamp
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A referrer's earnings in one mint, held in that mint's referral pool
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerBalance {
+    /// Referrer the balance belongs to
+    pub referrer: Pubkey,
+    /// Mint the earnings are in
+    pub mint: Pubkey,
+    /// Credited and not yet claimed
+    pub claimable: u64,
+    /// Credited over the referrer's lifetime
+    pub total_earned: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Per-player record kept across games
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    /// Player the profile belongs to
+    pub player: Pubkey,
+    /// Settled games the player had a seat in
+    pub games_played: u32,
+    /// Games the player won, drew or won with their team
+    pub wins: u32,
+    /// Games the player lost
+    pub losses: u32,
+    /// Wins since the player's last recorded loss
+    pub win_streak: u32,
+    /// Lifetime base units staked, including rebuys, power-ups and side
+    /// stakes, summed across mints
+    pub total_staked: u64,
+    /// Lifetime prize base units won, vested or not, summed across mints
+    pub total_won: u64,
+    /// Elo rating, meaningful once `rated_games` is non-zero
+    pub rating: u32,
+    /// Games applied to the rating
+    pub rated_games: u32,
+    /// Number of registered withdrawal destinations
+    pub withdrawal_address_count: u8,
+    /// Wallets payouts and balance withdrawals may go to; the first is
+    /// where settlement pays (unrestricted when none are registered)
+    pub withdrawal_addresses: [Pubkey; MAX_WITHDRAWAL_ADDRESSES],
+    /// Lifetime house fee paid on the player's stakes in the default
+    /// stake mint
+    pub rake_paid: u64,
+    /// Rakeback accrued and not yet claimed
+    pub rakeback_claimable: u64,
+    /// Rakeback claimed over the player's lifetime
+    pub rakeback_claimed: u64,
+    /// Moderation strikes issued against the player
+    pub strikes: u32,
+    /// Number of mints with a stats bucket
+    pub mint_stat_count: u8,
+    /// Lifetime stake and winnings per stake mint played in
+    pub mint_stats: [MintStats; MAX_STAT_MINTS],
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A player's lifetime stake and winnings in one stake mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct MintStats {
+    pub mint: Pubkey,
+    pub staked: u64,
+    pub won: u64,
+}
+
+/// Milestone a player can unlock an achievement for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AchievementKind {
+    /// Won a game
+    FirstWin,
+    /// Won `ACHIEVEMENT_WIN_STREAK` recorded games in a row
+    WinStreak,
+    /// Won the jackpot by clearing the board without a wrong flag
+    JackpotClear,
+}
+
+/// Badge proving a player reached a milestone, one per player and kind
+#[account]
+#[derive(InitSpace)]
+pub struct Achievement {
+    pub player: Pubkey,
+    pub kind: AchievementKind,
+    /// Game the achievement was unlocked in
+    pub game_id: [u8; 8],
+    pub unlocked_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A player's stats for one stats season, kept after the season ends
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonStats {
+    pub player: Pubkey,
+    /// Stats season these cover
+    pub season: u16,
+    /// Settled games the player had a seat in
+    pub games_played: u32,
+    /// Games the player won, drew or won with their team
+    pub wins: u32,
+    /// Games the player lost
+    pub losses: u32,
+    /// Base units staked, including rebuys, power-ups and side stakes
+    pub total_staked: u64,
+    /// Prize base units won, vested or not
+    pub total_won: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    /// Lifetime winnings net of everything staked
+    pub fn net_winnings(&self) -> i64 {
+        (self.total_won as i128 - self.total_staked as i128)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
     
-    #[account(
-        init,
-        payer = creator,
-        seeds = [b"vault", game_id.as_ref()],
-        bump,
-        token::mint = usdc_mint,
-        token::authority = vault,
-    )]
-    pub vault: Account<'info, TokenAccount>,
+    /// Registered withdrawal destinations
+    pub fn withdrawal_addresses(&self) -> &[Pubkey] {
+        &self.withdrawal_addresses[..self.withdrawal_address_count as usize]
+    }
     
-    pub usdc_mint: Account<'info, Mint>,
+    /// Stats buckets of the mints the player has played in
+    pub fn mint_stats(&self) -> &[MintStats] {
+        &self.mint_stats[..self.mint_stat_count as usize]
+    }
     
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    /// Add a game's stake and winnings to the bucket of `mint`, opening
+    /// one if the player has not played in it before
+    pub fn record_in_mint(&mut self, mint: Pubkey, staked: u64, won: u64) -> Result<()> {
+        let index = match self.mint_stats().iter().position(|stats| stats.mint == mint) {
+            Some(index) => index,
+            None => {
+                let index = self.mint_stat_count as usize;
+                require!(index < MAX_STAT_MINTS, EscrowError::TooManyMints);
+                self.mint_stats[index] = MintStats {
+                    mint,
+                    ..Default::default()
+                };
+                self.mint_stat_count += 1;
+                index
+            }
+        };
+        let stats = &mut self.mint_stats[index];
+        stats.staked = stats.staked.checked_add(staked).ok_or(EscrowError::MathOverflow)?;
+        stats.won = stats.won.checked_add(won).ok_or(EscrowError::MathOverflow)?;
+        Ok(())
+    }
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    /// Remove the bucket of `mint`, adding its totals to the bucket of
+    /// `into` if given, and return what it held
+    pub fn merge_mint_stats(&mut self, mint: &Pubkey, into: Option<Pubkey>) -> Result<MintStats> {
+        let count = self.mint_stat_count as usize;
+        let index = self
+            .mint_stats()
+            .iter()
+            .position(|stats| stats.mint == *mint)
+            .ok_or(EscrowError::NoMintStats)?;
+        let merged = self.mint_stats[index];
+        self.mint_stats.copy_within(index + 1..count, index);
+        self.mint_stats[count - 1] = MintStats::default();
+        self.mint_stat_count -= 1;
+        if let Some(into) = into {
+            self.record_in_mint(into, merged.staked, merged.won)?;
+        }
+        Ok(merged)
+    }
+    
+    /// Lifetime winnings in `mint` net of everything staked in it
+    pub fn net_winnings_in(&self, mint: &Pubkey) -> i64 {
+        self.mint_stats()
+            .iter()
+            .find(|stats| stats.mint == *mint)
+            .map_or(0, |stats| {
+                (stats.won as i128 - stats.staked as i128)
+                    .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+            })
+    }
+    
+    /// Current rating, or the starting rating before any rated game
+    pub fn current_rating(&self) -> u32 {
+        if self.rated_games == 0 {
+            rating::INITIAL_RATING
+        } else {
+            self.rating
+        }
+    }
+}
+
+/// A player's place on the global leaderboard
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    /// Lifetime net winnings in the default stake mint as of the player's
+    /// last recorded game
+    pub net_winnings: i64,
+}
+
+/// Top players by lifetime net winnings in the default stake mint, which
+/// other mints' units are not comparable with, kept sorted best first so
+/// standings render from a single account read
+#[account]
+#[derive(InitSpace)]
+pub struct Leaderboard {
+    /// Number of ranked players
+    pub entry_count: u8,
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Leaderboard {
+    /// Re-rank `player` at `net_winnings`; a player who falls below a
+    /// full board drops off it
+    pub fn update(&mut self, player: Pubkey, net_winnings: i64) {
+        let mut count = self.entry_count as usize;
+        if let Some(index) = self.entries[..count].iter().position(|e| e.player == player) {
+            self.entries.copy_within(index + 1..count, index);
+            count -= 1;
+        }
+        
+        // Ties keep the earlier entry ahead
+        let position = self.entries[..count]
+            .iter()
+            .position(|e| e.net_winnings < net_winnings)
+            .unwrap_or(count);
+        if position < LEADERBOARD_SIZE {
+            let end = count.min(LEADERBOARD_SIZE - 1);
+            self.entries.copy_within(position..end, position + 1);
+            self.entries[position] = LeaderboardEntry { player, net_winnings };
+            count = (count + 1).min(LEADERBOARD_SIZE);
+        }
+        for entry in self.entries[count..].iter_mut() {
+            *entry = LeaderboardEntry::default();
+        }
+        self.entry_count = count as u8;
+    }
+}
+
+/// A settlement the backend proposed, paid out by the matching
+/// `end_game_*` instruction or `settle_deal` after the dispute window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProposedSettlement {
+    /// Seat indices from first place down; the first `count` are used
+    Ranked { placements: [u8; MAX_PLAYERS], count: u8 },
+    /// Bitmask of the tied seats
+    Draw { tied: u8 },
+    /// Id of the winning team
+    Team { team: u8 },
+    /// Seats still standing survived until `ended_at`
+    Survival { ended_at: i64 },
+    /// The deal every remaining player accepted
+    Deal,
+}
+
+impl ProposedSettlement {
+    /// Whether a replayed winner at `seat` disproves the settlement
+    /// Survival and deal payouts follow the reported eliminations and the
+    /// players' own agreement, not the board, so a replay cannot
+    pub fn contradicts(&self, game: &Game, seat: u8) -> bool {
+        match *self {
+            Self::Ranked { placements, .. } => placements[0] != seat,
+            Self::Draw { tied } => tied & (1 << seat) == 0,
+            Self::Team { team } => game.teams[seat as usize] != team,
+            Self::Survival { .. } | Self::Deal => false,
+        }
+    }
+}
+
+/// A split of the prize pool the remaining players agree to end on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Deal {
+    /// Share of the prize pool per seat, in basis points
+    pub splits: [u16; MAX_PLAYERS],
+    /// Bitmask of seats that accepted the deal
+    pub accepted: u8,
+}
+
+/// A spectator's running tip to one player of one game
+#[account]
+#[derive(InitSpace)]
+pub struct Tip {
+    /// Game being played
+    pub game: Pubkey,
+    /// Tipping wallet
+    pub tipper: Pubkey,
+    /// Tipped player
+    pub player: Pubkey,
+    /// Total tipped
+    pub amount: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Spectator parimutuel pool on one game's winner
+#[account]
+#[derive(InitSpace)]
+pub struct BettingPool {
+    /// Game the pool resolves against
+    pub game: Pubkey,
+    /// Mint of the betting vault, the game's stake mint
+    pub mint: Pubkey,
+    /// Total backing per seat
+    pub backed: [u64; MAX_PLAYERS],
+    /// Total of all bets
+    pub total: u64,
+    /// Vig to the treasury, snapshotted from the config at creation
+    pub vig_bps: u16,
+    /// Bets left to share among the winning backers after the vig
+    pub payout_pool: u64,
+    /// Seat the pool settled on
+    pub winning_seat: Option<u8>,
+    pub status: BettingStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Betting vault PDA bump
+    pub vault_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BettingStatus {
+    /// Taking bets until the game ends
+    Open,
+    /// Winning backers can claim their share
+    Settled,
+    /// Every bet is refunded
+    Voided,
+}
+
+/// A spectator's running bet on one seat
+#[account]
+#[derive(InitSpace)]
+pub struct Bet {
+    pub betting_pool: Pubkey,
+    pub bettor: Pubkey,
+    /// Seat backed
+    pub seat: u8,
+    /// Total staked on the seat
+    pub amount: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// "Pink slip" game - every player stakes an NFT from one collection
+/// and the winner takes them all
+#[account]
+#[derive(InitSpace)]
+pub struct NftGame {
+    /// Unique NFT game identifier
+    pub nft_game_id: [u8; 8],
+    /// Game creator (seat 0)
+    pub creator: Pubkey,
+    /// Verified collection every staked NFT must belong to
+    pub collection: Pubkey,
+    /// Player pubkeys (first `player_count` entries are seated)
+    pub players: [Pubkey; MAX_PLAYERS],
+    /// Mint of the NFT each seat staked
+    pub nfts: [Pubkey; MAX_PLAYERS],
+    /// Number of players joined
+    pub player_count: u8,
+    /// Seats to fill before the game starts
+    pub max_players: u8,
+    /// Game status
+    pub status: GameStatus,
+    /// Seed for board generation (set when the last seat fills)
+    pub seed: [u8; 32],
+    /// Winner pubkey (set after settlement)
+    pub winner: Option<Pubkey>,
+    /// Bitmask of seats whose NFT has left escrow
+    pub released: u8,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Start timestamp
+    pub started_at: Option<i64>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A player's in-program balance in one stake mint, held in the mint's
+/// balance vault
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerBalance {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    /// Base units available to stake or withdraw
+    pub amount: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// An ephemeral keypair allowed to act for a wallet in game, so moves
+/// don't need the main wallet to sign each one
+#[account]
+#[derive(InitSpace)]
+pub struct SessionKey {
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+    /// The key stops working after this timestamp
+    pub expires_at: i64,
+    /// `SESSION_*` bits the key may use
+    pub permissions: u8,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Hot operator key an agent plays with, mapped to the cold wallet its
+/// winnings are paid to
+#[account]
+#[derive(InitSpace)]
+pub struct Agent {
+    /// Key that joins games and signs gameplay
+    pub operator: Pubkey,
+    /// Wallet settlement pays the operator's winnings to
+    pub payout_wallet: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// One page of the games a player joined, oldest first, so a wallet's
+/// history is a few account reads instead of a program-wide scan
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerGameIndex {
+    pub player: Pubkey,
+    /// Page number (part of the PDA seeds)
+    pub page: u16,
+    /// Number of listed games
+    pub count: u8,
+    /// Game accounts in join order; games left before start stay listed
+    pub games: [Pubkey; GAME_INDEX_PAGE_SIZE],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PlayerGameIndex {
+    /// Append a joined game to this page
+    pub fn insert(&mut self, game: Pubkey) -> Result<()> {
+        require!(
+            (self.count as usize) < GAME_INDEX_PAGE_SIZE,
+            EscrowError::GameIndexFull
+        );
+        self.games[self.count as usize] = game;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Immutable summary a settled game is archived into once its full
+/// account is closed
+#[account]
+#[derive(InitSpace)]
+pub struct GameArchive {
+    /// Address the game account had
+    pub game: Pubkey,
+    pub game_id: [u8; 8],
+    /// Status the game ended in
+    pub status: GameStatus,
+    pub stake_mint: Pubkey,
+    pub player_count: u8,
+    pub players: [Pubkey; MAX_PLAYERS],
+    /// Base units each seat paid in
+    pub paid: [u64; MAX_PLAYERS],
+    /// Prize paid or vested to each seat
+    pub winnings: [u64; MAX_PLAYERS],
+    pub winner: Option<Pubkey>,
+    pub house_fee: u64,
+    pub seed: [u8; 32],
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    /// Settlement, cancellation or void timestamp
+    pub ended_at: Option<i64>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Books of the shared vault of one stake mint
+/// Every game staked in the mint keeps its funds in the one vault and
+/// its own share in `Game::vault_balance`; the vault must always hold at
+/// least the sum of those shares
+#[account]
+#[derive(InitSpace)]
+pub struct VaultLedger {
+    pub mint: Pubkey,
+    /// Sum of the balances of every game staked in the mint
+    pub liabilities: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl VaultLedger {
+    /// Book the vault's net movement during this instruction against
+    /// `game`, then check the vault still covers every game's balance
+    /// Call once the instruction's last transfer in or out is done
+    pub fn settle(
+        &mut self,
+        game: &mut Game,
+        vault: &mut InterfaceAccount<TokenAccount>,
+    ) -> Result<()> {
+        self.settle_balance(&mut game.vault_balance, vault)
+    }
+    
+    /// `settle` for a duel, which keeps its share in `Duel::vault_balance`
+    pub fn settle_duel(
+        &mut self,
+        duel: &mut Duel,
+        vault: &mut InterfaceAccount<TokenAccount>,
+    ) -> Result<()> {
+        self.settle_balance(&mut duel.vault_balance, vault)
+    }
+    
+    fn settle_balance(
+        &mut self,
+        balance: &mut u64,
+        vault: &mut InterfaceAccount<TokenAccount>,
+    ) -> Result<()> {
+        let before = vault.amount;
+        vault.reload()?;
+        
+        if vault.amount >= before {
+            let credit = vault.amount - before;
+            *balance = balance
+                .checked_add(credit)
+                .ok_or(EscrowError::MathOverflow)?;
+            self.liabilities = self
+                .liabilities
+                .checked_add(credit)
+                .ok_or(EscrowError::MathOverflow)?;
+        } else {
+            // A game can never pay out more than it holds, so one game's
+            // funds stay out of reach of every other game
+            let debit = before - vault.amount;
+            *balance = balance
+                .checked_sub(debit)
+                .ok_or(EscrowError::GameBalanceExceeded)?;
+            self.liabilities = self
+                .liabilities
+                .checked_sub(debit)
+                .ok_or(EscrowError::MathOverflow)?;
+        }
+        require!(
+            vault.amount >= self.liabilities,
+            EscrowError::VaultUndercollateralized
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum GameStatus {
+    Waiting,
+    Live,
+    Finished,
+    Cancelled,
+    Voided,
+}
+
+// === Events ===
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub params: ConfigParams,
+}
+
+#[event]
+pub struct GameCreated {
+    pub game_id: [u8; 8],
+    pub label: Option<[u8; 8]>,
+    pub creator: Pubkey,
+    pub stake_amount: u64,
+    pub min_players: u8,
+    pub max_players: u8,
+    pub fee_bps: u16,
+    pub payout_schedule: Vec<u16>,
+    pub join_deadline: i64,
+    pub scheduled_start: Option<i64>,
+}
+
+#[event]
+pub struct PlayerJoined {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub player_count: u8,
+}
+
+#[event]
+pub struct GameStarted {
+    pub game_id: [u8; 8],
+    pub seed: [u8; 32],
+    pub started_at: i64,
+}
+
+#[event]
+pub struct GameEnded {
+    pub game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub winner_payout: u64,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct PayoutDiverted {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PendingClaimOpened {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PendingPayoutClaimed {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WinningsClaimed {
+    pub game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GameEndedRanked {
+    pub game_id: [u8; 8],
+    pub placements: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct GameDrawn {
+    pub game_id: [u8; 8],
+    pub tied: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct PlayerLeft {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub refunded: u64,
+    pub player_count: u8,
+}
+
+#[event]
+pub struct PlayerKicked {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct PlayerReplaced {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub substitute: Pubkey,
+    pub seat: u8,
+}
+
+#[event]
+pub struct TeamsAssigned {
+    pub game_id: [u8; 8],
+    pub teams: Vec<u8>,
+}
+
+#[event]
+pub struct GameEndedSurvival {
+    pub game_id: [u8; 8],
+    pub players: Vec<Pubkey>,
+    pub survival_secs: Vec<u64>,
+    pub payouts: Vec<u64>,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct GameEndedTeam {
+    pub game_id: [u8; 8],
+    pub winning_team: u8,
+    pub members: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct StrikeIssued {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub reason_code: u8,
+    pub strikes: u8,
+    pub action_nonce: u64,
+}
+
+#[event]
+pub struct PlayerAutoKicked {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub strikes: u8,
+    pub refunded: u64,
+    pub action_nonce: u64,
+}
+
+#[event]
+pub struct ParlayCreated {
+    pub owner: Pubkey,
+    pub total_amount: u64,
+    pub max_stake: u64,
+    pub games_remaining: u8,
+}
+
+#[event]
+pub struct ParlayEntered {
+    pub owner: Pubkey,
+    pub game_id: [u8; 8],
+    pub stake_amount: u64,
+    pub games_remaining: u8,
+}
+
+#[event]
+pub struct ParlayClosed {
+    pub owner: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct DuelCreated {
+    pub duel_id: [u8; 8],
+    pub creator: Pubkey,
+    pub stake_amount: u64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct DuelStarted {
+    pub duel_id: [u8; 8],
+    pub opponent: Pubkey,
+    pub seed: [u8; 32],
+    pub started_at: i64,
+}
+
+#[event]
+pub struct DuelEnded {
+    pub duel_id: [u8; 8],
+    pub winner: Pubkey,
+    pub winner_payout: u64,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct DuelCancelled {
+    pub duel_id: [u8; 8],
+}
+
+#[event]
+pub struct DuelTimedOut {
+    pub duel_id: [u8; 8],
+    pub started_at: i64,
+}
+
+#[event]
+pub struct GameCancelled {
+    pub game_id: [u8; 8],
+}
+
+#[event]
+pub struct GameExpired {
+    pub game_id: [u8; 8],
+    pub join_deadline: i64,
+}
+
+#[event]
+pub struct GameTimedOut {
+    pub game_id: [u8; 8],
+    pub started_at: i64,
+}
+
+#[event]
+pub struct GameVoided {
+    pub game_id: [u8; 8],
+}
+
+#[event]
+pub struct GameFlagged {
+    pub game_id: [u8; 8],
+    pub status: GameStatus,
+    pub flags: u8,
+}
+
+#[event]
+pub struct PlayerRefunded {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeriesCreated {
+    pub series_id: [u8; 8],
+    pub creator: Pubkey,
+    pub buy_in: u64,
+    pub max_players: u8,
+    pub wins_required: u8,
+}
+
+#[event]
+pub struct SeriesJoined {
+    pub series_id: [u8; 8],
+    pub player: Pubkey,
+    pub player_count: u8,
+}
+
+#[event]
+pub struct SeriesGameStarted {
+    pub series_id: [u8; 8],
+    pub game_number: u8,
+    pub seed: [u8; 32],
+}
+
+#[event]
+pub struct SeriesGameRecorded {
+    pub series_id: [u8; 8],
+    pub game_number: u8,
+    pub winner: Pubkey,
+    pub wins: u8,
+    pub action_nonce: u64,
+}
+
+#[event]
+pub struct SeriesEnded {
+    pub series_id: [u8; 8],
+    pub winner: Pubkey,
+    pub games_played: u8,
+    pub winner_payout: u64,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct SeriesCancelled {
+    pub series_id: [u8; 8],
+}
+
+#[event]
+pub struct SeriesTimedOut {
+    pub series_id: [u8; 8],
+    pub games_played: u8,
+}
+
+#[event]
+pub struct SeriesPlayerRefunded {
+    pub series_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TournamentCreated {
+    pub tournament_id: [u8; 8],
+    pub creator: Pubkey,
+    pub entry_fee: u64,
+    pub max_entrants: u8,
+}
+
+#[event]
+pub struct TournamentJoined {
+    pub tournament_id: [u8; 8],
+    pub player: Pubkey,
+    pub entrant_count: u8,
+}
+
+#[event]
+pub struct TournamentStarted {
+    pub tournament_id: [u8; 8],
+    pub entrants: u8,
+}
+
+#[event]
+pub struct TournamentAdvanced {
+    pub tournament_id: [u8; 8],
+    pub round: u8,
+    pub winner: Pubkey,
+    pub eliminated: Pubkey,
+}
+
+#[event]
+pub struct TournamentEnded {
+    pub tournament_id: [u8; 8],
+    pub champion: Pubkey,
+    pub prize: u64,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct TournamentCancelled {
+    pub tournament_id: [u8; 8],
+}
+
+#[event]
+pub struct TournamentTimedOut {
+    pub tournament_id: [u8; 8],
+    pub round: u8,
+}
+
+#[event]
+pub struct TournamentEntrantRefunded {
+    pub tournament_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeasonCreated {
+    pub season_id: [u8; 8],
+    pub rake_share_bps: u16,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub payout_schedule: Vec<u16>,
+}
+
+#[event]
+pub struct SeasonFinalized {
+    pub season_id: [u8; 8],
+    pub prize_pool: u64,
+    pub winners: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+}
+
+#[event]
+pub struct QueueCreated {
+    pub stake_amount: u64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct QueueJoined {
+    pub stake_amount: u64,
+    pub player: Pubkey,
+    pub entrant_count: u8,
+}
+
+#[event]
+pub struct QueueLeft {
+    pub stake_amount: u64,
+    pub player: Pubkey,
+    pub entrant_count: u8,
+}
+
+#[event]
+pub struct QueueFormed {
+    pub stake_amount: u64,
+    pub game_id: [u8; 8],
+    pub players: Vec<Pubkey>,
+}
+
+#[event]
+pub struct GameTemplateCreated {
+    pub template_id: [u8; 8],
+    pub creator: Pubkey,
+    pub stake_amount: u64,
+    pub first_start: i64,
+    pub cadence_secs: i64,
+}
+
+#[event]
+pub struct GameTemplateClosed {
+    pub template_id: [u8; 8],
+}
+
+#[event]
+pub struct AllowlistUpdated {
+    pub game_id: [u8; 8],
+    pub entries: Vec<Pubkey>,
+}
+
+#[event]
+pub struct PrizeFunded {
+    pub game_id: [u8; 8],
+    pub amount: u64,
+    pub funded_prize: u64,
+}
+
+#[event]
+pub struct PrizeReclaimed {
+    pub game_id: [u8; 8],
+    pub amount: u64,
+}
+
+#[event]
+pub struct PotSponsored {
+    pub game_id: [u8; 8],
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub sponsored_pot: u64,
+}
+
+#[event]
+pub struct ContributionReclaimed {
+    pub game_id: [u8; 8],
+    pub sponsor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RematchCreated {
+    pub game_id: [u8; 8],
+    pub previous_game_id: [u8; 8],
+    pub players: Vec<Pubkey>,
+}
+
+#[event]
+pub struct EntropyRevealed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct GameSeeded {
+    pub game_id: [u8; 8],
+    pub seed: [u8; 32],
+}
+
+#[event]
+pub struct ResultVerified {
+    pub game_id: [u8; 8],
+    pub claimed_winner: Option<Pubkey>,
+    pub replayed_winner: Pubkey,
+    pub valid: bool,
 }
 
-#[derive(Accounts)]
-pub struct JoinGame<'info> {
-    #[account(mut)]
-    pub game: Account<'info, Game>,
-    
-    #[account(
-        mut,
-        seeds = [b"vault", game.game_id.as_ref()],
-        bump,
-    )]
-    pub vault: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub player_token_account: Account<'info, TokenAccount>,
-    
-    pub player: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct EngineRoundResolved {
+    pub game_id: [u8; 8],
+    pub round: u16,
+    /// Bitmask of seats eliminated this round
+    pub eliminated: u8,
+    /// Winning seat, if the round decided the game
+    pub winner: Option<u8>,
 }
 
-#[derive(Accounts)]
-pub struct StartGame<'info> {
-    #[account(mut)]
-    pub game: Account<'info, Game>,
-    
-    pub authority: Signer<'info>,
+#[event]
+pub struct MoveSubmitted {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub action: MoveAction,
+    pub x: u8,
+    pub y: u8,
+    pub slot: u64,
 }
 
-#[derive(Accounts)]
-pub struct EndGame<'info> {
-    #[account(mut)]
-    pub game: Account<'info, Game>,
-    
-    #[account(
-        mut,
-        seeds = [b"vault", game.game_id.as_ref()],
-        bump,
-    )]
-    pub vault: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub winner_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub house_token_account: Account<'info, TokenAccount>,
-    
-    pub authority: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct MovesBatchSubmitted {
+    pub game_id: [u8; 8],
+    pub count: u8,
+    pub slot: u64,
+    pub action_nonce: u64,
+}
+
+#[event]
+pub struct ResultProposed {
+    pub game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub dispute_deadline: i64,
+}
+
+#[event]
+pub struct BondDeposited {
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SettlementProposed {
+    pub game_id: [u8; 8],
+    pub settlement: ProposedSettlement,
+    pub dispute_deadline: i64,
+}
+
+#[event]
+pub struct ResultChallenged {
+    pub game_id: [u8; 8],
+    pub challenger: Pubkey,
+    pub proposed_winner: Option<Pubkey>,
+    pub replayed_winner: Pubkey,
+    pub reward: u64,
+}
+
+#[event]
+pub struct SettlementVerifierUpdated {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct TreasuryCreated {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RakeBurned {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RakeWaived {
+    /// Id of the settled game, duel, series or tournament
+    pub id: [u8; 8],
+    pub fee_waived: u64,
+}
+
+#[event]
+pub struct CreatorFeePaid {
+    pub game_id: [u8; 8],
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralRegistered {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralPoolCreated {
+    pub mint: Pubkey,
+    pub referral_pool: Pubkey,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralEarningsClaimed {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RakebackAccrued {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub rake: u64,
+    pub rakeback: u64,
+}
+
+#[event]
+pub struct RakebackClaimed {
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JackpotCreated {
+    pub mint: Pubkey,
+    pub jackpot: Pubkey,
+}
+
+#[event]
+pub struct JackpotWon {
+    pub game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerEliminated {
+    pub game_id: [u8; 8],
+    pub eliminated: Pubkey,
+    pub eliminator: Option<Pubkey>,
+    pub bounty: u64,
+    pub action_nonce: u64,
+}
+
+#[event]
+pub struct BountyClaimed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct InsurancePoolCreated {
+    pub mint: Pubkey,
+    pub insurance_pool: Pubkey,
+}
+
+#[event]
+pub struct InsurancePurchased {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub premium: u64,
+}
+
+#[event]
+pub struct InsuranceClaimed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub refund: u64,
+}
+
+#[event]
+pub struct DealProposed {
+    pub game_id: [u8; 8],
+    pub proposer: Pubkey,
+    pub splits: Vec<u16>,
+}
+
+#[event]
+pub struct DealAccepted {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct GameEndedDeal {
+    pub game_id: [u8; 8],
+    pub players: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub house_fee: u64,
+}
+
+#[event]
+pub struct SidePotJoined {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub side_stake: u64,
+    pub side_pot: u64,
+}
+
+#[event]
+pub struct SidePotClaimed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerRebought {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+    pub rebuys: u8,
+}
+
+#[event]
+pub struct PowerupPurchased {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub kind: u8,
+    pub price: u64,
+    pub powerup_pot: u64,
+}
+
+#[event]
+pub struct PlayerTipped {
+    pub game_id: [u8; 8],
+    pub tipper: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TipsClaimed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TipRefunded {
+    pub game_id: [u8; 8],
+    pub tipper: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BettingPoolCreated {
+    pub game_id: [u8; 8],
+    pub betting_pool: Pubkey,
+    pub vig_bps: u16,
+}
+
+#[event]
+pub struct BetPlaced {
+    pub game_id: [u8; 8],
+    pub bettor: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub backed: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct BettingPoolSettled {
+    pub game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub total: u64,
+    pub vig: u64,
+    pub payout_pool: u64,
+}
+
+#[event]
+pub struct BettingPoolVoided {
+    pub game_id: [u8; 8],
+    pub total: u64,
+}
+
+#[event]
+pub struct BetClaimed {
+    pub betting_pool: Pubkey,
+    pub bettor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PayoutVested {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+    pub period: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct NftGameCreated {
+    pub nft_game_id: [u8; 8],
+    pub creator: Pubkey,
+    pub collection: Pubkey,
+    pub nft: Pubkey,
+    pub max_players: u8,
+}
+
+#[event]
+pub struct NftGameJoined {
+    pub nft_game_id: [u8; 8],
+    pub player: Pubkey,
+    pub nft: Pubkey,
+    pub player_count: u8,
+}
+
+#[event]
+pub struct NftGameStarted {
+    pub nft_game_id: [u8; 8],
+    pub seed: [u8; 32],
+    pub started_at: i64,
+}
+
+#[event]
+pub struct NftGameEnded {
+    pub nft_game_id: [u8; 8],
+    pub winner: Pubkey,
+    pub nfts: Vec<Pubkey>,
+}
+
+#[event]
+pub struct NftGameCancelled {
+    pub nft_game_id: [u8; 8],
+}
+
+#[event]
+pub struct NftGameTimedOut {
+    pub nft_game_id: [u8; 8],
+    pub started_at: i64,
 }
 
-#[derive(Accounts)]
-pub struct CancelGame<'info> {
-    #[account(mut)]
-    pub game: Account<'info, Game>,
-    
-    pub authority: Signer<'info>,
+#[event]
+pub struct NftReleased {
+    pub nft_game_id: [u8; 8],
+    pub nft: Pubkey,
+    pub recipient: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct RefundPlayer<'info> {
-    pub game: Account<'info, Game>,
-    
-    #[account(
-        mut,
-        seeds = [b"vault", game.game_id.as_ref()],
-        bump,
-    )]
-    pub vault: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub player_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct BalanceVaultCreated {
+    pub mint: Pubkey,
+    pub balance_vault: Pubkey,
 }
 
-// === State ===
+#[event]
+pub struct BalanceDeposited {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
 
-#[account]
-#[derive(InitSpace)]
-pub struct Game {
-    /// Unique game identifier
+#[event]
+pub struct BalanceWithdrawn {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct StakeSponsored {
     pub game_id: [u8; 8],
-    /// Game creator
-    pub creator: Pubkey,
-    /// Stake amount per player (in USDC lamports)
-    pub stake_amount: u64,
-    /// Number of players joined
-    pub player_count: u8,
-    /// Player pubkeys (max 5)
-    pub players: [Pubkey; 5],
-    /// Game status
-    pub status: GameStatus,
-    /// Seed for board generation (revealed after game starts)
-    pub seed: [u8; 32],
-    /// Winner pubkey (set after game ends)
-    pub winner: Option<Pubkey>,
-    /// Creation timestamp
-    pub created_at: i64,
-    /// Start timestamp
-    pub started_at: Option<i64>,
-    /// PDA bump
-    pub bump: u8,
+    pub player: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum GameStatus {
-    Waiting,
-    Live,
-    Finished,
-    Cancelled,
+#[event]
+pub struct StakePulled {
+    pub game_id: [u8; 8],
+    pub player: Pubkey,
+    pub amount: u64,
 }
 
-// === Events ===
+#[event]
+pub struct SessionCreated {
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    pub permissions: u8,
+}
 
 #[event]
-pub struct GameCreated {
+pub struct SessionRevoked {
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+}
+
+#[event]
+pub struct PlayerForfeited {
     pub game_id: [u8; 8],
-    pub creator: Pubkey,
-    pub stake_amount: u64,
+    pub player: Pubkey,
+    pub round: u16,
 }
 
 #[event]
-pub struct PlayerJoined {
+pub struct GameStatsRecorded {
     pub game_id: [u8; 8],
     pub player: Pubkey,
-    pub player_count: u8,
+    pub mint: Pubkey,
+    pub won: bool,
+    pub staked: u64,
+    pub winnings: u64,
 }
 
 #[event]
-pub struct GameStarted {
+pub struct RatingUpdated {
     pub game_id: [u8; 8],
-    pub seed: [u8; 32],
+    pub player: Pubkey,
+    pub rating: u32,
+    pub change: i32,
+}
+
+#[event]
+pub struct StatsSeasonStarted {
+    pub season: u16,
     pub started_at: i64,
 }
 
 #[event]
-pub struct GameEnded {
+pub struct AchievementUnlocked {
+    pub player: Pubkey,
+    pub kind: AchievementKind,
     pub game_id: [u8; 8],
-    pub winner: Pubkey,
-    pub winner_payout: u64,
-    pub house_fee: u64,
 }
 
 #[event]
-pub struct GameCancelled {
+pub struct AgentRegistered {
+    pub operator: Pubkey,
+    pub payout_wallet: Pubkey,
+}
+
+#[event]
+pub struct AgentClosed {
+    pub operator: Pubkey,
+    pub payout_wallet: Pubkey,
+}
+
+#[event]
+pub struct WithdrawalAddressesSet {
+    pub player: Pubkey,
+    pub addresses: Vec<Pubkey>,
+}
+
+#[event]
+pub struct GameArchived {
     pub game_id: [u8; 8],
+    pub archive: Pubkey,
 }
 
 #[event]
-pub struct PlayerRefunded {
+pub struct GameClosed {
     pub game_id: [u8; 8],
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct GameMigrated {
+    pub game: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct VaultCreated {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct StatsBucketMerged {
     pub player: Pubkey,
-    pub amount: u64,
+    pub mint: Pubkey,
+    pub into: Option<Pubkey>,
+    pub staked: u64,
+    pub won: u64,
 }
 
 // === Errors ===
@@ -450,10 +15764,829 @@ pub enum EscrowError {
     InvalidWinner,
     #[msg("Unauthorized")]
     Unauthorized,
-    #[msg("Game is not cancelled")]
+    #[msg("Game is not cancelled or voided")]
     GameNotCancelled,
     #[msg("Invalid player index")]
     InvalidPlayer,
     #[msg("Wrong player for refund")]
     WrongPlayer,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Action nonce does not match")]
+    NonceMismatch,
+    #[msg("Invalid number of parlay games")]
+    InvalidParlayGames,
+    #[msg("Invalid parlay amount")]
+    InvalidParlayAmount,
+    #[msg("Parlay has no games remaining")]
+    ParlayExhausted,
+    #[msg("Game stake exceeds parlay maximum")]
+    StakeAboveParlayMax,
+    #[msg("A game entered by the parlay is still open")]
+    ParlayGameOpen,
+    #[msg("A game entered by the parlay was not provided")]
+    ParlayGameMissing,
+    #[msg("Account is not the game entered by the parlay")]
+    InvalidParlayGame,
+    #[msg("A cancelled game entered by the parlay has not refunded it yet")]
+    ParlayRefundPending,
+    #[msg("Player already refunded")]
+    AlreadyRefunded,
+    #[msg("Game is not stuck")]
+    GameNotStuck,
+    #[msg("Invalid min/max player counts")]
+    InvalidPlayerLimits,
+    #[msg("Mint is not the approved stake mint")]
+    InvalidMint,
+    #[msg("House token account does not match config")]
+    InvalidHouseAccount,
+    #[msg("Fee basis points out of range")]
+    InvalidFeeBps,
+    #[msg("Invalid placements")]
+    InvalidPlacements,
+    #[msg("Invalid payout schedule")]
+    InvalidPayoutSchedule,
+    #[msg("Game pays multiple places - settle with end_game_ranked")]
+    RankedSettlementRequired,
+    #[msg("Invalid draw")]
+    InvalidDraw,
+    #[msg("Join deadline must be in the future")]
+    InvalidJoinDeadline,
+    #[msg("Join deadline has passed")]
+    JoinDeadlinePassed,
+    #[msg("Join deadline has not passed yet")]
+    JoinDeadlineNotReached,
+    #[msg("Duration must be positive")]
+    InvalidDuration,
+    #[msg("Game has not exceeded its maximum duration")]
+    GameNotTimedOut,
+    #[msg("Player is not in this game")]
+    NotInGame,
+    #[msg("Invalid team assignment")]
+    InvalidTeams,
+    #[msg("Game is played in teams - settle with end_game_team")]
+    TeamSettlementRequired,
+    #[msg("Invalid series parameters")]
+    InvalidSeries,
+    #[msg("Series already has a winner")]
+    SeriesDecided,
+    #[msg("Series has no winner yet")]
+    SeriesNotDecided,
+    #[msg("Invalid tournament")]
+    InvalidTournament,
+    #[msg("Not a valid bracket match")]
+    InvalidBracketMatch,
+    #[msg("Game is not finished")]
+    GameNotFinished,
+    #[msg("Invalid season")]
+    InvalidSeason,
+    #[msg("Season is not active")]
+    SeasonNotActive,
+    #[msg("Season has not ended")]
+    SeasonNotEnded,
+    #[msg("Season games settle through finalize_result")]
+    SeasonSettlementRequired,
+    #[msg("Lobby registry page is full")]
+    LobbyRegistryFull,
+    #[msg("Wrong lobby registry page")]
+    InvalidLobbyRegistry,
+    #[msg("Invalid scheduled start")]
+    InvalidScheduledStart,
+    #[msg("Scheduled start not reached")]
+    ScheduledStartNotReached,
+    #[msg("Invalid game template")]
+    InvalidTemplate,
+    #[msg("Template lobby is not due")]
+    TemplateNotDue,
+    #[msg("Player is not on the allowlist")]
+    NotAllowlisted,
+    #[msg("Allowlist is full")]
+    AllowlistFull,
+    #[msg("Invalid invite code")]
+    InvalidInviteCode,
+    #[msg("Joiner does not hold an NFT from the required collection")]
+    NftGateFailed,
+    #[msg("Joiner does not hold the required token balance")]
+    BalanceGateFailed,
+    #[msg("Invalid prize amount")]
+    InvalidPrizeAmount,
+    #[msg("Invalid entropy commitment")]
+    InvalidCommitment,
+    #[msg("Not every player has revealed")]
+    EntropyNotRevealed,
+    #[msg("Invalid randomness account")]
+    InvalidRandomnessAccount,
+    #[msg("Randomness already revealed")]
+    RandomnessAlreadyRevealed,
+    #[msg("Randomness not revealed in this slot")]
+    RandomnessNotRevealed,
+    #[msg("Board seed has not been revealed yet")]
+    SeedPending,
+    #[msg("Randomness source requires commit-reveal games")]
+    CommitRevealRequired,
+    #[msg("Invalid SlotHashes sysvar account")]
+    InvalidSlotHashes,
+    #[msg("Seed reveal does not match the backend commitment")]
+    InvalidSeedReveal,
+    #[msg("Move log does not replay to a finished game")]
+    InvalidMoveLog,
+    #[msg("Not supported for on-chain engine games")]
+    EngineModeUnsupported,
+    #[msg("On-chain engine games settle through settle_engine_game")]
+    EngineSettlementRequired,
+    #[msg("Game is not played by the on-chain engine")]
+    EngineModeRequired,
+    #[msg("The engine has not decided a winner yet")]
+    EngineGameUndecided,
+    #[msg("Round deadline has passed")]
+    RoundClosed,
+    #[msg("Round is still open for moves")]
+    RoundStillOpen,
+    #[msg("Player has been eliminated")]
+    PlayerEliminated,
+    #[msg("Player already moved this round")]
+    AlreadyMoved,
+    #[msg("Cell is already revealed")]
+    CellRevealed,
+    #[msg("Move log is full")]
+    MoveLogFull,
+    #[msg("Invalid packed move batch")]
+    InvalidMoveBatch,
+    #[msg("A result has already been proposed")]
+    ResultAlreadyProposed,
+    #[msg("No result has been proposed")]
+    NoResultProposed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Move log replays to the proposed winner")]
+    ChallengeFailed,
+    #[msg("Settlement proof failed to verify")]
+    InvalidProof,
+    #[msg("Missing or invalid Ed25519 signature instruction")]
+    InvalidSignatureInstruction,
+    #[msg("Result is not signed by the attested enclave")]
+    AttestationRequired,
+    #[msg("Invalid referee quorum")]
+    InvalidRefereeQuorum,
+    #[msg("Not enough referee signatures")]
+    RefereeQuorumNotMet,
+    #[msg("Mint extension not supported for stakes")]
+    UnsupportedMintExtension,
+    #[msg("Invalid stake mint allowlist")]
+    InvalidStakeMints,
+    #[msg("Mint not allowed for stakes")]
+    MintNotAllowed,
+    #[msg("Not supported for USD-staked games")]
+    UsdStakeUnsupported,
+    #[msg("Invalid price account")]
+    InvalidPriceAccount,
+    #[msg("Price is too old")]
+    StalePrice,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Pending-claim account required for a frozen payout account")]
+    PendingClaimRequired,
+    #[msg("Unwrap account required for a SOL payout")]
+    UnwrapAccountRequired,
+    #[msg("Invalid fee split")]
+    InvalidFeeSplit,
+    #[msg("Fee recipient account does not match the fee split")]
+    InvalidFeeRecipient,
+    #[msg("Fee tiers must ascend by stake and stay within the max fee")]
+    InvalidFeeTiers,
+    #[msg("Rake-free window must end after it starts")]
+    InvalidRakeFreeWindow,
+    #[msg("Creator token account required to pay the creator fee")]
+    CreatorAccountRequired,
+    #[msg("Players cannot refer themselves")]
+    SelfReferral,
+    #[msg("Referral accounts do not match the game")]
+    InvalidReferral,
+    #[msg("Referral pool required to credit referrers")]
+    ReferralPoolRequired,
+    #[msg("Rakeback already accrued for this game")]
+    RakebackAlreadyAccrued,
+    #[msg("Jackpot pool required to divert the jackpot share")]
+    JackpotRequired,
+    #[msg("Jackpot already paid for this game")]
+    JackpotAlreadyClaimed,
+    #[msg("Winner did not clear the board without a wrong flag")]
+    JackpotNotWon,
+    #[msg("Bounties exceed the pot left after the house fee")]
+    InvalidBounty,
+    #[msg("Player already eliminated")]
+    AlreadyEliminated,
+    #[msg("Survival games must be settled with end_game_survival")]
+    SurvivalSettlementRequired,
+    #[msg("Game does not split its pool by survival time")]
+    SurvivalModeRequired,
+    #[msg("Result contradicts the on-chain elimination log")]
+    ResultContradictsLog,
+    #[msg("Insurance is not offered or its accounts are missing")]
+    InsuranceUnavailable,
+    #[msg("Player is not insured against a loss in this game")]
+    NotInsured,
+    #[msg("Insurance pool cannot cover the refund")]
+    InsurancePoolShort,
+    #[msg("Deal splits must cover the pool and pay only remaining players")]
+    InvalidDeal,
+    #[msg("No deal has been proposed")]
+    NoDeal,
+    #[msg("Not every remaining player has accepted the deal")]
+    DealNotAccepted,
+    #[msg("This game has no side pot")]
+    SidePotDisabled,
+    #[msg("Player already joined the side pot")]
+    AlreadyInSidePot,
+    #[msg("Rebuy window and rebuy cap must be set together")]
+    InvalidRebuy,
+    #[msg("Player has not been eliminated")]
+    NotEliminated,
+    #[msg("No rebuy available for this seat")]
+    RebuyUnavailable,
+    #[msg("Betting pool is not taking bets")]
+    BettingClosed,
+    #[msg("Betting pool has not been settled")]
+    BettingNotSettled,
+    #[msg("Too many NFT collections")]
+    InvalidNftCollections,
+    #[msg("Balance vault account required")]
+    BalanceVaultRequired,
+    #[msg("Insufficient in-program balance")]
+    InsufficientBalance,
+    #[msg("A sponsored stake must be paid from the sponsor's token account")]
+    SponsorTokenAccountRequired,
+    #[msg("Token account has not approved the join delegate")]
+    StakeNotDelegated,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key is not permitted to do this")]
+    SessionNotPermitted,
+    #[msg("Game result already recorded on this player's profile")]
+    StatsAlreadyRecorded,
+    #[msg("Ratings already updated for this game")]
+    RatingsAlreadyUpdated,
+    #[msg("Every seat's player profile must be passed, in seat order")]
+    ProfileAccountsRequired,
+    #[msg("Achievement has not been earned in this game")]
+    AchievementNotEarned,
+    #[msg("Too many or invalid withdrawal addresses")]
+    InvalidWithdrawalAddresses,
+    #[msg("Destination is not on the player's withdrawal allowlist")]
+    WithdrawalAddressNotAllowed,
+    #[msg("Loosening the withdrawal allowlist needs a registered address to sign")]
+    WithdrawalChangeNotApproved,
+    #[msg("Game index page is full - open the next page")]
+    GameIndexFull,
+    #[msg("Game has not been settled, cancelled or voided")]
+    GameNotSettled,
+    #[msg("Vault still holds funds owed to players")]
+    VaultNotEmpty,
+    #[msg("Account is not a game")]
+    InvalidGameAccount,
+    #[msg("Game is already on the current layout")]
+    GameAlreadyMigrated,
+    #[msg("Payout exceeds the game's balance in the shared vault")]
+    GameBalanceExceeded,
+    #[msg("Shared vault holds less than the games staked in it")]
+    VaultUndercollateralized,
+    #[msg("Not the game's own vault from before shared vaults")]
+    InvalidLegacyVault,
+    #[msg("Game must be migrated to the current layout first")]
+    GameNotMigrated,
+    #[msg("The proposed settlement is of another kind")]
+    WrongSettlement,
+    #[msg("Game is not the series' current game")]
+    InvalidSeriesGame,
+    #[msg("The series' current game has not been recorded yet")]
+    SeriesGameInProgress,
+    #[msg("Refund token account required to kick the player")]
+    RefundAccountRequired,
+    #[msg("The remaining seats are held for players who may rejoin")]
+    SeatHeld,
+    #[msg("No seat is held for this player")]
+    NoSeatHold,
+    #[msg("Rating band minimum is above its maximum")]
+    InvalidRatingBand,
+    #[msg("Player rating is outside the game's rating band")]
+    RatingOutOfBand,
+    #[msg("Player has stats in too many mints")]
+    TooManyMints,
+    #[msg("Player has no stats in this mint")]
+    NoMintStats,
+    #[msg("Mint is still accepted for staking")]
+    MintStillListed,
+    #[msg("Game ended too recently to be archived or closed")]
+    CloseDelayNotPassed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    
+    fn fails_with<T: std::fmt::Debug>(result: Result<T>, error: EscrowError) {
+        assert_eq!(result.unwrap_err(), error.into());
+    }
+    
+    /// A lobby with every seat available and `players` seated in order
+    fn game(players: &[Pubkey]) -> Game {
+        let zeroed = vec![0u8; Game::space(MAX_PLAYERS as u8)];
+        let mut game = Game::deserialize(&mut &zeroed[..]).unwrap();
+        game.init(
+            [3u8; 8],
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            MAX_PLAYERS as u8,
+            0,
+            255,
+        );
+        for player in players {
+            game.add_player(*player).unwrap();
+        }
+        game
+    }
+    
+    fn game_address(game: &Game) -> Pubkey {
+        Pubkey::find_program_address(&[b"game", game.game_id.as_ref()], &ID).0
+    }
+    
+    fn parlay(max_stake: u64, games: u8) -> Parlay {
+        Parlay {
+            owner: Pubkey::new_unique(),
+            max_stake,
+            games_remaining: games,
+            entered_count: 0,
+            entered_games: [[0u8; 8]; MAX_PARLAY_GAMES],
+            bump: 0,
+            vault_bump: 0,
+        }
+    }
+    
+    #[test]
+    fn parlay_stops_entering_once_its_games_are_used() {
+        let mut parlay = parlay(10, 2);
+        
+        parlay.enter([1u8; 8], 10, 100).unwrap();
+        parlay.enter([2u8; 8], 10, 90).unwrap();
+        fails_with(parlay.enter([3u8; 8], 10, 80), EscrowError::ParlayExhausted);
+        assert_eq!(parlay.entered_count, 2);
+        assert_eq!(&parlay.entered_games[..2], &[[1u8; 8], [2u8; 8]]);
+    }
+    
+    #[test]
+    fn parlay_rejects_a_stake_above_its_maximum() {
+        let mut parlay = parlay(10, 2);
+        
+        fails_with(parlay.enter([1u8; 8], 11, 100), EscrowError::StakeAboveParlayMax);
+        assert_eq!(parlay.games_remaining, 2);
+    }
+    
+    #[test]
+    fn parlay_cannot_commit_more_than_its_balance() {
+        let mut parlay = parlay(60, 3);
+        
+        parlay.enter([1u8; 8], 60, 100).unwrap();
+        fails_with(parlay.enter([2u8; 8], 60, 40), EscrowError::InsufficientBalance);
+        assert_eq!(parlay.entered_count, 1);
+        assert_eq!(parlay.games_remaining, 2);
+    }
+    
+    #[test]
+    fn parlay_game_must_be_the_game_pda() {
+        let parlay = parlay(10, 1);
+        let vault = Pubkey::new_unique();
+        let mut game = game(&[parlay.owner]);
+        game.stake_sponsors[0] = vault;
+        game.status = GameStatus::Finished;
+        
+        fails_with(
+            parlay.check_settled(&Pubkey::new_unique(), &game, &vault),
+            EscrowError::InvalidParlayGame,
+        );
+        parlay.check_settled(&game_address(&game), &game, &vault).unwrap();
+    }
+    
+    #[test]
+    fn parlay_cannot_close_over_an_open_game() {
+        let parlay = parlay(10, 1);
+        let vault = Pubkey::new_unique();
+        let mut game = game(&[Pubkey::new_unique(), parlay.owner]);
+        game.stake_sponsors[1] = vault;
+        
+        for status in [GameStatus::Waiting, GameStatus::Live] {
+            game.status = status;
+            fails_with(
+                parlay.check_settled(&game_address(&game), &game, &vault),
+                EscrowError::ParlayGameOpen,
+            );
+        }
+    }
+    
+    #[test]
+    fn parlay_waits_for_the_refund_of_a_cancelled_game() {
+        let parlay = parlay(10, 1);
+        let vault = Pubkey::new_unique();
+        let mut game = game(&[Pubkey::new_unique(), parlay.owner]);
+        game.stake_sponsors[1] = vault;
+        game.status = GameStatus::Cancelled;
+        
+        fails_with(
+            parlay.check_settled(&game_address(&game), &game, &vault),
+            EscrowError::ParlayRefundPending,
+        );
+        game.refunded |= 1 << 1;
+        parlay.check_settled(&game_address(&game), &game, &vault).unwrap();
+    }
+    
+    #[test]
+    fn parlay_is_done_with_a_game_it_no_longer_sponsors() {
+        let parlay = parlay(10, 1);
+        let vault = Pubkey::new_unique();
+        // The owner left and rejoined from their own wallet
+        let mut game = game(&[parlay.owner]);
+        game.status = GameStatus::Live;
+        
+        parlay.check_settled(&game_address(&game), &game, &vault).unwrap();
+    }
+    
+    #[test]
+    fn lobby_waiting_too_long_is_stuck() {
+        let game = game(&[Pubkey::new_unique()]);
+        
+        assert_eq!(game.stuck_flags_at(STUCK_WAITING_SECS).unwrap(), 0);
+        assert_eq!(
+            game.stuck_flags_at(STUCK_WAITING_SECS + 1).unwrap(),
+            STUCK_WAITING_TOO_LONG
+        );
+    }
+    
+    #[test]
+    fn game_live_too_long_is_stuck() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.status = GameStatus::Live;
+        game.started_at = Some(100);
+        
+        assert_eq!(game.stuck_flags_at(100 + STUCK_LIVE_SECS).unwrap(), 0);
+        assert_eq!(
+            game.stuck_flags_at(100 + STUCK_LIVE_SECS + 1).unwrap(),
+            STUCK_LIVE_TOO_LONG
+        );
+    }
+    
+    #[test]
+    fn cancelled_game_not_fully_refunded_is_stuck() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.status = GameStatus::Cancelled;
+        game.cancelled_at = Some(100);
+        game.refunded = 0b01;
+        let late = 100 + STUCK_REFUND_SECS + 1;
+        
+        assert_eq!(game.stuck_flags_at(100 + STUCK_REFUND_SECS).unwrap(), 0);
+        assert_eq!(game.stuck_flags_at(late).unwrap(), STUCK_UNREFUNDED);
+        
+        game.refunded = 0b11;
+        assert_eq!(game.stuck_flags_at(late).unwrap(), 0);
+    }
+    
+    #[test]
+    fn finished_game_holding_more_than_it_owes_is_stuck() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.status = GameStatus::Finished;
+        game.unclaimed = 50;
+        game.tips[1] = 5;
+        game.vault_balance = 55;
+        
+        assert_eq!(game.stuck_flags_at(0).unwrap(), 0);
+        game.vault_balance = 56;
+        assert_eq!(game.stuck_flags_at(0).unwrap(), STUCK_VAULT_NOT_EMPTY);
+    }
+    
+    #[test]
+    fn recovered_game_is_no_longer_stuck() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        let late = STUCK_WAITING_SECS + 1;
+        assert_eq!(game.stuck_flags_at(late).unwrap(), STUCK_WAITING_TOO_LONG);
+        
+        game.status = GameStatus::Live;
+        game.started_at = Some(late);
+        assert_eq!(game.stuck_flags_at(late).unwrap(), 0);
+    }
+    
+    #[test]
+    fn game_closes_only_after_the_close_delay() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.status = GameStatus::Finished;
+        game.finished_at = Some(100);
+        
+        fails_with(
+            game.check_closable(100 + GAME_CLOSE_DELAY_SECS - 1),
+            EscrowError::CloseDelayNotPassed,
+        );
+        game.check_closable(100 + GAME_CLOSE_DELAY_SECS).unwrap();
+    }
+    
+    #[test]
+    fn game_holding_funds_cannot_close() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.status = GameStatus::Cancelled;
+        game.cancelled_at = Some(0);
+        game.vault_balance = 1;
+        
+        fails_with(game.check_closable(GAME_CLOSE_DELAY_SECS), EscrowError::VaultNotEmpty);
+        game.status = GameStatus::Live;
+        fails_with(game.check_closable(GAME_CLOSE_DELAY_SECS), EscrowError::GameNotSettled);
+    }
+    
+    #[test]
+    fn strikes_count_per_seat_up_to_the_threshold() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        
+        for expected in 1..STRIKE_THRESHOLD {
+            assert_eq!(game.add_strike(1), expected);
+        }
+        assert_eq!(game.strikes[0], 0);
+        assert_eq!(game.add_strike(1), STRIKE_THRESHOLD);
+    }
+    
+    #[test]
+    fn strikes_saturate() {
+        let mut game = game(&[Pubkey::new_unique()]);
+        game.strikes[0] = u8::MAX;
+        
+        assert_eq!(game.add_strike(0), u8::MAX);
+    }
+    
+    #[test]
+    fn kicked_player_takes_their_strikes_with_them() {
+        let players = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut game = game(&players);
+        game.add_strike(0);
+        game.add_strike(2);
+        game.add_strike(2);
+        
+        game.remove_player(0);
+        assert_eq!(&game.players[..2], &players[1..]);
+        assert_eq!(&game.strikes[..3], &[0, 2, 0]);
+    }
+    
+    #[test]
+    fn replayed_nonce_is_rejected() {
+        let mut game = game(&[Pubkey::new_unique()]);
+        
+        assert_eq!(game.consume_nonce(0).unwrap(), 0);
+        fails_with(game.consume_nonce(0), EscrowError::NonceMismatch);
+        assert_eq!(game.action_nonce, 1);
+    }
+    
+    #[test]
+    fn nonce_must_not_run_ahead() {
+        let mut game = game(&[Pubkey::new_unique()]);
+        
+        fails_with(game.consume_nonce(1), EscrowError::NonceMismatch);
+        assert_eq!(game.consume_nonce(0).unwrap(), 0);
+        assert_eq!(game.consume_nonce(1).unwrap(), 1);
+    }
+    
+    #[test]
+    fn lobby_fills_around_a_held_seat() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut game = game(&[a, b]);
+        game.max_players = 3;
+        game.remove_player(0);
+        game.hold_seat(a, 100, 160);
+        
+        // One seat is free for anyone, the other is held for `a`
+        game.seat_player(c, 110).unwrap();
+        fails_with(game.seat_player(Pubkey::new_unique(), 120), EscrowError::SeatHeld);
+        assert_eq!(game.seat_player(a, 130).unwrap(), 2);
+        assert_eq!(game.player_count, 3);
+        assert_eq!(game.held_seats(130), 0);
+    }
+    
+    #[test]
+    fn seat_hold_expires_after_the_grace_window() {
+        let a = Pubkey::new_unique();
+        let mut game = game(&[a]);
+        game.max_players = 1;
+        game.remove_player(0);
+        game.hold_seat(a, 100, 160);
+        
+        fails_with(game.seat_player(Pubkey::new_unique(), 159), EscrowError::SeatHeld);
+        assert_eq!(game.seat_hold_of(&a, 160), None);
+        game.seat_player(Pubkey::new_unique(), 160).unwrap();
+        fails_with(game.seat_player(a, 161), EscrowError::GameFull);
+    }
+    
+    #[test]
+    fn seat_hold_is_used_once() {
+        let a = Pubkey::new_unique();
+        let mut game = game(&[a]);
+        game.remove_player(0);
+        game.hold_seat(a, 100, 160);
+        
+        assert_eq!(game.seat_hold_of(&a, 110), Some(0));
+        game.seat_player(a, 110).unwrap();
+        assert_eq!(game.seat_hold_of(&a, 120), None);
+        fails_with(game.seat_player(a, 120), EscrowError::AlreadyJoined);
+    }
+    
+    #[test]
+    fn seat_holds_keep_the_latest_departures() {
+        let players: Vec<Pubkey> = (0..MAX_SEAT_HOLDS + 1).map(|_| Pubkey::new_unique()).collect();
+        let mut game = game(&[]);
+        for (i, player) in players.iter().enumerate() {
+            game.hold_seat(*player, 100, 200 + i as i64);
+        }
+        
+        // The hold expiring soonest made way for the latest departure
+        assert_eq!(game.seat_hold_of(&players[0], 100), None);
+        assert!(players[1..].iter().all(|player| game.seat_hold_of(player, 100).is_some()));
+        assert_eq!(game.held_seats(100), MAX_SEAT_HOLDS as u8);
+        
+        // Leaving again only refreshes the player's own hold
+        game.hold_seat(players[1], 150, 300);
+        assert_eq!(game.held_seats(150), MAX_SEAT_HOLDS as u8);
+        assert_eq!(game.held_seats(250), 1);
+    }
+    
+    #[test]
+    fn no_seat_is_held_without_a_grace_window() {
+        let a = Pubkey::new_unique();
+        let mut game = game(&[a]);
+        game.remove_player(0);
+        game.hold_seat(a, 100, 100);
+        
+        assert_eq!(game.held_seats(100), 0);
+    }
+    
+    #[test]
+    fn rating_band_admits_its_bounds() {
+        let band = RatingBand {
+            min_rating: 1_400,
+            max_rating: 1_600,
+        };
+        
+        assert!(band.admits(1_400) && band.admits(1_500) && band.admits(1_600));
+        assert!(!band.admits(1_399) && !band.admits(1_601));
+        // Players without a rated game join at the starting rating
+        assert!(!band.admits(rating::INITIAL_RATING));
+    }
+    
+    fn profile() -> PlayerProfile {
+        let zeroed = vec![0u8; PlayerProfile::INIT_SPACE];
+        PlayerProfile::deserialize(&mut &zeroed[..]).unwrap()
+    }
+    
+    #[test]
+    fn mint_stats_are_kept_per_mint() {
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut profile = profile();
+        
+        profile.record_in_mint(usdc, 100, 0).unwrap();
+        profile.record_in_mint(sol, 5, 9).unwrap();
+        profile.record_in_mint(usdc, 100, 250).unwrap();
+        
+        assert_eq!(profile.mint_stat_count, 2);
+        assert_eq!(profile.net_winnings_in(&usdc), 50);
+        assert_eq!(profile.net_winnings_in(&sol), 4);
+        assert_eq!(profile.net_winnings_in(&Pubkey::new_unique()), 0);
+    }
+    
+    #[test]
+    fn mint_stats_reject_a_mint_past_the_last_bucket() {
+        let mut profile = profile();
+        for _ in 0..MAX_STAT_MINTS {
+            profile.record_in_mint(Pubkey::new_unique(), 1, 1).unwrap();
+        }
+        
+        fails_with(
+            profile.record_in_mint(Pubkey::new_unique(), 1, 1),
+            EscrowError::TooManyMints,
+        );
+        // Mints that already have a bucket still record
+        let mint = profile.mint_stats[0].mint;
+        profile.record_in_mint(mint, 1, 1).unwrap();
+        assert_eq!(profile.mint_stats[0].staked, 2);
+    }
+    
+    #[test]
+    fn merging_a_mint_bucket_frees_its_slot() {
+        let mints: Vec<Pubkey> = (0..MAX_STAT_MINTS).map(|_| Pubkey::new_unique()).collect();
+        let mut profile = profile();
+        for (i, mint) in mints.iter().enumerate() {
+            profile.record_in_mint(*mint, 10 * i as u64, i as u64).unwrap();
+        }
+        
+        let merged = profile.merge_mint_stats(&mints[1], Some(mints[3])).unwrap();
+        assert_eq!((merged.staked, merged.won), (10, 1));
+        assert_eq!(profile.mint_stat_count, 3);
+        assert_eq!(profile.mint_stats()[2].mint, mints[3]);
+        assert_eq!((profile.mint_stats()[2].staked, profile.mint_stats()[2].won), (40, 4));
+        
+        profile.merge_mint_stats(&mints[0], None).unwrap();
+        assert_eq!(profile.mint_stat_count, 2);
+        profile.record_in_mint(Pubkey::new_unique(), 1, 1).unwrap();
+        profile.record_in_mint(Pubkey::new_unique(), 1, 1).unwrap();
+        fails_with(
+            profile.merge_mint_stats(&mints[0], None).map(|_| ()),
+            EscrowError::NoMintStats,
+        );
+    }
+    
+    #[test]
+    fn template_advance_rejects_a_spawn_count_overflow() {
+        let zeroed = [0u8; GameTemplate::INIT_SPACE];
+        let mut template = GameTemplate::deserialize(&mut &zeroed[..]).unwrap();
+        template.cadence_secs = 60;
+        
+        template.advance().unwrap();
+        assert_eq!((template.games_spawned, template.next_start), (1, 60));
+        template.games_spawned = u64::MAX;
+        fails_with(template.advance(), EscrowError::MathOverflow);
+    }
+    
+    /// Account data of a version 0 or 1 game with two seated players
+    fn legacy_game_data(version: u8, players: [Pubkey; 2]) -> Vec<u8> {
+        let zeroed = [0u8; legacy::GameV1::INIT_SPACE];
+        let mut legacy = legacy::GameV1::deserialize(&mut &zeroed[..]).unwrap();
+        legacy.game_id = [5u8; 8];
+        legacy.max_players = 2;
+        legacy.player_count = 2;
+        legacy.players[..2].copy_from_slice(&players);
+        legacy.paid[..2].copy_from_slice(&[10, 40]);
+        legacy.status = GameStatus::Finished;
+        
+        let mut fields = legacy.try_to_vec().unwrap();
+        fields.resize(legacy::GameV1::INIT_SPACE, 0);
+        let mut data = Game::DISCRIMINATOR.to_vec();
+        if version == 1 {
+            data.push(1);
+        }
+        data.extend(fields);
+        data
+    }
+    
+    #[test]
+    fn version_0_game_is_the_legacy_layout_without_a_version_byte() {
+        let players = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let data = legacy_game_data(0, players);
+        assert_eq!(data.len(), 8 + legacy::GameV1::INIT_SPACE);
+        
+        let (version, game) = legacy::decode_game(&data).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(game.version, GAME_VERSION);
+        assert_eq!(game.game_id, [5u8; 8]);
+        assert_eq!(game.players, players);
+        assert_eq!(game.paid, [10, 40]);
+        assert_eq!(game.strikes.len(), 2);
+        assert!(game.status == GameStatus::Finished);
+    }
+    
+    #[test]
+    fn version_1_game_has_its_seat_arrays_cut_to_the_seat_count() {
+        let players = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let data = legacy_game_data(1, players);
+        assert_eq!(data.len(), 8 + legacy::GameV1::INIT_SPACE + 1);
+        
+        let (version, game) = legacy::decode_game(&data).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(game.version, GAME_VERSION);
+        assert_eq!(game.players, players);
+        assert_eq!(game.paid, [10, 40]);
+        assert_eq!(game.payout_wallets, [Pubkey::default(); 2]);
+        assert_eq!(game.vault_balance, 0);
+        assert!(game.proposal.is_none());
+        assert!(game.rating_band.is_none());
+    }
+    
+    #[test]
+    fn version_2_game_gains_the_version_3_fields() {
+        let mut game = game(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        game.paid[1] = 40;
+        game.version = 2;
+        
+        let mut fields = game.try_to_vec().unwrap();
+        fields.truncate(fields.len() - (10 + MAX_SEAT_HOLDS * SeatHold::INIT_SPACE));
+        let mut data = Game::DISCRIMINATOR.to_vec();
+        data.extend(fields);
+        
+        let (version, migrated) = legacy::decode_game(&data).unwrap();
+        assert_eq!(version, 2);
+        game.version = GAME_VERSION;
+        assert_eq!(migrated.try_to_vec().unwrap(), game.try_to_vec().unwrap());
+    }
+    
+    #[test]
+    fn current_game_is_not_migrated_again() {
+        let game = game(&[Pubkey::new_unique()]);
+        let mut data = Game::DISCRIMINATOR.to_vec();
+        data.extend(game.try_to_vec().unwrap());
+        
+        fails_with(legacy::decode_game(&data).map(|_| ()), EscrowError::GameAlreadyMigrated);
+        data[0] ^= 1;
+        fails_with(legacy::decode_game(&data).map(|_| ()), EscrowError::InvalidGameAccount);
+    }
 }
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::EscrowError;
+
+/// Pyth receiver program that owns price update accounts
+pub mod pyth_receiver {
+    use anchor_lang::declare_id;
+    declare_id!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+}
+
+/// Pyth feed id of SOL/USD
+pub const SOL_USD_FEED_ID: [u8; 32] = [
+    239, 13, 139, 111, 218, 44, 235, 164, 29, 161, 93, 64, 149, 209, 218, 57,
+    42, 13, 47, 142, 208, 198, 199, 188, 15, 76, 250, 200, 194, 128, 181, 109,
+];
+
+/// Account discriminator of a Pyth `PriceUpdateV2`
+const PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Byte offset of the verification level (after discriminator and write authority)
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+/// `VerificationLevel::Full` - every guardian signature was checked
+const VERIFICATION_FULL: u8 = 1;
+/// Byte length of the price message fields read here:
+/// feed id, price, conf, exponent, publish time
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8;
+
+/// Lamports per SOL, as a power of ten
+const LAMPORTS_EXPONENT: i32 = 9;
+/// Cents per dollar, as a power of ten
+const CENTS_EXPONENT: i32 = 2;
+
+/// The fields of a Pyth price update the escrow relies on
+pub struct PythPrice {
+    /// Price in units of 10^`exponent`
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    /// Read a fully verified price update for `feed_id`, checking its
+    /// owner and discriminator
+    pub fn load(account: &AccountInfo, feed_id: &[u8; 32]) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            pyth_receiver::ID,
+            EscrowError::InvalidPriceAccount
+        );
+        
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() > VERIFICATION_LEVEL_OFFSET && data[..8] == PRICE_UPDATE_DISCRIMINATOR,
+            EscrowError::InvalidPriceAccount
+        );
+        // Partial verification carries a signature count, Full nothing
+        require!(
+            data[VERIFICATION_LEVEL_OFFSET] == VERIFICATION_FULL,
+            EscrowError::InvalidPriceAccount
+        );
+        
+        let message = &data[VERIFICATION_LEVEL_OFFSET + 1..];
+        require!(
+            message.len() >= PRICE_MESSAGE_LEN && message[..32] == feed_id[..],
+            EscrowError::InvalidPriceAccount
+        );
+        
+        let read_8 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&message[offset..offset + 8]);
+            bytes
+        };
+        let mut exponent = [0u8; 4];
+        exponent.copy_from_slice(&message[48..52]);
+        
+        Ok(Self {
+            price: i64::from_le_bytes(read_8(32)),
+            exponent: i32::from_le_bytes(exponent),
+            publish_time: i64::from_le_bytes(read_8(52)),
+        })
+    }
+    
+    /// Lamports worth `cents` at this SOL/USD price, rounded up so the
+    /// vault is never short of the stake
+    /// Rejects prices published more than `max_age` seconds ago
+    pub fn lamports_for_cents(&self, cents: u64, clock: &Clock, max_age: i64) -> Result<u64> {
+        require!(
+            clock.unix_timestamp.saturating_sub(self.publish_time) <= max_age,
+            EscrowError::StalePrice
+        );
+        require!(self.price > 0, EscrowError::InvalidPriceAccount);
+        
+        // lamports = cents * 10^(9 - 2) / (price * 10^exponent)
+        let scale = LAMPORTS_EXPONENT - CENTS_EXPONENT - self.exponent;
+        let pow = 10u128
+            .checked_pow(scale.unsigned_abs())
+            .ok_or(EscrowError::MathOverflow)?;
+        let (numerator, denominator) = if scale >= 0 {
+            (cents as u128).checked_mul(pow).map(|n| (n, self.price as u128))
+        } else {
+            (self.price as u128).checked_mul(pow).map(|d| (cents as u128, d))
+        }
+        .ok_or(EscrowError::MathOverflow)?;
+        
+        let lamports = numerator
+            .checked_add(denominator - 1)
+            .ok_or(EscrowError::MathOverflow)?
+            / denominator;
+        u64::try_from(lamports).map_err(|_| EscrowError::MathOverflow.into())
+    }
+}
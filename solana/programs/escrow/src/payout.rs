@@ -0,0 +1,606 @@
+use anchor_lang::prelude::*;
+
+use crate::{Config, Duel, EscrowError, Game, Season, Series, Tournament, BPS_DENOMINATOR, MAX_PLAYERS};
+
+/// Full settlement breakdown for a game
+///
+/// Shared by `end_game` and `preview_payout` so the amounts a frontend
+/// previews are exactly the amounts settlement transfers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoutBreakdown {
+    /// Total tokens held for the game
+    pub total_pool: u64,
+    /// Amount paid to the winner
+    pub winner_payout: u64,
+    /// Amount paid to the house
+    pub house_fee: u64,
+    /// House fee not charged because settlement fell in a rake-free window
+    pub fee_waived: u64,
+    /// Amount paid per placement when settling by rank
+    pub place_payouts: [u64; MAX_PLAYERS],
+}
+
+/// Compute the settlement breakdown for a game
+/// The house fee is taken from player stakes only, at the rate of the
+/// config's fee tier for the game's stake; funded and sponsored prizes
+/// are added to the prize pool untouched, and earned bounties are left
+/// out of it
+pub fn compute_breakdown(game: &Game, config: &Config, now: i64) -> Result<PayoutBreakdown> {
+    let stakes = game.total_stakes()?;
+    let total_pool = stakes
+        .checked_add(game.funded_prize)
+        .and_then(|pool| pool.checked_add(game.sponsored_pot))
+        .and_then(|pool| pool.checked_add(game.powerup_pot))
+        .ok_or(EscrowError::MathOverflow)?;
+    let (house_fee, fee_waived) = charged_fee(stakes, config.settlement_fee_bps(game), config, now)?;
+    // Elimination bounties stay in the vault for `claim_bounty`
+    let winner_payout = total_pool - house_fee - game.total_bounties()?;
+    let place_payouts = split_by_schedule(
+        &game.payout_schedule,
+        game.player_count as usize,
+        winner_payout,
+    )?;
+    
+    Ok(PayoutBreakdown {
+        total_pool,
+        winner_payout,
+        house_fee,
+        fee_waived,
+        place_payouts,
+    })
+}
+
+/// Compute the settlement breakdown for a duel (winner takes all)
+pub fn compute_duel_breakdown(duel: &Duel, config: &Config, now: i64) -> Result<PayoutBreakdown> {
+    let total_pool = duel
+        .stake_amount
+        .checked_mul(2)
+        .ok_or(EscrowError::MathOverflow)?;
+    let (house_fee, fee_waived) = charged_fee(total_pool, duel.fee_bps, config, now)?;
+    let winner_payout = total_pool - house_fee;
+    let mut place_payouts = [0u64; MAX_PLAYERS];
+    place_payouts[0] = winner_payout;
+    
+    Ok(PayoutBreakdown {
+        total_pool,
+        winner_payout,
+        house_fee,
+        fee_waived,
+        place_payouts,
+    })
+}
+
+/// Compute the settlement breakdown for a series (winner takes all)
+pub fn compute_series_breakdown(series: &Series, config: &Config, now: i64) -> Result<PayoutBreakdown> {
+    let total_pool = series
+        .buy_in
+        .checked_mul(series.player_count as u64)
+        .ok_or(EscrowError::MathOverflow)?;
+    let (house_fee, fee_waived) = charged_fee(total_pool, series.fee_bps, config, now)?;
+    let winner_payout = total_pool - house_fee;
+    let mut place_payouts = [0u64; MAX_PLAYERS];
+    place_payouts[0] = winner_payout;
+    
+    Ok(PayoutBreakdown {
+        total_pool,
+        winner_payout,
+        house_fee,
+        fee_waived,
+        place_payouts,
+    })
+}
+
+/// Compute the settlement breakdown for a tournament (champion takes all)
+pub fn compute_tournament_breakdown(
+    tournament: &Tournament,
+    config: &Config,
+    now: i64,
+) -> Result<PayoutBreakdown> {
+    let total_pool = tournament
+        .entry_fee
+        .checked_mul(tournament.entrant_count as u64)
+        .ok_or(EscrowError::MathOverflow)?;
+    let (house_fee, fee_waived) = charged_fee(total_pool, tournament.fee_bps, config, now)?;
+    let winner_payout = total_pool - house_fee;
+    let mut place_payouts = [0u64; MAX_PLAYERS];
+    place_payouts[0] = winner_payout;
+    
+    Ok(PayoutBreakdown {
+        total_pool,
+        winner_payout,
+        house_fee,
+        fee_waived,
+        place_payouts,
+    })
+}
+
+/// House fee on `total_pool` at `fee_bps`, or nothing if `now` falls in
+/// the config's rake-free window
+/// Returns the fee charged and the fee waived
+fn charged_fee(total_pool: u64, fee_bps: u16, config: &Config, now: i64) -> Result<(u64, u64)> {
+    let fee = house_fee(total_pool, fee_bps)?;
+    Ok(if config.is_rake_free(now) { (0, fee) } else { (fee, 0) })
+}
+
+/// House fee on `total_pool` at `fee_bps`
+fn house_fee(total_pool: u64, fee_bps: u16) -> Result<u64> {
+    Ok(total_pool
+        .checked_mul(fee_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Compute per-standing prizes when a season is finalized
+pub fn compute_season_payouts(season: &Season, prize_pool: u64) -> Result<[u64; MAX_PLAYERS]> {
+    if season.paid_places() == 0 {
+        return Ok([0u64; MAX_PLAYERS]);
+    }
+    split_by_schedule(
+        &season.payout_schedule,
+        season.entry_count as usize,
+        prize_pool,
+    )
+}
+
+/// Share of a season game's `house_fee` paid into the season pool
+pub fn season_cut(house_fee: u64, rake_share_bps: u16) -> Result<u64> {
+    Ok(house_fee
+        .checked_mul(rake_share_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Creator's share of a game's `house_fee`
+pub fn creator_cut(house_fee: u64, creator_fee_bps: u16) -> Result<u64> {
+    Ok(house_fee
+        .checked_mul(creator_fee_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Referrer's credit for a referred seat: `referral_bps` of the seat's
+/// `stake`-weighted part of a game's `house_fee`, out of total `stakes`
+pub fn referral_cut(house_fee: u64, stake: u64, stakes: u64, referral_bps: u16) -> Result<u64> {
+    require!(stakes > 0, EscrowError::MathOverflow);
+    u64::try_from(
+        house_fee as u128 * stake as u128 * referral_bps as u128
+            / (stakes as u128 * BPS_DENOMINATOR as u128),
+    )
+    .map_err(|_| EscrowError::MathOverflow.into())
+}
+
+/// Part of a `house_fee` burned, or paid to a fee split recipient, at `bps`
+pub fn fee_share(house_fee: u64, bps: u16) -> u64 {
+    (house_fee as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Share of the `rake` a player paid that accrues back to them
+pub fn rakeback(rake: u64, rakeback_bps: u16) -> Result<u64> {
+    Ok(rake
+        .checked_mul(rakeback_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Share of a game's `house_fee` diverted into the jackpot pool
+pub fn jackpot_cut(house_fee: u64, jackpot_bps: u16) -> Result<u64> {
+    Ok(house_fee
+        .checked_mul(jackpot_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Bounty paid per elimination out of `stakes` at `bounty_bps`
+pub fn bounty(stakes: u64, bounty_bps: u16) -> Result<u64> {
+    Ok(stakes
+        .checked_mul(bounty_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Insurance premium or refund on `stake` at `bps`
+pub fn insurance_share(stake: u64, bps: u16) -> Result<u64> {
+    Ok(stake
+        .checked_mul(bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// Treasury's vig on a betting pool's `total` at `vig_bps`
+pub fn betting_vig(total: u64, vig_bps: u16) -> Result<u64> {
+    Ok(total
+        .checked_mul(vig_bps as u64)
+        .ok_or(EscrowError::MathOverflow)?
+        / BPS_DENOMINATOR)
+}
+
+/// A winning backer's share of a betting pool: `bet` of the `backed`
+/// total on the winner, pro rata of `payout_pool`
+/// Rounds down, so dust stays in the betting vault
+pub fn parimutuel_share(bet: u64, payout_pool: u64, backed: u64) -> Result<u64> {
+    require!(backed > 0, EscrowError::MathOverflow);
+    u64::try_from(bet as u128 * payout_pool as u128 / backed as u128)
+        .map_err(|_| EscrowError::MathOverflow.into())
+}
+
+/// Part of `total` released `elapsed` seconds into a linear vesting
+/// `period`
+pub fn vested(total: u64, elapsed: i64, period: i64) -> Result<u64> {
+    if elapsed >= period {
+        return Ok(total);
+    }
+    if elapsed <= 0 {
+        return Ok(0);
+    }
+    u64::try_from(total as u128 * elapsed as u128 / period as u128)
+        .map_err(|_| EscrowError::MathOverflow.into())
+}
+
+/// Split `prize_pool` across the first `places` placements of `schedule`
+/// Rounding dust goes to first place so the vault is fully drained
+fn split_by_schedule(
+    schedule: &[u16],
+    places: usize,
+    prize_pool: u64,
+) -> Result<[u64; MAX_PLAYERS]> {
+    let mut payouts = [0u64; MAX_PLAYERS];
+    let mut distributed = 0u64;
+    
+    for (place, &bps) in schedule.iter().enumerate().take(places) {
+        let share = prize_pool
+            .checked_mul(bps as u64)
+            .ok_or(EscrowError::MathOverflow)?
+            / BPS_DENOMINATOR;
+        payouts[place] = share;
+        distributed += share;
+    }
+    
+    payouts[0] += prize_pool - distributed;
+    Ok(payouts)
+}
+
+/// Split `prize_pool` pro-rata across `weights`
+/// Rounding dust goes to the `dust_to` share; all-zero weights split evenly
+pub fn split_by_weight(
+    prize_pool: u64,
+    weights: &[u64],
+    dust_to: usize,
+) -> Result<[u64; MAX_PLAYERS]> {
+    let total_weight = weights
+        .iter()
+        .try_fold(0u128, |total, &weight| total.checked_add(weight as u128))
+        .ok_or(EscrowError::MathOverflow)?;
+    if total_weight == 0 {
+        return Ok(split_evenly(prize_pool, weights.len()));
+    }
+    
+    let mut payouts = [0u64; MAX_PLAYERS];
+    let mut distributed = 0u64;
+    for (payout, &weight) in payouts.iter_mut().zip(weights.iter()) {
+        *payout = (prize_pool as u128 * weight as u128 / total_weight) as u64;
+        distributed += *payout;
+    }
+    
+    payouts[dust_to] += prize_pool - distributed;
+    Ok(payouts)
+}
+
+/// Split `prize_pool` evenly across `ways` tied players
+/// Rounding dust goes to the first tied player, as does the whole pool
+/// when there is nobody to split it with
+pub fn split_evenly(prize_pool: u64, ways: usize) -> [u64; MAX_PLAYERS] {
+    let mut payouts = [0u64; MAX_PLAYERS];
+    let ways = ways.max(1);
+    let share = prize_pool / ways as u64;
+    
+    for payout in payouts.iter_mut().take(ways) {
+        *payout = share;
+    }
+    payouts[0] += prize_pool - share * ways as u64;
+    payouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FeeShare, FeeTier, RakeBurn, RakeFreeWindow};
+    
+    /// Every transfer out of the vault a settlement makes, in the order
+    /// `finalize_result` and the `end_game_*` instructions make them
+    #[derive(Debug, Default)]
+    struct Transfers {
+        payouts: Vec<u64>,
+        season: u64,
+        creator: u64,
+        referral: u64,
+        jackpot: u64,
+        burned: u64,
+        splits: Vec<u64>,
+        treasury: u64,
+    }
+    
+    impl Transfers {
+        fn total(&self) -> u64 {
+            self.payouts.iter().sum::<u64>()
+                + self.season
+                + self.creator
+                + self.referral
+                + self.jackpot
+                + self.burned
+                + self.splits.iter().sum::<u64>()
+                + self.treasury
+        }
+    }
+    
+    /// Walk the house fee through the settlement fee path: the season cut
+    /// when `rake_share_bps` is set, then the creator, referral and jackpot
+    /// cuts, then `distribute_house_fee` on what is left
+    fn settle(
+        game: &Game,
+        config: &Config,
+        breakdown: &PayoutBreakdown,
+        rake_share_bps: Option<u16>,
+        referred: &[usize],
+    ) -> Transfers {
+        let mut transfers = Transfers {
+            payouts: breakdown.place_payouts[..game.paid_places().max(1)].to_vec(),
+            ..Default::default()
+        };
+        if let Some(bps) = rake_share_bps {
+            transfers.season = season_cut(breakdown.house_fee, bps).unwrap();
+        }
+        let fee = breakdown.house_fee - transfers.season;
+        transfers.creator = creator_cut(fee, game.creator_fee_bps).unwrap();
+        if fee > 0 && config.referral_bps > 0 {
+            let stakes = game.total_stakes().unwrap();
+            for &index in referred {
+                transfers.referral +=
+                    referral_cut(fee, game.stake_of(index), stakes, config.referral_bps).unwrap();
+            }
+        }
+        transfers.jackpot = jackpot_cut(fee, config.jackpot_bps).unwrap();
+        let rest = fee - transfers.creator - transfers.referral - transfers.jackpot;
+        distribute(&mut transfers, rest, config, game.stake_mint);
+        transfers
+    }
+    
+    /// `distribute_house_fee`: burn, then the fee split, then the treasury
+    fn distribute(transfers: &mut Transfers, house_fee: u64, config: &Config, mint: Pubkey) {
+        let mut house_fee = house_fee;
+        if let Some(burn) = config.rake_burn.filter(|burn| burn.mint == mint) {
+            transfers.burned = fee_share(house_fee, burn.bps);
+            house_fee -= transfers.burned;
+        }
+        transfers.splits = config
+            .fee_split
+            .iter()
+            .map(|share| fee_share(house_fee, share.bps))
+            .collect();
+        transfers.treasury = house_fee - transfers.splits.iter().sum::<u64>();
+    }
+    
+    fn config() -> Config {
+        let zeroed = vec![0u8; Config::INIT_SPACE];
+        Config::deserialize(&mut &zeroed[..]).unwrap()
+    }
+    
+    fn game(players: u8, stake_amount: u64, fee_bps: u16, schedule: &[u16]) -> Game {
+        let zeroed = vec![0u8; Game::space(MAX_PLAYERS as u8)];
+        let mut game = Game::deserialize(&mut &zeroed[..]).unwrap();
+        game.init(
+            [1u8; 8],
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            MAX_PLAYERS as u8,
+            0,
+            255,
+        );
+        game.stake_amount = stake_amount;
+        game.fee_bps = fee_bps;
+        game.payout_schedule[..schedule.len()].copy_from_slice(schedule);
+        for _ in 0..players {
+            game.add_player(Pubkey::new_unique()).unwrap();
+        }
+        game
+    }
+    
+    /// A config with every house fee carve-out switched on
+    fn busy_config(mint: Pubkey) -> Config {
+        let mut config = config();
+        config.referral_bps = 2_000;
+        config.jackpot_bps = 700;
+        config.fee_split = vec![
+            FeeShare {
+                recipient: Pubkey::new_unique(),
+                bps: 2_500,
+            },
+            FeeShare {
+                recipient: Pubkey::new_unique(),
+                bps: 1_234,
+            },
+        ];
+        config.rake_burn = Some(RakeBurn { mint, bps: 1_111 });
+        config
+    }
+    
+    #[test]
+    fn preview_matches_a_plain_winner_take_all_settlement() {
+        let game = game(4, 1_000, 500, &[10_000]);
+        let config = config();
+        
+        let breakdown = compute_breakdown(&game, &config, 0).unwrap();
+        let transfers = settle(&game, &config, &breakdown, None, &[]);
+        
+        assert_eq!(breakdown.total_pool, 4_000);
+        assert_eq!(breakdown.house_fee, 200);
+        assert_eq!(transfers.payouts, vec![breakdown.winner_payout]);
+        assert_eq!(transfers.treasury, breakdown.house_fee);
+        assert_eq!(transfers.total(), breakdown.total_pool);
+    }
+    
+    #[test]
+    fn preview_matches_a_ranked_settlement_with_every_fee_cut() {
+        let mut game = game(5, 1_003, 731, &[6_000, 3_000, 1_000]);
+        game.creator_fee_bps = 1_500;
+        game.bounties[2] = 77;
+        let config = busy_config(game.stake_mint);
+        
+        let breakdown = compute_breakdown(&game, &config, 0).unwrap();
+        let transfers = settle(&game, &config, &breakdown, None, &[1, 3]);
+        
+        assert_eq!(breakdown.house_fee, 5_015 * 731 / 10_000);
+        assert_eq!(transfers.payouts, breakdown.place_payouts[..3].to_vec());
+        assert_eq!(transfers.payouts.iter().sum::<u64>(), breakdown.winner_payout);
+        assert!(transfers.creator > 0 && transfers.referral > 0 && transfers.jackpot > 0);
+        assert!(transfers.burned > 0 && transfers.splits.iter().all(|&split| split > 0));
+        // Earned bounties stay in the vault for `claim_bounty`
+        assert_eq!(transfers.total() + 77, breakdown.total_pool);
+    }
+    
+    #[test]
+    fn preview_matches_a_season_settlement() {
+        let mut game = game(3, 2_500, 1_000, &[10_000]);
+        game.creator_fee_bps = 333;
+        game.funded_prize = 400;
+        let config = busy_config(game.stake_mint);
+        
+        let breakdown = compute_breakdown(&game, &config, 0).unwrap();
+        let transfers = settle(&game, &config, &breakdown, Some(3_000), &[0]);
+        
+        // The house fee is charged on stakes only, not the funded prize
+        assert_eq!(breakdown.house_fee, 750);
+        assert_eq!(transfers.season, 225);
+        assert_eq!(transfers.creator, creator_cut(525, 333).unwrap());
+        assert_eq!(transfers.payouts, vec![breakdown.winner_payout]);
+        assert_eq!(transfers.total(), breakdown.total_pool);
+    }
+    
+    #[test]
+    fn preview_matches_a_settlement_in_a_fee_tier() {
+        let mut game = game(2, 50_000, 800, &[10_000]);
+        let mut config = busy_config(Pubkey::new_unique());
+        config.stake_mint = game.stake_mint;
+        config.fee_tiers = vec![
+            FeeTier {
+                min_stake: 0,
+                fee_bps: 800,
+            },
+            FeeTier {
+                min_stake: 10_000,
+                fee_bps: 300,
+            },
+        ];
+        game.creator_fee_bps = 1_000;
+        
+        let breakdown = compute_breakdown(&game, &config, 0).unwrap();
+        let transfers = settle(&game, &config, &breakdown, None, &[0, 1]);
+        
+        assert_eq!(breakdown.house_fee, 3_000);
+        // The rake burn is for another mint
+        assert_eq!(transfers.burned, 0);
+        assert_eq!(transfers.total(), breakdown.total_pool);
+    }
+    
+    #[test]
+    fn preview_matches_a_rake_free_settlement() {
+        let mut game = game(4, 1_000, 500, &[7_000, 3_000]);
+        game.creator_fee_bps = 2_000;
+        let mut config = busy_config(game.stake_mint);
+        config.rake_free_window = Some(RakeFreeWindow { start: 10, end: 20 });
+        
+        let breakdown = compute_breakdown(&game, &config, 15).unwrap();
+        let transfers = settle(&game, &config, &breakdown, Some(3_000), &[0]);
+        
+        assert_eq!(breakdown.house_fee, 0);
+        assert_eq!(breakdown.fee_waived, 200);
+        assert_eq!(transfers.payouts, vec![2_800, 1_200]);
+        assert_eq!(transfers.total(), breakdown.total_pool);
+    }
+    
+    #[test]
+    fn preview_matches_a_duel_settlement() {
+        let zeroed = vec![0u8; Duel::INIT_SPACE];
+        let mut duel = Duel::deserialize(&mut &zeroed[..]).unwrap();
+        duel.stake_amount = 12_345;
+        duel.fee_bps = 250;
+        duel.stake_mint = Pubkey::new_unique();
+        let config = busy_config(duel.stake_mint);
+        
+        let breakdown = compute_duel_breakdown(&duel, &config, 0).unwrap();
+        // `end_duel` sends the whole house fee through `distribute_house_fee`
+        let mut transfers = Transfers {
+            payouts: vec![breakdown.winner_payout],
+            ..Default::default()
+        };
+        distribute(&mut transfers, breakdown.house_fee, &config, duel.stake_mint);
+        
+        assert_eq!(breakdown.house_fee, 617);
+        assert_eq!(transfers.total(), breakdown.total_pool);
+    }
+    
+    #[test]
+    fn schedule_split_gives_rounding_dust_to_first_place() {
+        let payouts = split_by_schedule(&[5_000, 3_000, 2_000], 3, 1_001).unwrap();
+        
+        assert_eq!(&payouts[..4], &[501, 300, 200, 0]);
+    }
+    
+    #[test]
+    fn schedule_split_pays_only_the_places_in_the_schedule() {
+        let payouts = split_by_schedule(&[7_000, 3_000], 5, 1_000).unwrap();
+        
+        assert_eq!(&payouts[..5], &[700, 300, 0, 0, 0]);
+    }
+    
+    #[test]
+    fn schedule_split_pays_unclaimed_places_to_first_place() {
+        // More places in the schedule than players to fill them
+        let payouts = split_by_schedule(&[5_000, 3_000, 2_000], 2, 1_000).unwrap();
+        assert_eq!(&payouts[..3], &[700, 300, 0]);
+        
+        let payouts = split_by_schedule(&[6_000, 4_000], 0, 1_000).unwrap();
+        assert_eq!(payouts.iter().sum::<u64>(), 1_000);
+        assert_eq!(payouts[0], 1_000);
+    }
+    
+    #[test]
+    fn even_split_gives_rounding_dust_to_the_first_tied_player() {
+        assert_eq!(&split_evenly(1_000, 3)[..4], &[334, 333, 333, 0]);
+        assert_eq!(&split_evenly(7, 7)[..7], &[1; 7]);
+    }
+    
+    #[test]
+    fn even_split_with_nobody_keeps_the_pool_in_first_place() {
+        let payouts = split_evenly(1_000, 0);
+        
+        assert_eq!(payouts[0], 1_000);
+        assert_eq!(payouts.iter().sum::<u64>(), 1_000);
+    }
+    
+    #[test]
+    fn weighted_split_gives_rounding_dust_to_the_chosen_share() {
+        let payouts = split_by_weight(1_000, &[1, 1, 1], 2).unwrap();
+        assert_eq!(&payouts[..3], &[333, 333, 334]);
+        
+        let payouts = split_by_weight(100, &[3, 0, 1], 0).unwrap();
+        assert_eq!(&payouts[..3], &[75, 0, 25]);
+    }
+    
+    #[test]
+    fn weighted_split_without_weight_splits_evenly() {
+        assert_eq!(&split_by_weight(10, &[0, 0, 0], 1).unwrap()[..3], &[4, 3, 3]);
+        assert_eq!(split_by_weight(10, &[], 0).unwrap()[0], 10);
+    }
+    
+    #[test]
+    fn season_payouts_are_empty_without_entries() {
+        let zeroed = vec![0u8; Season::INIT_SPACE];
+        let mut season = Season::deserialize(&mut &zeroed[..]).unwrap();
+        season.payout_schedule[..2].copy_from_slice(&[6_000, 4_000]);
+        
+        assert_eq!(compute_season_payouts(&season, 1_000).unwrap(), [0u64; MAX_PLAYERS]);
+        
+        season.entry_count = 3;
+        assert_eq!(&compute_season_payouts(&season, 1_001).unwrap()[..3], &[601, 400, 0]);
+    }
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+
+use crate::EscrowError;
+
+/// Switchboard On-Demand program that owns randomness accounts
+#[cfg(not(feature = "devnet"))]
+pub mod switchboard_on_demand {
+    use anchor_lang::declare_id;
+    declare_id!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+}
+
+/// Switchboard On-Demand program that owns randomness accounts
+#[cfg(feature = "devnet")]
+pub mod switchboard_on_demand {
+    use anchor_lang::declare_id;
+    declare_id!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+}
+
+/// Account discriminator of a Switchboard `RandomnessAccountData`
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [10, 66, 229, 135, 220, 239, 217, 114];
+
+/// Byte offsets into a randomness account (after the discriminator)
+const SEED_SLOT_OFFSET: usize = 8 + 96;
+const REVEAL_SLOT_OFFSET: usize = 8 + 136;
+const VALUE_OFFSET: usize = 8 + 144;
+
+/// The fields of a Switchboard randomness account the escrow relies on
+pub struct SwitchboardRandomness {
+    /// Slot the randomness request was committed in
+    pub seed_slot: u64,
+    /// Slot the oracle revealed the value in
+    pub reveal_slot: u64,
+    /// Revealed randomness - only valid during `reveal_slot`
+    pub value: [u8; 32],
+}
+
+impl SwitchboardRandomness {
+    /// Read a randomness account, checking its owner and discriminator
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            switchboard_on_demand::ID,
+            EscrowError::InvalidRandomnessAccount
+        );
+        
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= VALUE_OFFSET + 32 && data[..8] == RANDOMNESS_DISCRIMINATOR,
+            EscrowError::InvalidRandomnessAccount
+        );
+        
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let mut value = [0u8; 32];
+        value.copy_from_slice(&data[VALUE_OFFSET..VALUE_OFFSET + 32]);
+        
+        Ok(Self {
+            seed_slot: read_u64(SEED_SLOT_OFFSET),
+            reveal_slot: read_u64(REVEAL_SLOT_OFFSET),
+            value,
+        })
+    }
+    
+    /// The revealed value, which Switchboard only vouches for in the
+    /// slot it was revealed
+    pub fn revealed_value(&self, clock: &Clock) -> Result<[u8; 32]> {
+        require!(
+            self.reveal_slot != 0 && clock.slot == self.reveal_slot,
+            EscrowError::RandomnessNotRevealed
+        );
+        Ok(self.value)
+    }
+}
+
+/// The most recent entry in the SlotHashes sysvar
+/// Read in place - the sysvar is too large to deserialize on-chain
+pub fn recent_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    require_keys_eq!(
+        slot_hashes.key(),
+        slot_hashes::ID,
+        EscrowError::InvalidSlotHashes
+    );
+    
+    // Layout: entry count (u64), then (slot u64, hash [u8; 32]) newest first
+    let data = slot_hashes.try_borrow_data()?;
+    require!(data.len() >= 8 + 8 + 32, EscrowError::InvalidSlotHashes);
+    
+    let mut recent = [0u8; 32];
+    recent.copy_from_slice(&data[16..48]);
+    Ok(recent)
+}
+
+/// ORAO VRF program that owns randomness request accounts
+pub mod orao_vrf {
+    use anchor_lang::declare_id;
+    declare_id!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+}
+
+/// PDA seed prefix of an ORAO randomness request
+const ORAO_RANDOMNESS_SEED: &[u8] = b"orao-vrf-randomness-request";
+
+/// Byte offset of the fulfilled randomness in an ORAO request account
+/// (legacy `Randomness` layout: discriminator, seed, randomness)
+const ORAO_RANDOMNESS_OFFSET: usize = 8 + 32;
+
+/// An ORAO VRF randomness request made for a specific seed
+pub struct OraoRandomness {
+    /// Fulfilled randomness, or `None` while the request is pending
+    pub value: Option<[u8; 32]>,
+}
+
+impl OraoRandomness {
+    /// Read the ORAO request account for `seed`, checking its owner and
+    /// that it is the request PDA for that seed
+    pub fn load(account: &AccountInfo, seed: &[u8; 32]) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            orao_vrf::ID,
+            EscrowError::InvalidRandomnessAccount
+        );
+        let (expected, _) =
+            Pubkey::find_program_address(&[ORAO_RANDOMNESS_SEED, seed.as_ref()], &orao_vrf::ID);
+        require_keys_eq!(account.key(), expected, EscrowError::InvalidRandomnessAccount);
+        
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= ORAO_RANDOMNESS_OFFSET + 64,
+            EscrowError::InvalidRandomnessAccount
+        );
+        
+        let randomness = &data[ORAO_RANDOMNESS_OFFSET..ORAO_RANDOMNESS_OFFSET + 64];
+        let value = if randomness.iter().all(|&b| b == 0) {
+            None
+        } else {
+            let mut value = [0u8; 32];
+            value.copy_from_slice(&randomness[..32]);
+            Some(value)
+        };
+        
+        Ok(Self { value })
+    }
+}
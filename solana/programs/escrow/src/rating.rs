@@ -0,0 +1,60 @@
+use crate::MAX_PLAYERS;
+
+/// Rating of a player before their first rated game
+pub const INITIAL_RATING: u32 = 1200;
+/// Ratings never drop below this floor
+pub const MIN_RATING: u32 = 100;
+/// Most a player's rating moves in one game
+const K_FACTOR: i64 = 32;
+
+/// Rating difference between table steps
+const STEP: i64 = 25;
+/// Expected score in thousandths against an opponent rated `i * STEP`
+/// lower: 1000 / (1 + 10^(-d / 400)), beyond which it is clamped
+const EXPECTED: [i64; 33] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+    920, 930, 939, 947, 954, 960, 965, 969, 973, 977, 980, 983, 985, 987, 989, 990,
+];
+
+/// Expected score in thousandths of a player rated `diff` above their
+/// opponent, interpolated linearly between table steps
+fn expected(diff: i64) -> i64 {
+    if diff < 0 {
+        return 1000 - expected(-diff);
+    }
+    let step = (diff / STEP) as usize;
+    if step + 1 >= EXPECTED.len() {
+        return EXPECTED[EXPECTED.len() - 1];
+    }
+    let within = diff % STEP;
+    EXPECTED[step] + (EXPECTED[step + 1] - EXPECTED[step]) * within / STEP
+}
+
+/// Multiplayer Elo: every seat plays every other seat once, ranked by
+/// `ranks` (lower is better, equal ranks draw), with the K-factor shared
+/// across the opponents so a game moves a rating by at most `K_FACTOR`
+/// Returns the rating change per seat
+pub fn rating_deltas(ratings: &[u32], ranks: &[u8]) -> [i32; MAX_PLAYERS] {
+    let mut deltas = [0i32; MAX_PLAYERS];
+    let opponents = ratings.len() as i64 - 1;
+    if opponents < 1 {
+        return deltas;
+    }
+    
+    for (i, delta) in deltas.iter_mut().enumerate().take(ratings.len()) {
+        let mut total = 0i64;
+        for j in (0..ratings.len()).filter(|&j| j != i) {
+            let score = match ranks[i].cmp(&ranks[j]) {
+                core::cmp::Ordering::Less => 1000,
+                core::cmp::Ordering::Equal => 500,
+                core::cmp::Ordering::Greater => 0,
+            };
+            total += score - expected(ratings[i] as i64 - ratings[j] as i64);
+        }
+        // Thousandths of a point, rounded half away from zero
+        let scaled = K_FACTOR * total;
+        let divisor = 1000 * opponents;
+        *delta = ((scaled + scaled.signum() * divisor / 2) / divisor) as i32;
+    }
+    deltas
+}
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+use crate::EscrowError;
+
+/// Size of one signature's offsets entry in an Ed25519 program instruction
+const OFFSETS_LEN: usize = 14;
+/// Offsets header: signature count and a padding byte
+const OFFSETS_START: usize = 2;
+
+/// Public keys whose signatures over `message` are checked by Ed25519
+/// program instructions in the current transaction
+///
+/// The runtime has already verified those signatures by the time this
+/// program runs; only entries that keep their key, signature and message
+/// inside their own instruction data are accepted.
+pub fn ed25519_signers(instructions: &AccountInfo, message: &[u8]) -> Result<Vec<Pubkey>> {
+    let count = {
+        let data = instructions.try_borrow_data()?;
+        require!(data.len() >= 2, EscrowError::InvalidSignatureInstruction);
+        u16::from_le_bytes([data[0], data[1]]) as usize
+    };
+    
+    let mut signers = Vec::new();
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, instructions)?;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        
+        let data = &ix.data;
+        require!(!data.is_empty(), EscrowError::InvalidSignatureInstruction);
+        let signatures = data[0] as usize;
+        for entry in 0..signatures {
+            let start = OFFSETS_START + entry * OFFSETS_LEN;
+            let offsets = data
+                .get(start..start + OFFSETS_LEN)
+                .ok_or(EscrowError::InvalidSignatureInstruction)?;
+            let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+            
+            // signature, public key and message instruction indexes must
+            // all point at this instruction
+            require!(
+                read(2) == u16::MAX && read(6) == u16::MAX && read(12) == u16::MAX,
+                EscrowError::InvalidSignatureInstruction
+            );
+            
+            let key_at = read(4) as usize;
+            let (message_at, message_len) = (read(8) as usize, read(10) as usize);
+            let key = data
+                .get(key_at..key_at + 32)
+                .ok_or(EscrowError::InvalidSignatureInstruction)?;
+            let signed = data
+                .get(message_at..message_at + message_len)
+                .ok_or(EscrowError::InvalidSignatureInstruction)?;
+            
+            if signed == message {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(key);
+                signers.push(Pubkey::new_from_array(bytes));
+            }
+        }
+    }
+    
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::sysvar::instructions::{
+        self, construct_instructions_data, BorrowedInstruction,
+    };
+    
+    use crate::TeeConfig;
+    
+    fn tee() -> TeeConfig {
+        TeeConfig {
+            measurement: [7u8; 32],
+            enclave_key: Pubkey::new_unique(),
+        }
+    }
+    
+    fn report(tee: &TeeConfig, winner: &Pubkey) -> Vec<u8> {
+        tee.result_report(&[1u8; 8], winner, &[9u8; 32])
+    }
+    
+    /// Ed25519 program data for one signature by `key` over `message`,
+    /// with all three instruction indexes set to `index`
+    fn ed25519_data(key: &Pubkey, message: &[u8], index: u16) -> Vec<u8> {
+        let key_at = OFFSETS_START + OFFSETS_LEN;
+        let signature_at = key_at + 32;
+        let message_at = signature_at + 64;
+        let offsets = [
+            signature_at as u16,
+            index,
+            key_at as u16,
+            index,
+            message_at as u16,
+            message.len() as u16,
+            index,
+        ];
+        
+        let mut data = vec![1u8, 0];
+        for value in offsets {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(key.as_ref());
+        data.extend_from_slice(&[0u8; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+    
+    /// Run `ed25519_signers` over a transaction whose only instruction is
+    /// an Ed25519 program instruction with `data`
+    fn signers(data: &[u8], message: &[u8]) -> Result<Vec<Pubkey>> {
+        let ix = BorrowedInstruction {
+            program_id: &ed25519_program::ID,
+            accounts: vec![],
+            data,
+        };
+        let mut sysvar_data = construct_instructions_data(&[ix]);
+        let key = instructions::ID;
+        let owner = Pubkey::default();
+        let mut lamports = 0;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut sysvar_data,
+            &owner,
+            false,
+            0,
+        );
+        ed25519_signers(&info, message)
+    }
+    
+    #[test]
+    fn valid_report_yields_enclave_key() {
+        let tee = tee();
+        let report = report(&tee, &Pubkey::new_unique());
+        
+        let data = ed25519_data(&tee.enclave_key, &report, u16::MAX);
+        assert_eq!(signers(&data, &report).unwrap(), vec![tee.enclave_key]);
+    }
+    
+    #[test]
+    fn report_signed_by_another_key_is_not_attested() {
+        let tee = tee();
+        let report = report(&tee, &Pubkey::new_unique());
+        
+        let data = ed25519_data(&Pubkey::new_unique(), &report, u16::MAX);
+        let signers = signers(&data, &report).unwrap();
+        assert_eq!(signers.len(), 1);
+        assert!(!signers.contains(&tee.enclave_key));
+    }
+    
+    #[test]
+    fn signature_over_another_report_is_ignored() {
+        let tee = tee();
+        let signed = report(&tee, &Pubkey::new_unique());
+        let expected = report(&tee, &Pubkey::new_unique());
+        
+        let data = ed25519_data(&tee.enclave_key, &signed, u16::MAX);
+        assert!(signers(&data, &expected).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn offsets_into_another_instruction_are_rejected() {
+        let tee = tee();
+        let report = report(&tee, &Pubkey::new_unique());
+        
+        let data = ed25519_data(&tee.enclave_key, &report, 0);
+        assert!(signers(&data, &report).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+
+use crate::EscrowError;
+
+/// Public inputs of the settlement circuit: game id, seed (two halves),
+/// move root (two halves) and winning seat
+pub const SETTLEMENT_PUBLIC_INPUTS: usize = 6;
+
+/// Groth16 verifying key over BN254 for the settlement circuit
+/// Points use the big-endian encoding of the alt_bn128 syscalls
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    /// One point per public input, plus the constant term first
+    pub ic: [[u8; 64]; SETTLEMENT_PUBLIC_INPUTS + 1],
+}
+
+/// A Groth16 proof, with `a` already negated by the prover
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Groth16Proof {
+    pub a: [u8; 64],
+    pub b: [u8; 128],
+    pub c: [u8; 64],
+}
+
+/// Encode the public inputs proving "given seed S and move log M, seat W
+/// won game G"; M is the root of the game's on-chain `MoveLog`, never a
+/// root the caller supplies. 32-byte values are split into 128-bit halves
+/// so every input fits the scalar field
+pub fn settlement_inputs(
+    game_id: &[u8; 8],
+    seed: &[u8; 32],
+    move_root: &[u8; 32],
+    winner_index: u8,
+) -> [[u8; 32]; SETTLEMENT_PUBLIC_INPUTS] {
+    let mut inputs = [[0u8; 32]; SETTLEMENT_PUBLIC_INPUTS];
+    inputs[0][24..].copy_from_slice(game_id);
+    inputs[1][16..].copy_from_slice(&seed[..16]);
+    inputs[2][16..].copy_from_slice(&seed[16..]);
+    inputs[3][16..].copy_from_slice(&move_root[..16]);
+    inputs[4][16..].copy_from_slice(&move_root[16..]);
+    inputs[5][31] = winner_index;
+    inputs
+}
+
+/// Check a Groth16 proof against `vk` and the public inputs:
+/// e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+pub fn verify(
+    vk: &VerifyingKey,
+    proof: &Groth16Proof,
+    inputs: &[[u8; 32]; SETTLEMENT_PUBLIC_INPUTS],
+) -> Result<()> {
+    // vk_x = ic[0] + sum(input_i * ic[i + 1])
+    let mut vk_x = vk.ic[0];
+    for (input, ic) in inputs.iter().zip(vk.ic[1..].iter()) {
+        let mut mul_input = [0u8; 96];
+        mul_input[..64].copy_from_slice(ic);
+        mul_input[64..].copy_from_slice(input);
+        let product =
+            alt_bn128_multiplication(&mul_input).map_err(|_| EscrowError::InvalidProof)?;
+        
+        let mut add_input = [0u8; 128];
+        add_input[..64].copy_from_slice(&vk_x);
+        add_input[64..].copy_from_slice(&product);
+        let sum = alt_bn128_addition(&add_input).map_err(|_| EscrowError::InvalidProof)?;
+        vk_x.copy_from_slice(&sum);
+    }
+    
+    let pairs: [(&[u8; 64], &[u8; 128]); 4] = [
+        (&proof.a, &proof.b),
+        (&vk.alpha_g1, &vk.beta_g2),
+        (&vk_x, &vk.gamma_g2),
+        (&proof.c, &vk.delta_g2),
+    ];
+    let mut pairing_input = [0u8; 4 * 192];
+    for (chunk, (g1, g2)) in pairing_input.chunks_exact_mut(192).zip(pairs.iter()) {
+        chunk[..64].copy_from_slice(*g1);
+        chunk[64..].copy_from_slice(*g2);
+    }
+    
+    let result = alt_bn128_pairing(&pairing_input).map_err(|_| EscrowError::InvalidProof)?;
+    require!(
+        result.len() == 32 && result[..31].iter().all(|&b| b == 0) && result[31] == 1,
+        EscrowError::InvalidProof
+    );
+    Ok(())
+}